@@ -1,7 +1,104 @@
 use crate::bitboard::BitBoard;
 use crate::color::Color;
+use crate::lazy::Lazy;
 use crate::square::Square;
 
+//#################################################################################################
+//
+//                                sliders attacks backend
+//
+//#################################################################################################
+
+/// The number of entries of the attacks tables, for both bishops and rooks.
+const SLIDER_ATTACKS: usize = 107648;
+
+/// The sliders attacks tables, for the backend selected at runtime. The tables are
+/// filled in place, they are too large for the stack of small devices. Only x86_64
+/// cpus may have fast bmi2 instructions, the bmi2 table does not exist elsewhere.
+#[derive(Debug)]
+struct Sliders {
+    /// Pext/pdep lookup, filled if the cpu supports bmi2 and selected if it is fast.
+    #[cfg(target_arch = "x86_64")]
+    bmi2: bool,
+    #[cfg(target_arch = "x86_64")]
+    bmi2_filled: bool,
+    #[cfg(target_arch = "x86_64")]
+    bmi2_bishop: Bmi2Array,
+    #[cfg(target_arch = "x86_64")]
+    bmi2_rook: Bmi2Array,
+    #[cfg(target_arch = "x86_64")]
+    bmi2_attacks: [u16; SLIDER_ATTACKS],
+    /// Fancy magic bitboards lookup, available everywhere.
+    magic_bishop: MagicArray,
+    magic_rook: MagicArray,
+    magic_attacks: [BitBoard; SLIDER_ATTACKS],
+}
+
+/// The sliders attacks tables, generated on first use.
+static SLIDERS: Lazy<Sliders> = Lazy::new(
+    Sliders {
+        #[cfg(target_arch = "x86_64")]
+        bmi2: false,
+        #[cfg(target_arch = "x86_64")]
+        bmi2_filled: false,
+        #[cfg(target_arch = "x86_64")]
+        bmi2_bishop: [Bmi2Info::ZERO; 64],
+        #[cfg(target_arch = "x86_64")]
+        bmi2_rook: [Bmi2Info::ZERO; 64],
+        #[cfg(target_arch = "x86_64")]
+        bmi2_attacks: [0; SLIDER_ATTACKS],
+        magic_bishop: [MagicInfo::ZERO; 64],
+        magic_rook: [MagicInfo::ZERO; 64],
+        magic_attacks: [BitBoard::EMPTY; SLIDER_ATTACKS],
+    },
+    init_sliders,
+);
+
+/// Fills the tables of the backends supported by the cpu, and selects the fastest.
+/// The magic tables are always filled, so that both backends may be compared.
+#[cold]
+fn init_sliders(sliders: &mut Sliders) {
+    #[cfg(target_arch = "x86_64")]
+    if has_bmi2() {
+        sliders.bmi2 = !has_slow_bmi2();
+        sliders.bmi2_filled = true;
+        let offset = init_bmi2(&mut sliders.bmi2_bishop, &BISHOP_DIR, &mut sliders.bmi2_attacks, 0);
+        init_bmi2(&mut sliders.bmi2_rook, &ROOK_DIR, &mut sliders.bmi2_attacks, offset);
+    }
+
+    let offset = init_magic(&mut sliders.magic_bishop, &BISHOP_DIR, &mut sliders.magic_attacks, 0);
+    init_magic(&mut sliders.magic_rook, &ROOK_DIR, &mut sliders.magic_attacks, offset);
+}
+
+/// Returns true if the cpu has the pext/pdep instructions. Without the
+/// standard library, the cpu features are only known at compile time.
+#[cfg(target_arch = "x86_64")]
+#[cold]
+fn has_bmi2() -> bool {
+    #[cfg(feature = "std")]
+    let bmi2 = std::is_x86_feature_detected!("bmi2");
+    #[cfg(not(feature = "std"))]
+    let bmi2 = cfg!(target_feature = "bmi2");
+    bmi2
+}
+
+/// Returns true if the pext/pdep instructions are slow. AMD Zen1 and Zen2
+/// (family 0x17) do support bmi2, but implement them in slow microcode.
+#[cfg(target_arch = "x86_64")]
+#[cold]
+fn has_slow_bmi2() -> bool {
+    use core::arch::x86_64::__cpuid;
+
+    let (vendor, signature) = (__cpuid(0), __cpuid(1).eax);
+    let amd = (vendor.ebx, vendor.edx, vendor.ecx) == (0x68747541, 0x69746E65, 0x444D4163); // "AuthenticAMD"
+    let family = match signature >> 8 & 0xF {
+        0xF => 0xF + (signature >> 20 & 0xFF),
+        family => family,
+    };
+
+    amd && family == 0x17
+}
+
 //#################################################################################################
 //
 //                                sliders attacks tables
@@ -9,6 +106,7 @@ use crate::square::Square;
 //#################################################################################################
 
 /// A struct containing the informations necessary for a bmi2 lookup.
+#[cfg(target_arch = "x86_64")]
 #[derive(Debug)]
 struct Bmi2Info {
     offset: usize,
@@ -16,6 +114,7 @@ struct Bmi2Info {
     mask2: BitBoard,
 }
 
+#[cfg(target_arch = "x86_64")]
 impl Bmi2Info {
     /// A default value for that particular struct.
     const ZERO: Bmi2Info = Bmi2Info {
@@ -26,14 +125,30 @@ impl Bmi2Info {
 }
 
 /// An array of 64 bmi2 infos, one for each square.
+#[cfg(target_arch = "x86_64")]
 type Bmi2Array = [Bmi2Info; 64];
 
-/// The bmi2 infos associated with bishops and rooks, for every square on the board.
-static mut BISHOP_BMI2: Bmi2Array = [Bmi2Info::ZERO; 64];
-static mut ROOK_BMI2  : Bmi2Array = [Bmi2Info::ZERO; 64];
+/// A struct containing the informations necessary for a fancy magic lookup.
+#[derive(Debug)]
+struct MagicInfo {
+    offset: usize,
+    mask: BitBoard,
+    magic: u64,
+    shift: u32,
+}
+
+impl MagicInfo {
+    /// A default value for that particular struct.
+    const ZERO: MagicInfo = MagicInfo {
+        offset: 0,
+        mask: BitBoard::EMPTY,
+        magic: 0,
+        shift: 0,
+    };
+}
 
-/// The array that contains every attack pattern, indexed through bmi2 infos with pext and pdep.
-static mut SLIDER_ATTACKS: [u16; 107648] = [0; 107648];
+/// An array of 64 magic infos, one for each square.
+type MagicArray = [MagicInfo; 64];
 
 /// For use with the 0x88 trick.
 type Dirs = [(i32, i32); 4];
@@ -44,67 +159,163 @@ const ROOK_DIR: Dirs = [
     (-8, -16), (-1, -1), (1, 1), (8, 16),
 ];
 
-/// Generates the bmi2 infos for a certain piece, with given dirs.
-/// Uses some space in the SLIDER_ATTACKS array and return the index of the next
-/// available spot.
+/// Returns the squares whose occupancy matters for a slider on sq:
+/// its rays, without the last square of each.
 #[cold]
-unsafe fn init_bmi2(info: &mut Bmi2Array, dirs: &Dirs, mut idx: usize) -> usize {
-    let mut squares = Vec::new();
+fn slider_mask(sq: i32, dirs: &Dirs) -> BitBoard {
+    let sq88 = sq + (sq & !7);
+    let mut bb = BitBoard::EMPTY;
 
-    for sq in 0..64 {
-        info[sq as usize].offset = idx as usize;
+    for dir in dirs {
+        if (sq88 + dir.1) & 0x88 != 0 {
+            continue;
+        }
 
-        let sq88 = sq + (sq & !7);
-        let mut bb = BitBoard::EMPTY;
-        for dir in dirs {
-            if (sq88 + dir.1) & 0x88 != 0 {
-                continue;
-            }
+        let mut d = 2;
+        while (sq88 + d * dir.1) & 0x88 == 0 {
+            bb |= Square::from((sq + (d-1) * dir.0) as i8).into();
+            d += 1;
+        }
+    }
 
-            let mut d = 2;
-            while (sq88 + d * dir.1) & 0x88 == 0 {
-                bb |= Square::from((sq + (d-1) * dir.0) as i8).into();
-                d += 1;
+    bb
+}
+
+/// Computes the attacks of a slider on sq with the given occupancy, ray by ray.
+#[cold]
+fn slider_attacks(sq: i32, dirs: &Dirs, occ: BitBoard) -> BitBoard {
+    let sq88 = sq + (sq & !7);
+    let mut bb = BitBoard::EMPTY;
+
+    for dir in dirs {
+        let mut d = 1;
+        while (sq88 + d * dir.1) & 0x88 == 0 {
+            let bb2 = Square::from((sq + d * dir.0) as i8).into();
+            bb |= bb2;
+            if (occ & bb2).not_empty() {
+                break;
             }
+            d += 1;
         }
-        info[sq as usize].mask1 = bb;
+    }
 
-        squares.clear();
-        for sq in bb.iter_squares() {
-            squares.push(sq);
+    bb
+}
+
+/// Generates the bmi2 infos for a certain piece, with given dirs, writing
+/// their attack patterns to the attacks table from offset. Returns the offset
+/// following them.
+#[cfg(target_arch = "x86_64")]
+#[cold]
+fn init_bmi2(info: &mut Bmi2Array, dirs: &Dirs, attacks: &mut [u16], mut offset: usize) -> usize {
+    for sq in 0..64 {
+        let mask1 = slider_mask(sq, dirs);
+        let mask2 = slider_attacks(sq, dirs, BitBoard::EMPTY);
+        info[sq as usize] = Bmi2Info {offset, mask1, mask2};
+
+        for i in 0..(1 << mask1.count()) {
+            let bb = slider_attacks(sq, dirs, BitBoard(i).pdep(mask1));
+            attacks[offset] = (bb.pext(mask2).0 & 0xFFFF) as u16;
+            offset += 1;
         }
+    }
 
-        for i in 0..(1 << squares.len()) {
-            bb = BitBoard::EMPTY;
+    offset
+}
 
-            for (j, &square) in squares.iter().enumerate() {
-                if i & (1 << j) != 0 {
-                    bb |= square.into();
-                }
+/// The xorshift64* algorithm, returning a random number with
+/// about an eighth of its bits set, as good magics are sparse.
+#[cold]
+fn sparse_random(seed: &mut u64) -> u64 {
+    let mut random = || {
+        *seed ^= *seed >> 12;
+        *seed ^= *seed << 25;
+        *seed ^= *seed >> 27;
+        seed.wrapping_mul(0x2545F4914F6CDD1D)
+    };
+
+    random() & random() & random()
+}
+
+/// Generates the magic infos for a certain piece, with given dirs, by trial and error,
+/// writing their attack patterns to the attacks table from offset. Returns the offset
+/// following them.
+#[cold]
+fn init_magic(info: &mut MagicArray, dirs: &Dirs, attacks: &mut [BitBoard], mut offset: usize) -> usize {
+    // Seeds for each rank, known to find magics quickly.
+    const SEEDS: [u64; 8] = [728, 10316, 55013, 32803, 12281, 15100, 16645, 255];
+
+    let mut occupancies = [BitBoard::EMPTY; 4096];
+    let mut references = [BitBoard::EMPTY; 4096];
+    // The attempt at which each entry was last written, so the table is not cleared between attempts.
+    let mut epoch = [0u32; 4096];
+    let mut attempt = 0;
+
+    for sq in 0..64 {
+        let mask = slider_mask(sq, dirs);
+        let shift = 64 - u32::from(mask.count());
+
+        // Enumerates every subset of the mask with the Carry-Rippler trick.
+        let mut len = 0;
+        let mut occ = BitBoard::EMPTY;
+        loop {
+            occupancies[len] = occ;
+            references[len] = slider_attacks(sq, dirs, occ);
+            len += 1;
+            occ = BitBoard(occ.0.wrapping_sub(mask.0) & mask.0);
+            if occ.empty() {
+                break;
+            }
+        }
+
+        let table = &mut attacks[offset..offset + len];
+        let mut seed = SEEDS[sq as usize / 8];
+
+        let magic = 'search: loop {
+            let magic = sparse_random(&mut seed);
+            if (mask.0.wrapping_mul(magic) >> 56).count_ones() < 6 {
+                continue;
             }
 
-            let mut bb2 = BitBoard::EMPTY;
-            for dir in dirs {
-                let mut d = 1;
-                while (sq88 + d * dir.1) & 0x88 == 0 {
-                    let bb3 = Square::from((sq + d * dir.0) as i8).into();
-                    bb2 |= bb3;
-                    if (bb & bb3).not_empty() {
-                        break;
-                    }
-                    d += 1;
+            attempt += 1;
+            for (occ, &reference) in occupancies[..len].iter().zip(references[..len].iter()) {
+                let i = (occ.0.wrapping_mul(magic) >> shift) as usize;
+
+                if epoch[i] < attempt {
+                    epoch[i] = attempt;
+                    table[i] = reference;
+                } else if table[i] != reference {
+                    continue 'search;
                 }
             }
 
-            if i == 0 {
-                info[sq as usize].mask2 = bb2;
-            }
-            SLIDER_ATTACKS[idx] = (bb2.pext(info[sq as usize].mask2).0 & 0xFFFF) as u16;
-            idx += 1;
-        }
+            break magic;
+        };
+
+        info[sq as usize] = MagicInfo {offset, mask, magic, shift};
+        offset += len;
     }
 
-    idx
+    offset
+}
+
+/// Looks up the attacks of a slider with the pext and pdep instructions.
+/// Assumes the info indexes into the attacks table and the cpu supports bmi2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+unsafe fn bmi2_lookup(info: &Bmi2Info, attacks: &[u16], occ: BitBoard) -> BitBoard {
+    use core::arch::x86_64::{_pdep_u64, _pext_u64};
+
+    let mask = *attacks.get_unchecked(info.offset + _pext_u64(occ.0, info.mask1.0) as usize);
+    BitBoard(_pdep_u64(mask as u64, info.mask2.0))
+}
+
+/// Looks up the attacks of a slider with its magic number.
+/// Assumes the info indexes into the attacks table.
+#[inline]
+unsafe fn magic_lookup(info: &MagicInfo, attacks: &[BitBoard], occ: BitBoard) -> BitBoard {
+    let idx = (occ & info.mask).0.wrapping_mul(info.magic) >> info.shift;
+    *attacks.get_unchecked(info.offset + idx as usize)
 }
 
 //#################################################################################################
@@ -114,22 +325,28 @@ unsafe fn init_bmi2(info: &mut Bmi2Array, dirs: &Dirs, mut idx: usize) -> usize
 //#################################################################################################
 
 /// King attacks.
-static mut KING_ATTACKS: [BitBoard; 64] = [BitBoard::EMPTY; 64];
+static KING_ATTACKS: [BitBoard; 64] = jumper_attacks(&[
+    (1, 1), (1, 0), (1, -1), (0, -1),
+    (-1, -1), (-1, 0), (-1, 1), (0, 1),
+]);
 
 /// Knight attacks.
-static mut KNIGHT_ATTACKS: [BitBoard; 64] = [BitBoard::EMPTY; 64];
+static KNIGHT_ATTACKS: [BitBoard; 64] = jumper_attacks(&[
+    (1, 2), (2, 1), (2, -1), (1, -2),
+    (-1, -2), (-2, -1), (-2, 1), (-1, 2),
+]);
 
 /// Pawn attacks.
-static mut WHITE_PAWN_ATTACKS: [BitBoard; 64] = [BitBoard::EMPTY; 64];
-static mut BLACK_PAWN_ATTACKS: [BitBoard; 64] = [BitBoard::EMPTY; 64];
+static WHITE_PAWN_ATTACKS: [BitBoard; 64] = jumper_attacks(&[(1, 1), (-1, 1)]);
+static BLACK_PAWN_ATTACKS: [BitBoard; 64] = jumper_attacks(&[(1, -1), (-1, -1)]);
 
 /// Pawn pushes.
-static mut WHITE_PAWN_PUSHES: [Option<Square>; 64] = [None; 64];
-static mut BLACK_PAWN_PUSHES: [Option<Square>; 64] = [None; 64];
+static WHITE_PAWN_PUSHES: [Option<Square>; 64] = pawn_pushes(BitBoard::FULL, 1);
+static BLACK_PAWN_PUSHES: [Option<Square>; 64] = pawn_pushes(BitBoard::FULL, -1);
 
 /// Pawn double pushes.
-static mut WHITE_PAWN_DOUBLE_PUSHES: [Option<Square>; 64] = [None; 64];
-static mut BLACK_PAWN_DOUBLE_PUSHES: [Option<Square>; 64] = [None; 64];
+static WHITE_PAWN_DOUBLE_PUSHES: [Option<Square>; 64] = pawn_pushes(BitBoard::RANK_2, 2);
+static BLACK_PAWN_DOUBLE_PUSHES: [Option<Square>; 64] = pawn_pushes(BitBoard::RANK_7, -2);
 
 //#################################################################################################
 //
@@ -137,52 +354,38 @@ static mut BLACK_PAWN_DOUBLE_PUSHES: [Option<Square>; 64] = [None; 64];
 //
 //#################################################################################################
 
-/// Turns an Option<Square> into a bitboard, an empty one if the Option is None.
-#[cold]
-fn to_bitboard(sq: Option<Square>) -> BitBoard {
-    sq.map_or(BitBoard::EMPTY, |sq| sq.into())
-}
+/// Computes the attacks of a jumper with the given dirs, for every square, at compile time.
+const fn jumper_attacks(dirs: &[(i8, i8)]) -> [BitBoard; 64] {
+    let mut attacks = [BitBoard::EMPTY; 64];
 
-/// Initializes the sliders attacks and their bmi infos, then initialize the 
-/// jumpers attacks.
-#[cold]
-pub(crate) unsafe fn init() {
-    // Slider attacks
-    let idx = init_bmi2(&mut BISHOP_BMI2, &BISHOP_DIR, 0);
-    init_bmi2(&mut ROOK_BMI2, &ROOK_DIR, idx);
-
-    for sq in Square::SQUARES {
-        // Kings attacks
-        for dir in [
-            (1, 1), (1, 0), (1, -1), (0, -1),
-            (-1, -1), (-1, 0), (-1, 1), (0, 1),
-        ] {
-            KING_ATTACKS[usize::from(sq)] |= to_bitboard(sq.displace(dir));
-        }
-
-        // Knights attacks
-        for dir in [
-            (1, 2), (2, 1), (2, -1), (1, -2),
-            (-1, -2), (-2, -1), (-2, 1), (-1, 2),
-        ] {
-            KNIGHT_ATTACKS[usize::from(sq)] |= to_bitboard(sq.displace(dir));
+    let mut sq = 0;
+    while sq < 64 {
+        let mut i = 0;
+        while i < dirs.len() {
+            if let Some(to) = Square::SQUARES[sq].displace(dirs[i]) {
+                attacks[sq].0 |= 1 << to as u64;
+            }
+            i += 1;
         }
+        sq += 1;
+    }
 
-        // Pawns attacks
-        WHITE_PAWN_ATTACKS[usize::from(sq)] = to_bitboard(sq.displace((1, 1)))  | to_bitboard(sq.displace((-1, 1)));
-        BLACK_PAWN_ATTACKS[usize::from(sq)] = to_bitboard(sq.displace((1, -1))) | to_bitboard(sq.displace((-1, -1)));
+    attacks
+}
 
-        // Pawn pushes
-        WHITE_PAWN_PUSHES[usize::from(sq)] = sq.displace((0,  1));
-        BLACK_PAWN_PUSHES[usize::from(sq)] = sq.displace((0, -1));
+/// Computes the squares reached by pushing a pawn from the given squares by dy ranks, at compile time.
+const fn pawn_pushes(from: BitBoard, dy: i8) -> [Option<Square>; 64] {
+    let mut pushes = [None; 64];
 
-        // Pawn double pushes
-        match sq.y() {
-            1 => WHITE_PAWN_DOUBLE_PUSHES[usize::from(sq)] = sq.displace((0,  2)),
-            6 => BLACK_PAWN_DOUBLE_PUSHES[usize::from(sq)] = sq.displace((0, -2)),
-            _ => (),
+    let mut sq = 0;
+    while sq < 64 {
+        if from.0 & 1 << sq != 0 {
+            pushes[sq] = Square::SQUARES[sq].displace((0, dy));
         }
+        sq += 1;
     }
+
+    pushes
 }
 
 //#################################################################################################
@@ -193,7 +396,7 @@ pub(crate) unsafe fn init() {
 
 /// Returns the attacks BitBoard of a Pawn of Color color located on square sq with Board occupancy occ.
 #[inline]
-pub(crate) fn pawn(color: Color, sq: Square) -> BitBoard {
+pub fn pawn(color: Color, sq: Square) -> BitBoard {
     // SAFE: 0 <= usize::from(sq) < 64
     unsafe {
         match color {
@@ -206,7 +409,7 @@ pub(crate) fn pawn(color: Color, sq: Square) -> BitBoard {
 /// Returns the square, if available, of the position the pawn
 /// would occupy if it was pushed.
 #[inline]
-pub(crate) fn pawn_push(color: Color, sq: Square) -> Option<Square> {
+pub fn pawn_push(color: Color, sq: Square) -> Option<Square> {
     // SAFE: 0 <= usize::from(sq) < 64
     unsafe {
         match color {
@@ -219,7 +422,7 @@ pub(crate) fn pawn_push(color: Color, sq: Square) -> Option<Square> {
 /// Returns the square, if available, of the position the pawn
 /// would occupy if it was double pushed.
 #[inline]
-pub(crate) fn pawn_double_push(color: Color, sq: Square) -> Option<Square> {
+pub fn pawn_double_push(color: Color, sq: Square) -> Option<Square> {
     // SAFE: 0 <= usize::from(sq) < 64
     unsafe {
         match color {
@@ -231,18 +434,24 @@ pub(crate) fn pawn_double_push(color: Color, sq: Square) -> Option<Square> {
 
 /// Returns the attacks BitBoard of a Rook located on square sq, with Board occupancy occ.
 #[inline]
-pub(crate) fn rook(sq: Square, occ: BitBoard) -> BitBoard {
-    // SAFE: 0 <= usize::from(sq) < 64
+pub fn rook(sq: Square, occ: BitBoard) -> BitBoard {
+    // SAFE: 0 <= usize::from(sq) < 64, the infos index into their attacks table and
+    //       the bmi2 backend is only selected if the cpu supports it
     unsafe {
-        let info = ROOK_BMI2.get_unchecked(usize::from(sq));
-        let mask = SLIDER_ATTACKS[info.offset + occ.pext(info.mask1).0 as usize]; // Keeping that bounds check for now.
-        BitBoard(mask as u64).pdep(info.mask2)
+        let sliders = &*SLIDERS;
+
+        #[cfg(target_arch = "x86_64")]
+        if sliders.bmi2 {
+            return bmi2_lookup(sliders.bmi2_rook.get_unchecked(usize::from(sq)), &sliders.bmi2_attacks, occ);
+        }
+
+        magic_lookup(sliders.magic_rook.get_unchecked(usize::from(sq)), &sliders.magic_attacks, occ)
     }
 }
 
 /// Returns the attacks BitBoard of a Knight located on square sq.
 #[inline]
-pub(crate) fn knight(sq: Square) -> BitBoard {
+pub fn knight(sq: Square) -> BitBoard {
     // SAFE: 0 <= usize::from(sq) < 64
     unsafe {
         *KNIGHT_ATTACKS.get_unchecked(usize::from(sq))
@@ -251,26 +460,96 @@ pub(crate) fn knight(sq: Square) -> BitBoard {
 
 /// Returns the attacks BitBoard of a Bishop located on square sq, with Board occupancy occ.
 #[inline]
-pub(crate) fn bishop(sq: Square, occ: BitBoard) -> BitBoard {
-    // SAFE: 0 <= usize::from(sq) < 64
+pub fn bishop(sq: Square, occ: BitBoard) -> BitBoard {
+    // SAFE: 0 <= usize::from(sq) < 64, the infos index into their attacks table and
+    //       the bmi2 backend is only selected if the cpu supports it
     unsafe {
-        let info = BISHOP_BMI2.get_unchecked(usize::from(sq));
-        let mask = SLIDER_ATTACKS[info.offset + occ.pext(info.mask1).0 as usize]; // Keeping that bounds check for now.
-        BitBoard(mask as u64).pdep(info.mask2)
+        let sliders = &*SLIDERS;
+
+        #[cfg(target_arch = "x86_64")]
+        if sliders.bmi2 {
+            return bmi2_lookup(sliders.bmi2_bishop.get_unchecked(usize::from(sq)), &sliders.bmi2_attacks, occ);
+        }
+
+        magic_lookup(sliders.magic_bishop.get_unchecked(usize::from(sq)), &sliders.magic_attacks, occ)
     }
 }
 
 /// Returns the attacks BitBoard of a Queen located on square sq, with Board occupancy occ.
 #[inline]
-pub(crate) fn queen(sq: Square, occ: BitBoard) -> BitBoard {
+pub fn queen(sq: Square, occ: BitBoard) -> BitBoard {
     bishop(sq, occ) | rook(sq, occ)
 }
 
 /// Returns the attacks BitBoard of a King located on square sq.
 #[inline]
-pub(crate) fn king(sq: Square) -> BitBoard {
+pub fn king(sq: Square) -> BitBoard {
     // SAFE: 0 <= usize::from(sq) < 64
     unsafe {
         *KING_ATTACKS.get_unchecked(usize::from(sq))
     }
+}
+
+//#################################################################################################
+//
+//                                      backends accessers
+//
+//#################################################################################################
+
+/// Returns the attacks of a bishop with the fancy magic bitboards lookup,
+/// whatever the selected backend. Used to compare the backends in tests.
+#[doc(hidden)]
+pub fn magic_bishop(sq: Square, occ: BitBoard) -> BitBoard {
+    // SAFE: 0 <= usize::from(sq) < 64, the magic tables are always filled
+    unsafe {
+        let sliders = &*SLIDERS;
+        magic_lookup(sliders.magic_bishop.get_unchecked(usize::from(sq)), &sliders.magic_attacks, occ)
+    }
+}
+
+/// Returns the attacks of a rook with the fancy magic bitboards lookup,
+/// whatever the selected backend. Used to compare the backends in tests.
+#[doc(hidden)]
+pub fn magic_rook(sq: Square, occ: BitBoard) -> BitBoard {
+    // SAFE: 0 <= usize::from(sq) < 64, the magic tables are always filled
+    unsafe {
+        let sliders = &*SLIDERS;
+        magic_lookup(sliders.magic_rook.get_unchecked(usize::from(sq)), &sliders.magic_attacks, occ)
+    }
+}
+
+/// Returns the attacks of a bishop with the pext lookup, or None if the cpu
+/// does not support bmi2. Used to compare the backends in tests.
+#[doc(hidden)]
+pub fn pext_bishop(sq: Square, occ: BitBoard) -> Option<BitBoard> {
+    #[cfg(target_arch = "x86_64")]
+    // SAFE: 0 <= usize::from(sq) < 64, the bmi2 tables are only filled if the cpu supports it
+    unsafe {
+        let sliders = &*SLIDERS;
+        if sliders.bmi2_filled {
+            return Some(bmi2_lookup(sliders.bmi2_bishop.get_unchecked(usize::from(sq)), &sliders.bmi2_attacks, occ));
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = (sq, occ);
+    None
+}
+
+/// Returns the attacks of a rook with the pext lookup, or None if the cpu
+/// does not support bmi2. Used to compare the backends in tests.
+#[doc(hidden)]
+pub fn pext_rook(sq: Square, occ: BitBoard) -> Option<BitBoard> {
+    #[cfg(target_arch = "x86_64")]
+    // SAFE: 0 <= usize::from(sq) < 64, the bmi2 tables are only filled if the cpu supports it
+    unsafe {
+        let sliders = &*SLIDERS;
+        if sliders.bmi2_filled {
+            return Some(bmi2_lookup(sliders.bmi2_rook.get_unchecked(usize::from(sq)), &sliders.bmi2_attacks, occ));
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = (sq, occ);
+    None
 }
\ No newline at end of file
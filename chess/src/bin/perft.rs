@@ -0,0 +1,171 @@
+use std::env;
+use std::str::FromStr;
+use std::thread;
+use std::time::Instant;
+
+use chess::perft::{Perft, PerftStats};
+use chess::prelude::*;
+
+/// The usage/help of this binary.
+const USAGE: &str = r#"The goal of this binary is to help debug the move generator, notably through perftree (https://github.com/agausmann/perftree).
+It can also be used for profiling and benchmarking.
+
+Usage:
+  ./target/release/perft [flags] <depth> <fen> <moves>
+    <depth> : The depth at which the perft needs to be carried.
+    <fen>   : the fen string to be used, put it into quotes.
+    <moves> : (optional) a list of space seperated moves, in pure algebraic
+               coordinates notation, to be performed before node counting.
+               Needs to be a single arguments, use quotes.
+
+Flags:
+  --threads <n> : the number of threads the root moves are split between.
+                   Defaults to the available parallelism.
+  --hash <mb>   : the size in megabytes of the hash table. Defaults to 0 (disabled).
+  --stats       : prints the full statistics of the leaves: captures, en passants,
+                   castles, promotions, checks, discovered and double checks, checkmates.
+                   Every leaf is then played, so it cannot be used with --hash.
+  --variant <v> : the variant the fen is played with: standard, kingOfTheHill,
+                   threeCheck, antichess, atomic or crazyhouse. Defaults to standard.
+  --divide      : prints the node count of each root move before the total, in the
+                   format expected by perftree. On by default.
+  --no-divide   : only prints the total.
+
+Example:
+  ./target/release/perft 3 "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+
+For profiling with perf:
+  cargo build --bin perft --release
+  perf record --call-graph dwarf target/release/perft 3 "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+  perf report"#;
+
+fn main() {
+    // Get the arguments, executable path excluded.
+    let mut args = env::args().skip(1);
+
+    // Parse flags and positional arguments.
+    let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut hash = 0;
+    let mut stats = false;
+    let mut divide = true;
+    let mut variant = Variant::Standard;
+    let mut positionals = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threads" | "--hash" => {
+                let value = match args.next().map(|s| usize::from_str(&s)) {
+                    Some(Ok(n)) => n,
+                    _ => {
+                        println!("{} expects a positive integer.", arg);
+                        return;
+                    }
+                };
+
+                if arg == "--threads" {
+                    threads = value;
+                } else {
+                    hash = value;
+                }
+            },
+            "--variant" => {
+                variant = match args.next().map(|s| Variant::from_str(&s)) {
+                    Some(Ok(variant)) => variant,
+                    _ => {
+                        println!("--variant expects a variant name.");
+                        return;
+                    }
+                };
+            },
+            "--stats" => stats = true,
+            "--divide" => divide = true,
+            "--no-divide" => divide = false,
+            _ => positionals.push(arg),
+        }
+    }
+
+    if stats && hash > 0 {
+        println!("--stats plays every leaf, it cannot be used with --hash.");
+        return;
+    }
+
+    let mut positionals = positionals.into_iter();
+
+    // Parse depth.
+    let arg = positionals.next();
+    if arg.is_none() {
+        println!("{}", USAGE);
+        return;
+    }
+    let depth = match usize::from_str(arg.unwrap().as_str()) {
+        Ok(n) => n,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    if !(1..=12).contains(&depth) {
+        println!("<depth>, must be comprised between 1 and 12 inclusive.");
+        return;
+    }
+
+    // fen position.
+    let arg = positionals.next();
+    if arg.is_none() {
+        println!("{}", USAGE);
+        return;
+    }
+    let fen = arg.unwrap();
+    let mut board = match Board::new_variant(variant, &fen) {
+        Ok(board) => board,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+
+    // Moves to apply.
+    if let Some(arg) = positionals.next() {
+        for s in arg.split(' ') {
+            match board.parse_move(s) {
+                Ok(mv) => board.do_move(mv),
+                Err(err) => {
+                    println!("{}", err);
+                    return;
+                }
+            };
+        }
+    }
+
+    // Run the perft.
+    let start = Instant::now();
+    let perft = Perft::new(threads, hash, stats);
+    let results = perft.divide(&board, depth);
+    let elapsed = start.elapsed();
+
+    if divide {
+        for (mv, stats) in results.iter() {
+            println!("{} {}", mv, stats.nodes);
+        }
+        println!();
+    }
+
+    let total = results.into_iter().fold(PerftStats::default(), |acc, (_, stats)| acc + stats);
+
+    if stats {
+        println!("nodes             : {}", total.nodes);
+        println!("captures          : {}", total.captures);
+        println!("en passants       : {}", total.en_passants);
+        println!("castles           : {}", total.castles);
+        println!("promotions        : {}", total.promotions);
+        println!("checks            : {}", total.checks);
+        println!("discovered checks : {}", total.discovered_checks);
+        println!("double checks     : {}", total.double_checks);
+        println!("checkmates        : {}", total.checkmates);
+    } else {
+        println!("{}", total.nodes);
+    }
+
+    // Timing goes to stderr, so it does not disturb perftree.
+    eprintln!("{:.3}s, {:.0} nodes/s", elapsed.as_secs_f64(), total.nodes as f64 / elapsed.as_secs_f64());
+}
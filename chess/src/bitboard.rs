@@ -1,6 +1,9 @@
-use std::fmt;
-use std::num::NonZeroU64;
-use std::ops;
+use core::fmt;
+use core::num::NonZeroU64;
+use core::ops;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::color::Color;
 use crate::square::Square;
@@ -13,76 +16,59 @@ use crate::square::Square;
 
 /// An array whose ith element is 1 << i, precalculated as lookup
 /// is slightly faster than calculating them.
-static mut SHIFTS: [BitBoard; 64] = [BitBoard::EMPTY; 64];
+static SHIFTS: [BitBoard; 64] = {
+    let mut shifts = [BitBoard::EMPTY; 64];
+    let mut i = 0;
+    while i < 64 {
+        shifts[i] = BitBoard(1 << i);
+        i += 1;
+    }
+    shifts
+};
 
 // These arrays contain bitboards indexed by two squares, from and to. They contain respectively:
 // - the squares between from and to if they are aligned horizontally or vertically.
 // - the squares between from and to if they are aligned diagonally.
 // - the xor of the two former arrays.
 // - same as the previous array but goes past to until the end of the board.
-static mut SQUARES_BETWEEN_STRAIGHT: [[BitBoard; 64]; 64] = [[BitBoard::EMPTY; 64]; 64];
-static mut SQUARES_BETWEEN_DIAGNOAL: [[BitBoard; 64]; 64] = [[BitBoard::EMPTY; 64]; 64];
-static mut SQUARES_BETWEEN: [[BitBoard; 64]; 64] = [[BitBoard::EMPTY; 64]; 64];
-static mut SQUARES_RAY_MASK: [[BitBoard; 64]; 64] = [[BitBoard::EMPTY; 64]; 64];
-
-/// Initializes the arrays above and the shifts table.
-#[cold]
-pub(crate) unsafe fn init() {
-    for (i, shift) in SHIFTS.iter_mut().enumerate() {
-        *shift = BitBoard(1 << i);
-    }
-
-    const SIGN: fn(i8) -> i8 = |i| match i {
-        0 => 0,
-        j if j > 0 => 1,
-        _ => -1,
-    };
-
-    for sq1 in Square::SQUARES {
-        for sq2 in Square::SQUARES {
-            if sq1 == sq2 {
-                continue;
-            }
-
+static SQUARES_BETWEEN_STRAIGHT: [[BitBoard; 64]; 64] = squares_between(true, false, false);
+static SQUARES_BETWEEN_DIAGNOAL: [[BitBoard; 64]; 64] = squares_between(false, true, false);
+static SQUARES_BETWEEN: [[BitBoard; 64]; 64] = squares_between(true, true, false);
+static SQUARES_RAY_MASK: [[BitBoard; 64]; 64] = squares_between(true, true, true);
+
+/// Computes one of the arrays above at compile time, for squares aligned straight
+/// and/or diagonally. If ray is true, the squares past to are included.
+const fn squares_between(straight: bool, diagonal: bool, ray: bool) -> [[BitBoard; 64]; 64] {
+    let mut between = [[BitBoard::EMPTY; 64]; 64];
+
+    let mut i = 0;
+    while i < 64 {
+        let mut j = 0;
+        while j < 64 {
+            let (sq1, sq2) = (Square::SQUARES[i], Square::SQUARES[j]);
             let dx = sq2.x() - sq1.x();
             let dy = sq2.y() - sq1.y();
 
-            let dir = (SIGN(dx), SIGN(dy));
-
-            let between;
-            if dx == 0 || dy == 0 {
-                between = &mut SQUARES_BETWEEN_STRAIGHT
-            } else if dx.abs() == dy.abs() {
-                between = &mut SQUARES_BETWEEN_DIAGNOAL
-            } else {
-                continue;
-            }
-
-            let i = usize::from(sq1);
-            let j = usize::from(sq2);
-
-            let mut sq = sq1;
-            loop {
-                sq = sq.displace(dir).unwrap();
-                if sq == sq2 {
-                    break;
+            let aligned = (straight && (dx == 0 || dy == 0)) || (diagonal && dx.abs() == dy.abs());
+            if i != j && aligned {
+                let dir = (dx.signum(), dy.signum());
+
+                let mut sq = sq1.displace(dir);
+                while let Some(s) = sq {
+                    if !ray && s as usize == j {
+                        break;
+                    }
+                    between[i][j].0 |= 1 << s as u64;
+                    sq = s.displace(dir);
                 }
-                between[i][j] |= sq.into();
             }
 
-            SQUARES_BETWEEN[i][j] = between[i][j];
-            SQUARES_RAY_MASK[i][j] = SQUARES_BETWEEN[i][j];
-            
-            loop {
-                SQUARES_RAY_MASK[i][j] |= sq.into();
-                if let Some(s) = sq.displace(dir) {
-                    sq = s;
-                } else {
-                    break;
-                }
-            }
+            j += 1;
         }
+        i += 1;
     }
+
+    between
 }
 
 //#################################################################################################
@@ -150,6 +136,8 @@ impl BitBoard {
     }
 
     /// Returns the first square of the bitboard, with no checks.
+    /// 
+    /// # Safety
     /// Assumes the bitboard is not empty.
     #[inline]
     pub unsafe fn as_square_unchecked(self) -> Square {
@@ -236,12 +224,12 @@ impl BitBoard {
     pub(crate) fn pext(self, mask: BitBoard) -> BitBoard {
         // SAFE: arch and flags checked
         BitBoard(unsafe {
-            std::arch::x86_64::_pext_u64(self.0, mask.0)
+            core::arch::x86_64::_pext_u64(self.0, mask.0)
         })
     }
 
     /// Performs a parallel bits extract (pext) without the intrinsic (slow).
-    #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+    #[cfg(all(target_arch = "x86_64", not(target_feature = "bmi2")))]
     #[inline]
     pub(crate) fn pext(self, mut mask: BitBoard) -> BitBoard {
         let (mut i, mut res) = (0, 0);
@@ -264,12 +252,12 @@ impl BitBoard {
     pub(crate) fn pdep(self, mask: BitBoard) -> BitBoard {
         // SAFE: arch and flags checked
         BitBoard(unsafe {
-            std::arch::x86_64::_pdep_u64(self.0, mask.0)
+            core::arch::x86_64::_pdep_u64(self.0, mask.0)
         })
     }
 
     /// Performs a parallel bits deposit (pdep) without the intrinsic (slow).
-    #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+    #[cfg(all(target_arch = "x86_64", not(target_feature = "bmi2")))]
     #[inline]
     pub(crate) fn pdep(self, mut mask: BitBoard) -> BitBoard {
         let (mut i, mut res) = (0, 0);
@@ -468,4 +456,20 @@ impl ops::ShrAssign<BitBoard> for BitBoard {
     fn shr_assign(&mut self, rhs: BitBoard) {
         self.0.shr_assign(rhs.0)
     }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BitBoard {
+    /// Serializes the bitboard as it's raw 64 bits value.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for BitBoard {
+    /// Deserializes the bitboard from it's raw 64 bits value.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BitBoard, D::Error> {
+        u64::deserialize(deserializer).map(BitBoard)
+    }
 }
\ No newline at end of file
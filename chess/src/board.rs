@@ -1,21 +1,37 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
-use anyhow::{Error, Result};
+#[cfg(feature = "alloc")]
+use alloc::{format, string::{String, ToString}, vec::Vec};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::attacks;
 use crate::bitboard::BitBoard;
+use crate::builder::BoardBuilder;
 use crate::castle_rights::CastleMask;
 use crate::castle_rights::CastleRights;
 use crate::color::Color;
 use crate::cuckoo;
 use crate::en_passant::EnPassantSquare;
-use crate::movegen;
+use crate::error::{Error, Result};
+use crate::history::History;
+use crate::movegen::{self, MoveList};
 use crate::moves::Move;
 use crate::piece::Piece;
 use crate::square::Square;
+use crate::variant::Variant;
 use crate::zobrist::Zobrist;
 
+// The number of previous states kept by a board without the allocator, or with the
+// fixed-history feature: moves may not be undone further back.
+const HISTORY: usize = 256;
+// The number of pieces that may be exploded in a game of atomic.
+const EXPLODED: usize = 32;
+// The weight of each piece in the phase of the game, by piece order.
+const PHASE_WEIGHTS: [u8; 6] = [0, 2, 1, 1, 4, 0];
+
 //#################################################################################################
 //
 //                                    struct StateInfo
@@ -23,7 +39,7 @@ use crate::zobrist::Zobrist;
 //#################################################################################################
 
 /// An enum representing the status of a game.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Status {
     Playing,
     Draw,
@@ -46,7 +62,7 @@ impl Status {
 //#################################################################################################
 
 /// The state of the board at a given turn.
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Copy, Default, Debug)]
 pub(crate) struct StateInfo {
     side_to_move: Color,
     halfmove: u8,
@@ -55,6 +71,15 @@ pub(crate) struct StateInfo {
     castle_rights: CastleRights,
     ep_square: EnPassantSquare,
     zobrist: Zobrist,
+    // The zobrist key of the pawns only, for pawn structure caches.
+    pawn_zobrist: Zobrist,
+    // The number of checks given by each color, in three-check.
+    checks: [u8; 2],
+    // The number of pieces exploded by the last move, in atomic.
+    exploded: u8,
+    // The pieces in hand of each color, and the promoted pieces on the board, in crazyhouse.
+    pockets: [[u8; 5]; 2],
+    promoted: BitBoard,
 }
 
 //#################################################################################################
@@ -95,6 +120,48 @@ impl Occupancy {
     }
 }
 
+//#################################################################################################
+//
+//                                        trait MoveHook
+//
+//#################################################################################################
+
+/// A hook notified of every piece placed on or removed from the board by do_move_with()
+/// and undo_move_with(), for example to incrementally update the accumulators of a neural
+/// network. Both methods do nothing by default.
+pub trait MoveHook {
+    /// Called when a piece of that color is placed on that square.
+    #[inline]
+    fn place(&mut self, _color: Color, _piece: Piece, _sq: Square) {}
+
+    /// Called when the piece of that color is removed from that square.
+    #[inline]
+    fn remove(&mut self, _color: Color, _piece: Piece, _sq: Square) {}
+}
+
+// ================================ traits impl
+
+/// The hook of do_move() and undo_move(), which does nothing.
+impl MoveHook for () {}
+
+impl<H: MoveHook> MoveHook for Option<H> {
+    /// Notifies the hook, if any.
+    #[inline]
+    fn place(&mut self, color: Color, piece: Piece, sq: Square) {
+        if let Some(hook) = self {
+            hook.place(color, piece, sq);
+        }
+    }
+
+    /// Notifies the hook, if any.
+    #[inline]
+    fn remove(&mut self, color: Color, piece: Piece, sq: Square) {
+        if let Some(hook) = self {
+            hook.remove(color, piece, sq);
+        }
+    }
+}
+
 //#################################################################################################
 //
 //                                         struct Board
@@ -105,26 +172,46 @@ impl Occupancy {
 /// methods to manipulate it.
 #[derive(Clone, Debug)]
 pub struct Board {
+    variant: Variant,
     ply: u16,
 
     bitboards: [[BitBoard; 6]; 2],
     mailbox: [Option<(Color, Piece)>; 64],
     occ: Occupancy,
+    phase: u8,
 
     state: StateInfo,
-    prev_states: Vec<StateInfo>,
+    prev_states: History<StateInfo, HISTORY>,
+    // The pieces exploded by the previous moves, in atomic.
+    exploded: History<(Square, Color, Piece), EXPLODED>,
 }
 
 // ================================ pub impl
 
 impl Board {
+    /// The phase of the initial position, with all the pieces on the board.
+    pub const MAX_PHASE: u8 = 24;
+
     /// Tries to parse the fen string into a board.
     pub fn new(fen: &str) -> Result<Board> {
         Board::from_str(fen)
     }
 
+    /// Tries to parse the fen string into a board of the given variant.
+    /// Three-check fens may contain the remaining checks after the
+    /// en passant square, like "3+3".
+    pub fn new_variant(variant: Variant, fen: &str) -> Result<Board> {
+        Board::parse_fen(variant, fen)
+    }
+
     // ================================ Accessers
 
+    /// Returns the variant the board is played with.
+    #[inline]
+    pub fn get_variant(&self) -> Variant {
+        self.variant
+    }
+
     /// Returns the color of the side to move.
     #[inline]
     pub fn get_side_to_move(&self) -> Color {
@@ -149,6 +236,29 @@ impl Board {
         self.state.pinned
     }
 
+    /// Returns the number of checks given by that color, only counted in three-check.
+    #[inline]
+    pub fn get_checks(&self, color: Color) -> u8 {
+        self.state.checks[usize::from(color)]
+    }
+
+    /// Returns the number of pieces of that type in the pocket of that color,
+    /// only filled in crazyhouse. The king is never in a pocket.
+    #[inline]
+    pub fn get_pocket(&self, color: Color, piece: Piece) -> u8 {
+        match piece {
+            Piece::King => 0,
+            _ => self.state.pockets[usize::from(color)][usize::from(piece)],
+        }
+    }
+
+    /// Returns the bitboard of the pieces that were promoted from pawns,
+    /// only tracked in crazyhouse.
+    #[inline]
+    pub fn get_promoted(&self) -> BitBoard {
+        self.state.promoted
+    }
+
     /// Returns the halfmove counter.
     #[inline]
     pub fn get_halfmove(&self) -> u8 {
@@ -191,6 +301,20 @@ impl Board {
         self.state.zobrist
     }
 
+    /// The zobrist hash of the pawns of the current board, and of them only.
+    #[inline]
+    pub fn get_pawn_zobrist(&self) -> Zobrist {
+        self.state.pawn_zobrist
+    }
+
+    /// Returns the phase of the game, from the non-pawn material on the board: knights
+    /// and bishops count 1, rooks 2 and queens 4, for Board::MAX_PHASE in the initial
+    /// position. It may exceed it after promotions.
+    #[inline]
+    pub fn get_phase(&self) -> u8 {
+        self.phase
+    }
+
     /// Clears the history of the board, making it impossible to 
     /// undo the previous moves but freeing a bit of memory.
     #[inline]
@@ -205,6 +329,163 @@ impl Board {
         self.mailbox[usize::from(sq)].unwrap().1
     }
 
+    // ================================ Attacks
+
+    /// Returns the bitboard of the pieces of that color attacking the square.
+    /// Does not take en passant into account.
+    #[inline]
+    pub fn attackers_to(&self, sq: Square, color: Color) -> BitBoard {
+        self.attackers_to_occ(sq, color, self.occ.all)
+    }
+
+    /// Returns true if the square is attacked by any piece of that color.
+    #[inline]
+    pub fn is_attacked(&self, sq: Square, by: Color) -> bool {
+        self.attackers_to(sq, by).not_empty()
+    }
+
+    /// Returns the squares attacked by the piece on that square, empty if there is none.
+    /// Pawns only attack diagonally.
+    pub fn attacks_from(&self, sq: Square) -> BitBoard {
+        let occ = self.occ.all;
+
+        match self.get_piece(sq) {
+            Some((color, Piece::Pawn)) => attacks::pawn(color, sq),
+            Some((_, Piece::Rook)) => attacks::rook(sq, occ),
+            Some((_, Piece::Knight)) => attacks::knight(sq),
+            Some((_, Piece::Bishop)) => attacks::bishop(sq, occ),
+            Some((_, Piece::Queen)) => attacks::queen(sq, occ),
+            Some((_, Piece::King)) => attacks::king(sq),
+            None => BitBoard::EMPTY,
+        }
+    }
+
+    /// Returns the squares attacked by any piece of that color.
+    pub fn attack_map(&self, color: Color) -> BitBoard {
+        self.occ.colored(color).iter_squares().fold(BitBoard::EMPTY, |map, sq| map | self.attacks_from(sq))
+    }
+
+    /// Returns the squares the slider on that square attacks through exactly one
+    /// blocker of any color, those it attacks directly excluded. Empty for other pieces.
+    pub fn xray_attacks_from(&self, sq: Square) -> BitBoard {
+        let occ = self.occ.all;
+        let xray = |attacks: fn(Square, BitBoard) -> BitBoard| {
+            let direct = attacks(sq, occ);
+            attacks(sq, occ & !direct) & !direct
+        };
+
+        match self.get_piece(sq) {
+            Some((_, Piece::Rook)) => xray(attacks::rook),
+            Some((_, Piece::Bishop)) => xray(attacks::bishop),
+            Some((_, Piece::Queen)) => xray(attacks::rook) | xray(attacks::bishop),
+            _ => BitBoard::EMPTY,
+        }
+    }
+
+    /// Returns the pieces of that color pinned to their king, as (pinner, pinned) pairs
+    /// of squares. There are no pins without a royal king, as in antichess.
+    #[cfg(feature = "alloc")]
+    pub fn pins(&self, color: Color) -> Vec<(Square, Square)> {
+        let mut pins = Vec::new();
+        if self.variant == Variant::Antichess {
+            return pins;
+        }
+
+        let them = color.invert();
+        let queens = self.get_bitboard(them, Piece::Queen);
+
+        for king_sq in self.get_bitboard(color, Piece::King).iter_squares() {
+            let straights = (self.get_bitboard(them, Piece::Rook) | queens).iter_squares()
+                .map(|sq| (sq, BitBoard::between_straight(king_sq, sq)));
+            let diagonals = (self.get_bitboard(them, Piece::Bishop) | queens).iter_squares()
+                .map(|sq| (sq, BitBoard::between_diagonal(king_sq, sq)));
+
+            for (pinner, between) in straights.chain(diagonals) {
+                let blockers = between & self.occ.all;
+                if blockers.count() == 1 && (blockers & self.occ.colored(color)).not_empty() {
+                    // SAFE: there is exactly one blocker
+                    pins.push((pinner, unsafe {blockers.as_square_unchecked()}));
+                }
+            }
+        }
+
+        pins
+    }
+
+    /// Returns true if that legal move gives check, computed without playing it.
+    pub fn gives_check(&self, mv: Move) -> bool {
+        let us = self.get_side_to_move();
+        let their_king = self.get_bitboard(self.get_other_side(), Piece::King);
+
+        // Without a royal king, there are no checks.
+        if self.variant == Variant::Antichess || their_king.empty() {
+            return false;
+        }
+
+        // Our pieces and the occupancy once the move is done.
+        let (from, to) = mv.squares();
+        let mut ours = self.bitboards[usize::from(us)];
+        let mut occ = self.occ.all;
+
+        if mv.is_drop() {
+            ours[usize::from(mv.get_drop())] |= to.into();
+            occ |= to.into();
+        } else {
+            let piece = self.get_piece_unchecked(from);
+            let moved = if mv.is_promote() {mv.get_promote()} else {piece};
+
+            ours[usize::from(piece)] ^= from.into();
+            ours[usize::from(moved)] |= to.into();
+            occ = occ & !BitBoard::from(from) | to.into();
+
+            if mv.is_en_passant() {
+                occ ^= self.get_ep_square().unwrap().into();
+            } else if mv.is_castle() {
+                let rook = match to {
+                    Square::G1 => BitBoard::from(Square::H1) | Square::F1.into(),
+                    Square::G8 => BitBoard::from(Square::H8) | Square::F8.into(),
+                    Square::C1 => BitBoard::from(Square::A1) | Square::D1.into(),
+                    Square::C8 => BitBoard::from(Square::A8) | Square::D8.into(),
+                    _ => unreachable!(),
+                };
+                ours[usize::from(Piece::Rook)] ^= rook;
+                occ ^= rook;
+            }
+        }
+
+        // SAFE: there is exactly one royal king
+        let king_sq = unsafe {their_king.as_square_unchecked()};
+
+        // In atomic, captures explode the surroundings, and connected kings are never in check.
+        if self.variant == Variant::Atomic {
+            if mv.is_capture() || mv.is_en_passant() {
+                let pawns = self.get_bitboard(Color::White, Piece::Pawn) | self.get_bitboard(Color::Black, Piece::Pawn);
+                let blast = BitBoard::from(to) | attacks::king(to) & occ & !pawns;
+
+                if blast.contains(king_sq) {
+                    return false;
+                }
+
+                occ &= !blast;
+                for bb in ours.iter_mut() {
+                    *bb &= !blast;
+                }
+            }
+
+            let our_king = ours[usize::from(Piece::King)];
+            if our_king.empty() || (attacks::king(king_sq) & our_king).not_empty() {
+                return false;
+            }
+        }
+
+        // The king itself never gives check.
+        let queens = ours[usize::from(Piece::Queen)];
+        (attacks::pawn(us.invert(), king_sq) & ours[usize::from(Piece::Pawn)]
+        | attacks::rook(king_sq, occ) & (ours[usize::from(Piece::Rook)] | queens)
+        | attacks::knight(king_sq) & ours[usize::from(Piece::Knight)]
+        | attacks::bishop(king_sq, occ) & (ours[usize::from(Piece::Bishop)] | queens)).not_empty()
+    }
+
     // ================================ Methods
 
     // Returns the square the king of the side to move is occupying. 
@@ -214,11 +495,46 @@ impl Board {
         unsafe {king_bb.as_square_unchecked()}
     }
 
+    /// Returns the winner of the game if it was won through a rule of the variant,
+    /// which ends the game regardless of the moves available:
+    /// - King of the hill: a king reached the center.
+    /// - Three-check: a color gave its third check.
+    /// - Antichess: the side to move has no pieces left.
+    /// - Atomic: a king exploded.
+    pub fn get_variant_winner(&self) -> Option<Color> {
+        let us = self.get_side_to_move();
+        let them = self.get_other_side();
+
+        match self.variant {
+            Variant::Standard | Variant::Crazyhouse => None,
+            Variant::KingOfTheHill => {
+                const HILL: BitBoard = BitBoard(0x0000001818000000);
+
+                Color::COLORS.iter().copied().find(|&color| (self.get_bitboard(color, Piece::King) & HILL).not_empty())
+            },
+            Variant::ThreeCheck => Color::COLORS.iter().copied().find(|&color| self.get_checks(color) >= 3),
+            Variant::Antichess => if self.occ.colored(us).empty() {Some(us)} else {None},
+            Variant::Atomic => {
+                if self.get_bitboard(us, Piece::King).empty() {
+                    Some(them)
+                } else if self.get_bitboard(them, Piece::King).empty() {
+                    Some(us)
+                } else {
+                    None
+                }
+            },
+        }
+    }
+
     /// Returns the status of the current game. Must be called every turn to be accurate.
     pub fn status(&self) -> Status {
+        if let Some(winner) = self.get_variant_winner() {
+            return Status::Win(winner);
+        }
+
         let halfmoves = self.get_halfmove();
 
-        if halfmoves >= 50 {
+        if halfmoves >= 100 {
             return Status::Draw;
         } else if halfmoves >= 3 {
             let repetitions = self.prev_states.iter().rev()
@@ -231,10 +547,13 @@ impl Board {
             }
         }
 
-        let mut legals = Vec::new();
+        let mut legals = MoveList::new();
         movegen::legals(self, &mut legals);
-        if legals.len() == 0 {
-            if self.get_checkers().empty() {
+        if legals.is_empty() {
+            if self.variant == Variant::Antichess {
+                // Being stalemated wins in antichess.
+                return Status::Win(self.get_side_to_move());
+            } else if self.get_checkers().empty() {
                 return Status::Draw;
             } else {
                 return Status::Win(self.get_other_side());
@@ -249,6 +568,17 @@ impl Board {
     /// (or double pin for en passant moves), or, if it is a castling move,
     /// whether or not the squares the king traverses are safe.
     pub fn is_legal(&self, mv: Move) -> bool {
+        // A drop never uncovers the king, and blocks the check if there is one.
+        if mv.is_drop() {
+            return true;
+        }
+
+        match self.variant {
+            Variant::Antichess => return mv.is_capture() || mv.is_en_passant() || !self.can_capture(),
+            Variant::Atomic => return self.is_legal_atomic(mv),
+            _ => (),
+        }
+
         let (from, to) = mv.squares();
 
         if mv.is_castle() {
@@ -256,7 +586,8 @@ impl Board {
             // passes are safe.
             let can_castle = |sq1, sq2| {
                 let occ = self.get_occupancy().all();
-                (self.attackers_to(sq1, occ) | self.attackers_to(sq2, occ)).empty()
+                let them = self.get_other_side();
+                (self.attackers_to_occ(sq1, them, occ) | self.attackers_to_occ(sq2, them, occ)).empty()
             };
 
             return match to {
@@ -293,7 +624,7 @@ impl Board {
         } else if from == self.king_sq() {
             let new_occ = (self.get_occupancy().all() | BitBoard::from(to)) ^ BitBoard::from(from);
             // If the move is done by the king, check the square it is moving to is safe.
-            return self.attackers_to(to, new_occ).empty();
+            return self.attackers_to_occ(to, self.get_other_side(), new_occ).empty();
         }
 
         // Any move is valid if the piece is not pinned or if it is moving in the squares 
@@ -308,6 +639,22 @@ impl Board {
 
         let (from, to) = mv.squares();
 
+        if mv.is_drop() {
+            let piece = mv.get_drop();
+            let checkers = self.get_checkers();
+
+            // The piece must be in our pocket, and dropped on a free square,
+            // outside of the first and last ranks for pawns.
+            verify!(self.get_pocket(self.get_side_to_move(), piece) != 0 && self.get_piece(to).is_none());
+            verify!(piece != Piece::Pawn || (1..7).contains(&to.y()));
+
+            // If there is a checker, the drop must block it.
+            return checkers.empty() || !checkers.more_than_one() && {
+                let checker = unsafe {checkers.as_square_unchecked()};
+                BitBoard::between(self.king_sq(), checker).contains(to)
+            };
+        }
+
         // Verify that the from square is occupied.
         if let Some((color, piece)) = self.get_piece(from) {
             // Verify it is one of our pieces.
@@ -356,8 +703,8 @@ impl Board {
                 // The move can't be a castle if the piece moving is not the king.
                 verify!(!mv.is_castle());
 
-                // If there are any checkers.
-                if checkers.not_empty() {
+                // If there are any checkers, and the usual check rules apply.
+                if checkers.not_empty() && self.variant.has_standard_checks() {
                     // Two checkers, the piece moving must be the king.
                     verify!(!checkers.more_than_one());
 
@@ -382,8 +729,9 @@ impl Board {
                     // It is a valid pawn attack too.
                     return attacks::pawn(color, from).contains(to);
                 } else {
-                    // If the move is a promotion, it must go to the first or last rank.
-                    verify!(to.y() == 0 || to.y() == 7 || !mv.is_promote());
+                    // If the move is a promotion, it must go to the first or last rank,
+                    // and the promotion piece must be allowed by the variant.
+                    verify!(!mv.is_promote() || (to.y() == 0 || to.y() == 7) && self.variant.promotes().contains(&mv.get_promote()));
 
                     // Verify that the move is legal for a pawn.
                     if mv.is_capture() {
@@ -419,17 +767,30 @@ impl Board {
 
     /// Do the move without checking anything about it's legality.
     /// Returns true if the move is irreversible.
+    #[inline]
     pub fn do_move(&mut self, mv: Move) -> bool {
+        self.do_move_with(mv, &mut ())
+    }
+
+    /// Does the move like do_move(), reporting every piece placed or removed to the hook.
+    pub fn do_move_with(&mut self, mv: Move, hook: &mut impl MoveHook) -> bool {
         // Store previous state and increment fullmove counter.
-        self.prev_states.push(self.state.clone());
+        self.prev_states.push(self.state);
         self.ply += 1;
 
         // Invert the side to move.
         self.state.side_to_move = self.get_other_side();
 
-        // Extract base move infos and remove piece from it's starting position.
+        // Extract base move infos and remove piece from it's starting position,
+        // or from the pocket if it is dropped.
         let (from, to) = mv.squares();
-        let (color, mut piece) = self.remove_piece::<true>(from);
+        let (color, mut piece) = if mv.is_drop() {
+            let color = self.get_other_side();
+            self.remove_from_pocket(color, mv.get_drop());
+            (color, mv.get_drop())
+        } else {
+            self.remove_piece::<true>(from, hook)
+        };
 
         // Determine if the move is reversible or not.
         let reversible = mv.is_quiet() && piece != Piece::Pawn;
@@ -437,19 +798,29 @@ impl Board {
         if mv.is_castle() {
             // If the move is castling, move the rook as well.
             match to {
-                Square::G1 => self.displace_piece::<true>(Square::H1, Square::F1),
-                Square::G8 => self.displace_piece::<true>(Square::H8, Square::F8),
-                Square::C1 => self.displace_piece::<true>(Square::A1, Square::D1),
-                Square::C8 => self.displace_piece::<true>(Square::A8, Square::D8),
+                Square::G1 => self.displace_piece::<true>(Square::H1, Square::F1, hook),
+                Square::G8 => self.displace_piece::<true>(Square::H8, Square::F8, hook),
+                Square::C1 => self.displace_piece::<true>(Square::A1, Square::D1, hook),
+                Square::C8 => self.displace_piece::<true>(Square::A8, Square::D8, hook),
                 _ => unreachable!(),
             };
         } else if mv.is_en_passant() {
             // If the move is en passant, remove the pawn at the en passant square.
-            self.remove_piece::<true>(self.get_ep_square().unwrap());
+            self.remove_piece::<true>(self.get_ep_square().unwrap(), hook);
+
+            if self.variant == Variant::Crazyhouse {
+                self.add_to_pocket(color, Piece::Pawn);
+            }
         } else {
             // If the move is a capture, remove the enemy piece from the destination square.
             if mv.is_capture() {
-                self.remove_piece::<true>(to);
+                self.remove_piece::<true>(to, hook);
+
+                // In crazyhouse, the piece goes to our pocket, as a pawn if it was promoted.
+                if self.variant == Variant::Crazyhouse {
+                    let captured = if self.state.promoted.contains(to) {Piece::Pawn} else {mv.get_capture()};
+                    self.add_to_pocket(color, captured);
+                }
             }
     
             // If the move is a promotion, 
@@ -459,12 +830,42 @@ impl Board {
         }
 
         // Finally, place the piece at it's destination.
-        self.place_piece::<true>(color, piece, to);
+        self.place_piece::<true>(color, piece, to, hook);
+
+        // In crazyhouse, promoted pieces remain so as they move.
+        if self.variant == Variant::Crazyhouse {
+            let promoted = self.state.promoted.contains(from) && !mv.is_drop() || mv.is_promote();
+            self.state.promoted &= !(BitBoard::from(from) | BitBoard::from(to));
+            if promoted {
+                self.state.promoted |= to.into();
+            }
+        }
+
+        // In atomic, captures explode the capturing piece and every piece but pawns around.
+        self.state.exploded = 0;
+        if self.variant == Variant::Atomic && (mv.is_capture() || mv.is_en_passant()) {
+            let pawns = self.get_bitboard(Color::White, Piece::Pawn) | self.get_bitboard(Color::Black, Piece::Pawn);
+            let blast = BitBoard::from(to) | attacks::king(to) & self.occ.all & !pawns;
+
+            for sq in blast.iter_squares() {
+                let (color, piece) = self.remove_piece::<true>(sq, hook);
+                self.exploded.push((sq, color, piece));
+                self.state.castle_rights.update(sq, sq);
+                self.state.exploded += 1;
+            }
+        }
 
         // Determine checkers and pinned bitboard.
         self.state.checkers = self.checkers();
         self.state.pinned = self.pinned();
 
+        // In three-check, count the checks given.
+        if self.variant == Variant::ThreeCheck && self.state.checkers.not_empty() {
+            self.state.zobrist ^= Zobrist::checks(color, self.state.checks[usize::from(color)]);
+            self.state.checks[usize::from(color)] += 1;
+            self.state.zobrist ^= Zobrist::checks(color, self.state.checks[usize::from(color)]);
+        }
+
         // Update castling rights and en passant square.
         self.state.castle_rights.update(from, to);
         if mv.is_double_push() {
@@ -487,34 +888,51 @@ impl Board {
     }
 
     /// Undoes the move, reverting the board to it's previous state.
+    #[inline]
     pub fn undo_move(&mut self, mv: Move) {
+        self.undo_move_with(mv, &mut ())
+    }
+
+    /// Undoes the move like undo_move(), reporting every piece placed or removed to the hook.
+    pub fn undo_move_with(&mut self, mv: Move, hook: &mut impl MoveHook) {
         // Them color.
         let them = self.get_side_to_move();
 
+        // Put back the pieces exploded by the move, in atomic.
+        for _ in 0..self.state.exploded {
+            let (sq, color, piece) = self.exploded.pop().unwrap();
+            self.place_piece::<false>(color, piece, sq, hook);
+        }
+
         // Restore the previous state and decrement the fullmove counter.
         self.state = self.prev_states.pop().unwrap();
         self.ply -= 1;
 
         // Extract basic move info and remove the piece from it's destination.
         let (from, to) = mv.squares();
-        let (color, mut piece) = self.remove_piece::<false>(to);
+        let (color, mut piece) = self.remove_piece::<false>(to, hook);
+
+        // A dropped piece is back in the pocket with the previous state.
+        if mv.is_drop() {
+            return;
+        }
 
         if mv.is_castle() {
             // If the move was castling, move the rook back as well.
             match to {
-                Square::G1 => self.displace_piece::<true>(Square::F1, Square::H1),
-                Square::G8 => self.displace_piece::<true>(Square::F8, Square::H8),
-                Square::C1 => self.displace_piece::<true>(Square::D1, Square::A1),
-                Square::C8 => self.displace_piece::<true>(Square::D8, Square::A8),
+                Square::G1 => self.displace_piece::<false>(Square::F1, Square::H1, hook),
+                Square::G8 => self.displace_piece::<false>(Square::F8, Square::H8, hook),
+                Square::C1 => self.displace_piece::<false>(Square::D1, Square::A1, hook),
+                Square::C8 => self.displace_piece::<false>(Square::D8, Square::A8, hook),
                 _ => unreachable!(),
             };
         } else if mv.is_en_passant() {
             // If the move was en passant, place the enemy pawn back as well.
-            self.place_piece::<false>(them, Piece::Pawn, self.get_ep_square().unwrap());
+            self.place_piece::<false>(them, Piece::Pawn, self.get_ep_square().unwrap(), hook);
         } else {
             // If the move was a capture, replace the taken enemy piece in it's place.
             if mv.is_capture() {
-                self.place_piece::<false>(them, mv.get_capture(), to);
+                self.place_piece::<false>(them, mv.get_capture(), to, hook);
             }
     
             // If the move was a promotion, the original piece was a pawn.
@@ -523,7 +941,7 @@ impl Board {
             }
         }
 
-        self.place_piece::<false>(color, piece, from);
+        self.place_piece::<false>(color, piece, from, hook);
     }
 
     /// Efficiently tests for an upcoming repetition on the line,
@@ -561,6 +979,10 @@ impl Board {
     /// Parses the move, checking the legality of the move.
     pub fn parse_move(&self, s: &str) -> Result<Move> {
         let mv = match s.len() {
+            4 if s.as_bytes()[1] == b'@' => {
+                let (_, piece) = Piece::from_char(s.chars().next().unwrap())?;
+                Move::drop(piece, Square::from_str(&s[2..4])?)
+            },
             4 => {
                 let from = Square::from_str(&s[0..2])?;
                 let to = Square::from_str(&s[2..4])?;
@@ -606,6 +1028,7 @@ impl Board {
                     'n' => Piece::Knight,
                     'b' => Piece::Bishop,
                     'q' => Piece::Queen,
+                    'k' => Piece::King,
                     _ => return Err(Error::msg("Unrecognized promotion.")),
                 };
     
@@ -625,7 +1048,48 @@ impl Board {
         }
     }
 
+    /// Parses a move encoded in standard algebraic notation (SAN), checking
+    /// the legality of the move. Check and annotation suffixes are ignored.
+    #[cfg(feature = "alloc")]
+    pub fn parse_san(&self, s: &str) -> Result<Move> {
+        let s = s.trim_end_matches(['+', '#', '!', '?']);
+        let s = match s {
+            "0-0" => "O-O",
+            "0-0-0" => "O-O-O",
+            s => s,
+        };
+
+        let mut legals = Vec::new();
+        movegen::legals(self, &mut legals);
+
+        legals.iter()
+            .copied()
+            .find(|&mv| self.san_base(mv, &legals) == s)
+            .ok_or_else(|| Error::msg("Move is illegal or not in standard algebraic notation."))
+    }
+
+    /// Formats a legal move in standard algebraic notation (SAN), with
+    /// the check or checkmate suffix.
+    #[cfg(feature = "alloc")]
+    pub fn to_san(&self, mv: Move) -> String {
+        let mut legals = Vec::new();
+        movegen::legals(self, &mut legals);
+
+        let mut san = self.san_base(mv, &legals);
+
+        let mut board = self.clone();
+        board.do_move(mv);
+        if board.get_checkers().not_empty() {
+            legals.clear();
+            movegen::legals(&board, &mut legals);
+            san.push(if legals.is_empty() {'#'} else {'+'});
+        }
+
+        san
+    }
+
     /// Pretty-prints the board into a terminal, with emojis for pieces and ansi colors for squares.
+    #[cfg(feature = "alloc")]
     pub fn pretty_print(&self) -> String {
         const RESET: &str = "\x1b[0m";
         const BLACK: &str = "\x1b[40;1m";
@@ -636,9 +1100,9 @@ impl Board {
 
         let mut res = String::new();
 
-        res.extend("  a b c d e f g h\n".chars());
+        res.push_str("  a b c d e f g h\n");
         for y in (0..8).rev() {
-            let rankc = char::from('1' as u8 + y);
+            let rankc = char::from(b'1' + y);
             res.push(rankc);
 
             for x in 0..8 {
@@ -662,15 +1126,73 @@ impl Board {
                 res.push('\n');
             }
         }
-        res.extend("\n  a b c d e f g h".chars());
+        res.push_str("\n  a b c d e f g h");
 
         res
     }
+
+    /// Returns true if the side to move may capture anything, in which case
+    /// it must in antichess.
+    pub fn can_capture(&self) -> bool {
+        let us = self.get_side_to_move();
+        let them_occ = self.occ.colored(self.get_other_side());
+
+        let mut en_passant = false;
+        movegen::gen_en_passant(self, |_, _| en_passant = true);
+        if en_passant {
+            return true;
+        }
+
+        self.occ.colored(us).iter_squares().any(|from| (self.attacks_from(from) & them_occ).not_empty())
+    }
 }
 
 // ================================ pub(crate) impl
 
 impl Board {
+    /// Creates the board described by the builder, which validated it.
+    pub(crate) fn from_builder(builder: &BoardBuilder) -> Board {
+        let mut board = Board {
+            variant: builder.variant,
+            ..Board::default()
+        };
+
+        for sq in Square::SQUARES {
+            if let Some((color, piece)) = builder.get_piece(sq) {
+                board.place_piece::<true>(color, piece, sq, &mut ());
+            }
+        }
+
+        for color in Color::COLORS {
+            let checks = builder.checks[usize::from(color)];
+            board.state.checks[usize::from(color)] = checks;
+            board.state.zobrist ^= Zobrist::checks(color, checks);
+
+            for piece in Piece::PIECES.iter().copied().take(5) {
+                for _ in 0..builder.pockets[usize::from(color)][usize::from(piece)] {
+                    board.add_to_pocket(color, piece);
+                }
+            }
+        }
+
+        board.state.side_to_move = builder.side_to_move;
+        board.state.castle_rights = builder.castle_rights;
+        board.state.ep_square = builder.ep_square;
+        board.state.halfmove = builder.halfmove;
+        board.state.promoted = builder.promoted & board.occ.all;
+        board.ply = 2 * (builder.fullmove - 1) + builder.side_to_move as u16;
+
+        board.state.checkers = board.checkers();
+        board.state.pinned = board.pinned();
+
+        // The zobrist key is inverted when black is to move, like it is in do_move().
+        if board.state.side_to_move == Color::Black {
+            board.state.zobrist = !board.state.zobrist;
+        }
+
+        board
+    }
+
     /// Returns the castling rights in the current position.
     #[inline]
     pub(crate) fn get_castle_rights(&self) -> CastleRights {
@@ -690,20 +1212,17 @@ impl Board {
         (BitBoard::between(from, to) & self.occ.all).empty()
     }
 
-    /// Returns the bitboard of all the attackers to that square. Does not take
-    /// en passant into account.
+    /// Returns the bitboard of all the attackers of that color to that square,
+    /// with the given occupancy. Does not take en passant into account.
     #[inline]
-    pub(crate) fn attackers_to(&self, sq: Square, occ: BitBoard) -> BitBoard {
-        let us = self.get_side_to_move();
-        let them = self.get_other_side();
-
-        let queens = self.get_bitboard(them, Piece::Queen);
-
-        attacks::pawn(us, sq) & self.get_bitboard(them, Piece::Pawn) 
-        | attacks::rook(sq, occ) & (self.get_bitboard(them, Piece::Rook) | queens)
-        | attacks::knight(sq) & self.get_bitboard(them, Piece::Knight) 
-        | attacks::bishop(sq, occ) & (self.get_bitboard(them, Piece::Bishop) | queens)
-        | attacks::king(sq) & self.get_bitboard(them, Piece::King)
+    pub(crate) fn attackers_to_occ(&self, sq: Square, color: Color, occ: BitBoard) -> BitBoard {
+        let queens = self.get_bitboard(color, Piece::Queen);
+
+        attacks::pawn(color.invert(), sq) & self.get_bitboard(color, Piece::Pawn) 
+        | attacks::rook(sq, occ) & (self.get_bitboard(color, Piece::Rook) | queens)
+        | attacks::knight(sq) & self.get_bitboard(color, Piece::Knight) 
+        | attacks::bishop(sq, occ) & (self.get_bitboard(color, Piece::Bishop) | queens)
+        | attacks::king(sq) & self.get_bitboard(color, Piece::King)
     }
 }
 
@@ -713,33 +1232,45 @@ impl Board {
     /// Places a piece of the given color on the given square. If ZOBRIST is true, 
     /// updates the zobrist key accordingly.
     #[inline]
-    fn place_piece<const ZOBRIST: bool>(&mut self, color: Color, piece: Piece, sq: Square) {
+    fn place_piece<const ZOBRIST: bool>(&mut self, color: Color, piece: Piece, sq: Square, hook: &mut impl MoveHook) {
+        hook.place(color, piece, sq);
         self.mailbox[usize::from(sq)] = Some((color, piece));
         
         let mask = sq.into();
         self.bitboards[usize::from(color)][usize::from(piece)] ^= mask;
         self.occ.all ^= mask;
         self.occ.colored[usize::from(color)] ^= mask;
+        self.phase += PHASE_WEIGHTS[usize::from(piece)];
 
         if ZOBRIST {
-            self.state.zobrist ^= Zobrist::from((color, piece, sq));
+            let zobrist = Zobrist::from((color, piece, sq));
+            self.state.zobrist ^= zobrist;
+            if piece == Piece::Pawn {
+                self.state.pawn_zobrist ^= zobrist;
+            }
         }
     }
 
     /// Removes the piece on the given square. If ZOBRIST is true, updates the
     /// zobrist key accordingly.
     #[inline]
-    fn remove_piece<const ZOBRIST: bool>(&mut self, sq: Square) -> (Color, Piece) {
+    fn remove_piece<const ZOBRIST: bool>(&mut self, sq: Square, hook: &mut impl MoveHook) -> (Color, Piece) {
         let (color, piece) = self.mailbox[usize::from(sq)].unwrap();
+        hook.remove(color, piece, sq);
         self.mailbox[usize::from(sq)] = None;
         
         let mask = sq.into();
         self.bitboards[usize::from(color)][usize::from(piece)] ^= mask;
         self.occ.all ^= mask;
         self.occ.colored[usize::from(color)] ^= mask;
+        self.phase -= PHASE_WEIGHTS[usize::from(piece)];
 
         if ZOBRIST {
-            self.state.zobrist ^= Zobrist::from((color, piece, sq));
+            let zobrist = Zobrist::from((color, piece, sq));
+            self.state.zobrist ^= zobrist;
+            if piece == Piece::Pawn {
+                self.state.pawn_zobrist ^= zobrist;
+            }
         }
 
         (color, piece)
@@ -748,23 +1279,305 @@ impl Board {
     /// Dispalces a piece between the two given squares. If ZOBRIST is true, updates the
     /// zobrist key accordingly.
     #[inline]
-    fn displace_piece<const ZOBRIST: bool>(&mut self, from: Square, to: Square) -> (Color, Piece) {
-        let (color, piece) = self.remove_piece::<ZOBRIST>(from);
-        self.place_piece::<ZOBRIST>(color, piece, to);
+    fn displace_piece<const ZOBRIST: bool>(&mut self, from: Square, to: Square, hook: &mut impl MoveHook) -> (Color, Piece) {
+        let (color, piece) = self.remove_piece::<ZOBRIST>(from, hook);
+        self.place_piece::<ZOBRIST>(color, piece, to, hook);
         (color, piece)
     }
 
+    /// Adds a piece to the pocket of that color, updating the zobrist key.
+    #[inline]
+    fn add_to_pocket(&mut self, color: Color, piece: Piece) {
+        let count = &mut self.state.pockets[usize::from(color)][usize::from(piece)];
+        self.state.zobrist ^= Zobrist::pocket(color, piece, *count) ^ Zobrist::pocket(color, piece, *count + 1);
+        *count += 1;
+    }
+
+    /// Removes a piece from the pocket of that color, updating the zobrist key.
+    #[inline]
+    fn remove_from_pocket(&mut self, color: Color, piece: Piece) {
+        let count = &mut self.state.pockets[usize::from(color)][usize::from(piece)];
+        self.state.zobrist ^= Zobrist::pocket(color, piece, *count) ^ Zobrist::pocket(color, piece, *count - 1);
+        *count -= 1;
+    }
+
+    /// Parses a fen string into a board of the given variant.
+    fn parse_fen(variant: Variant, s: &str) -> Result<Board> {
+            let mut split = s.split(' ');
+
+            let mut next_arg = || split.next().ok_or_else(|| Error::msg("not enough arguments in fen string"));
+
+            let ranks = next_arg()?;
+
+            let mut board = Board {
+                variant,
+                ..Board::default()
+            };
+            board.state.side_to_move = Color::from_str(next_arg()?)?;
+            board.state.castle_rights = CastleRights::from_str(next_arg()?)?;
+            board.state.ep_square = EnPassantSquare::from_str(next_arg()?)?;
+
+            if variant == Variant::Antichess && board.state.castle_rights.get_raw() != 0 {
+                return Err(Error::msg("Castling is not allowed in antichess."));
+            }
+
+            // The remaining checks of each color, optional in three-check.
+            let mut arg = next_arg()?;
+            if variant == Variant::ThreeCheck && arg.contains('+') {
+                let (white, black) = arg.split_once('+').unwrap();
+
+                for (color, remaining) in [(Color::White, white), (Color::Black, black)] {
+                    let remaining = u8::from_str(remaining)?;
+                    if remaining > 3 {
+                        return Err(Error::msg("Too many remaining checks in fen string."));
+                    }
+                    board.state.checks[usize::from(color)] = 3 - remaining;
+                    board.state.zobrist ^= Zobrist::checks(color, 3 - remaining);
+                }
+
+                arg = next_arg()?;
+            }
+
+            board.state.halfmove = u8::from_str(arg)?;
+            // The ply is deduced from the fullmove number and the side to move.
+            let fullmove = u16::from_str(next_arg()?)?.max(1);
+            board.ply = 2 * (fullmove - 1) + board.state.side_to_move as u16;
+
+            if split.next().is_some() {
+                return Err(Error::msg("Too many arguments in fen string."));
+            }
+
+            // The pockets, in crazyhouse, follow the ranks in brackets, like "[Qn]".
+            let ranks = if let Some((ranks, pockets)) = ranks.split_once('[') {
+                if variant != Variant::Crazyhouse {
+                    return Err(Error::msg("Pockets are only allowed in crazyhouse."));
+                }
+
+                let pockets = pockets.strip_suffix(']').ok_or_else(|| Error::msg("Unclosed pockets in fen string."))?;
+                for c in pockets.chars() {
+                    let (color, piece) = Piece::from_char(c)?;
+                    if piece == Piece::King || board.get_pocket(color, piece) == 16 {
+                        return Err(Error::msg("Invalid pockets in fen string."));
+                    }
+                    board.add_to_pocket(color, piece);
+                }
+
+                ranks
+            } else {
+                ranks
+            };
+
+            let mut y = 0;
+            let ranks = ranks.split('/');
+
+            for rank in ranks {
+                if y == 8 {
+                    return Err(Error::msg("Too many ranks in fen string."));
+                }
+                
+                let mut x = 0;
+                for c in rank.chars() {
+                    match c {
+                        '1'..='8' => x += c as i8 - '1' as i8,
+                        // A promoted piece is followed by a tilde, in crazyhouse.
+                        '~' => {
+                            if variant != Variant::Crazyhouse || x == 0 || board.get_piece(Square::from((x - 1, 7 - y))).is_none() {
+                                return Err(Error::msg("Misplaced promotion mark in fen string."));
+                            }
+                            board.state.promoted |= Square::from((x - 1, 7 - y)).into();
+                            continue;
+                        },
+                        _ => {
+                            let (color, piece) = Piece::from_char(c)?;
+                            let sq = Square::from((x, 7 - y));
+                            board.place_piece::<true>(color, piece, sq, &mut ());
+                        }
+                    }
+
+                    x += 1;
+                    if x > 8 {
+                        return Err(Error::msg("Rank too large in fen string."));
+                    }
+                }
+
+                if x != 8 {
+                    return Err(Error::msg("Rank too small in fen string."));
+                }
+                y += 1;
+            }
+
+            if y != 8 {
+                return Err(Error::msg("Not enough ranks in fen string."));
+            }
+
+            // The king is royal, there must be exactly one of each color.
+            if variant.has_standard_checks() && Color::COLORS.iter().any(|&color| board.get_bitboard(color, Piece::King).count() != 1) {
+                return Err(Error::msg("There must be exactly one king of each color in fen string."));
+            }
+
+            board.state.checkers = board.checkers();
+            board.state.pinned = board.pinned();
+
+            // The zobrist key is inverted when black is to move, like it is in do_move().
+            if board.state.side_to_move == Color::Black {
+                board.state.zobrist = !board.state.zobrist;
+            }
+
+            Ok(board)
+    }
+
+    /// Returns true if that pseudo-legal move is legal in atomic. Our king must not
+    /// explode, and must not be attacked after the move unless the opponent's king
+    /// exploded or the two kings are connected.
+    fn is_legal_atomic(&self, mv: Move) -> bool {
+        let (from, to) = mv.squares();
+        let king_sq = self.king_sq();
+        let occ = self.occ.all;
+
+        if mv.is_castle() {
+            // The king must not be in check, and the squares it passes must be safe.
+            let passes = match to {
+                Square::G1 => [Square::F1, Square::G1],
+                Square::G8 => [Square::F8, Square::G8],
+                Square::C1 => [Square::C1, Square::D1],
+                Square::C8 => [Square::C8, Square::D8],
+                _ => unreachable!(),
+            };
+
+            return !self.is_attacked_atomic(king_sq, occ, BitBoard::EMPTY)
+                && passes.iter().all(|&sq| !self.is_attacked_atomic(sq, occ, BitBoard::EMPTY));
+        }
+
+        if mv.is_capture() || mv.is_en_passant() {
+            // The king can't capture, it would explode.
+            if from == king_sq {
+                return false;
+            }
+
+            let pawns = self.get_bitboard(Color::White, Piece::Pawn) | self.get_bitboard(Color::Black, Piece::Pawn);
+            let mut blast = BitBoard::from(from) | BitBoard::from(to) | attacks::king(to) & occ & !pawns;
+            if mv.is_en_passant() {
+                blast |= self.get_ep_square().unwrap().into();
+            }
+
+            if blast.contains(king_sq) {
+                return false;
+            } else if (blast & self.get_bitboard(self.get_other_side(), Piece::King)).not_empty() {
+                return true;
+            }
+
+            return !self.is_attacked_atomic(king_sq, occ & !blast, blast);
+        }
+
+        let new_king_sq = if from == king_sq {to} else {king_sq};
+        !self.is_attacked_atomic(new_king_sq, occ ^ BitBoard::from(from) | BitBoard::from(to), BitBoard::EMPTY)
+    }
+
+    /// Returns true if the square is attacked by the opponent's pieces, the removed ones
+    /// excepted, with the given occupancy. The squares next to the opponent's king are
+    /// safe in atomic, as the kings can't capture each other.
+    fn is_attacked_atomic(&self, sq: Square, occ: BitBoard, removed: BitBoard) -> bool {
+        let their_king = self.get_bitboard(self.get_other_side(), Piece::King) & !removed;
+
+        if (attacks::king(sq) & their_king).not_empty() {
+            return false;
+        }
+
+        (self.attackers_to_occ(sq, self.get_other_side(), occ) & !removed & !their_king).not_empty()
+    }
+
+    /// Formats the move in standard algebraic notation, without the check suffix.
+    /// The legals moves of the position are used for disambiguation.
+    #[cfg(feature = "alloc")]
+    fn san_base(&self, mv: Move, legals: &[Move]) -> String {
+        const FILES: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+        const RANKS: [char; 8] = ['1', '2', '3', '4', '5', '6', '7', '8'];
+
+        let (from, to) = mv.squares();
+
+        if mv.is_castle() {
+            return if to.x() == 6 {"O-O"} else {"O-O-O"}.to_string();
+        } else if mv.is_drop() {
+            return format!("{}@{}", mv.get_drop().as_char(Color::White), to);
+        }
+
+        let mut san = String::new();
+        let piece = self.get_piece_unchecked(from);
+
+        if piece == Piece::Pawn {
+            if mv.is_capture() || mv.is_en_passant() {
+                san.push(FILES[from.x() as usize]);
+            }
+        } else {
+            san.push(piece.as_char(Color::White));
+
+            // Other pieces of the same type that may go to the same square.
+            let others = legals.iter()
+                .filter(|other| other.to() == to && other.from() != from && !other.is_drop())
+                .filter(|other| self.get_piece_unchecked(other.from()) == piece)
+                .map(|other| other.from())
+                .collect::<Vec<_>>();
+
+            if !others.is_empty() {
+                if others.iter().all(|sq| sq.x() != from.x()) {
+                    san.push(FILES[from.x() as usize]);
+                } else if others.iter().all(|sq| sq.y() != from.y()) {
+                    san.push(RANKS[from.y() as usize]);
+                } else {
+                    san.push_str(&from.to_string());
+                }
+            }
+        }
+
+        if mv.is_capture() || mv.is_en_passant() {
+            san.push('x');
+        }
+        san.push_str(&to.to_string());
+
+        if mv.is_promote() {
+            san.push('=');
+            san.push(mv.get_promote().as_char(Color::White));
+        }
+
+        san
+    }
+
     /// The bitboard of the checkers to the current king.
     #[inline]
     fn checkers(&self) -> BitBoard {
         let occ = self.get_occupancy().all();
-        self.attackers_to(self.king_sq(), occ)
+
+        match self.variant {
+            Variant::Antichess => BitBoard::EMPTY,
+            Variant::Atomic => {
+                // Kings may be exploded, and connected kings are never in check
+                // as they can't capture each other.
+                let their_king = self.get_bitboard(self.get_other_side(), Piece::King);
+                if self.get_bitboard(self.get_side_to_move(), Piece::King).empty() || their_king.empty() {
+                    return BitBoard::EMPTY;
+                }
+
+                let king_sq = self.king_sq();
+                if (attacks::king(king_sq) & their_king).not_empty() {
+                    BitBoard::EMPTY
+                } else {
+                    self.attackers_to_occ(king_sq, self.get_other_side(), occ) & !their_king
+                }
+            },
+            _ => self.attackers_to_occ(self.king_sq(), self.get_other_side(), occ),
+        }
     }
 
     /// The bitboard of the currently pinned pieces.
     #[inline]
     fn pinned(&self) -> BitBoard {
         let us = self.get_side_to_move();
+
+        // Without a royal king, nothing is pinned.
+        if self.variant == Variant::Antichess || self.get_bitboard(us, Piece::King).empty() {
+            return BitBoard::EMPTY;
+        }
+
         let occ_us = self.occ.colored(us);
         let them = self.get_other_side();
         let queens = self.get_bitboard(them, Piece::Queen);
@@ -796,14 +1609,17 @@ impl Default for Board {
     /// Returns an empty board.
     fn default() -> Board {
         Board {
+            variant: Variant::Standard,
             ply: 0,
 
             bitboards: Default::default(),
             mailbox: [None; 64],
             occ: Occupancy::default(),
+            phase: 0,
         
             state: StateInfo::default(),
-            prev_states: Vec::new(),
+            prev_states: History::new(),
+            exploded: History::new(),
         }
     }
 }
@@ -815,7 +1631,7 @@ impl fmt::Display for Board {
         macro_rules! write_if_not_zero {
             ($i: expr) => {
                 if $i != 0 {
-                    write!(f, "{}", ('0' as u8 + $i) as char)?;
+                    write!(f, "{}", (b'0' + $i) as char)?;
                 }
             };
         }
@@ -824,9 +1640,13 @@ impl fmt::Display for Board {
             let mut streak = 0;
 
             for x in 0..8 {
-                if let Some((color, piece)) = self.get_piece(Square::from((x, y))) {
+                let sq = Square::from((x, y));
+                if let Some((color, piece)) = self.get_piece(sq) {
                     write_if_not_zero!(streak);
                     write!(f, "{}", piece.as_char(color))?;
+                    if self.state.promoted.contains(sq) {
+                        write!(f, "~")?;
+                    }
                     streak = 0;
                 } else {
                     streak += 1;
@@ -839,79 +1659,60 @@ impl fmt::Display for Board {
             }
         }
 
-        write!(f, " {} {} {} {} {}", 
+        // The pockets, in crazyhouse.
+        if self.variant == Variant::Crazyhouse {
+            write!(f, "[")?;
+            for &color in &Color::COLORS {
+                for &piece in &[Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight, Piece::Pawn] {
+                    for _ in 0..self.get_pocket(color, piece) {
+                        write!(f, "{}", piece.as_char(color))?;
+                    }
+                }
+            }
+            write!(f, "]")?;
+        }
+
+        write!(f, " {} {} {}", 
             self.get_side_to_move(),
             self.get_castle_rights(),
             self.get_ep_square(),
-            self.get_halfmove(),
-            1 + self.get_ply() / 2,
         )?;
 
+        // The remaining checks of each color, in three-check.
+        if self.variant == Variant::ThreeCheck {
+            write!(f, " {}+{}",
+                3 - self.get_checks(Color::White).min(3),
+                3 - self.get_checks(Color::Black).min(3),
+            )?;
+        }
+
+        write!(f, " {} {}", self.get_halfmove(), 1 + self.get_ply() / 2)?;
+
         Ok(())
     }
 }
 
-impl<'a> FromStr for Board {
+impl FromStr for Board {
     type Err = Error;
 
     /// Tries to parse a board from a string in fen representation.
     fn from_str(s: &str) -> Result<Board> {
-        let mut split = s.split(' ');
-
-        let mut next_arg = || split.next().ok_or_else(|| Error::msg("not enough arguments in fen string"));
-
-        let ranks = next_arg()?;
-
-        let mut board = Board::default();
-        board.state.side_to_move = Color::from_str(next_arg()?)?;
-        board.state.castle_rights = CastleRights::from_str(next_arg()?)?;
-        board.state.ep_square = EnPassantSquare::from_str(next_arg()?)?;
-        board.state.halfmove = u8::from_str(next_arg()?)?;
-        board.ply = u16::from_str(next_arg()?)?;
-
-        if split.next().is_some() {
-            return Err(Error::msg("Too many arguments in fen string."));
-        }
-
-        let mut y = 0;
-        let ranks = ranks.split('/');
-
-        for rank in ranks {
-            if y == 8 {
-                return Err(Error::msg("Too many ranks in fen string."));
-            }
-            
-            let mut x = 0;
-            for c in rank.chars() {
-                match c {
-                    '1'..='8' => x += c as i8 - '1' as i8,
-                    _ => {
-                        let (color, piece) = Piece::from_char(c)?;
-                        let sq = Square::from((x, 7 - y));
-                        board.get_bitboard(Color::White, Piece::Pawn);
-                        board.place_piece::<true>(color, piece, sq);
-                    }
-                }
-
-                x += 1;
-                if x > 8 {
-                    return Err(Error::msg("Rank too large in fen string."));
-                }
-            }
-
-            if x != 8 {
-                return Err(Error::msg("Rank too small in fen string."));
-            }
-            y += 1;
-        }
-
-        if y != 8 {
-            return Err(Error::msg("Not enough ranks in fen string."));
-        }
+        Board::parse_fen(Variant::Standard, s)
+    }
+}
 
-        board.state.checkers = board.checkers();
-        board.state.pinned = board.pinned();
+#[cfg(feature = "serde")]
+impl Serialize for Board {
+    /// Serializes the board as it's fen representation.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
 
-        Ok(board)
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Board {
+    /// Deserializes the board from it's fen representation.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Board, D::Error> {
+        crate::serde_utils::deserialize_from_str(deserializer, "a fen string")
     }
 }
\ No newline at end of file
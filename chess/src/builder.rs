@@ -0,0 +1,243 @@
+use crate::bitboard::BitBoard;
+use crate::board::Board;
+use crate::castle_rights::{CastleMask, CastleRights};
+use crate::color::Color;
+use crate::en_passant::EnPassantSquare;
+use crate::error::{Error, Result};
+use crate::piece::Piece;
+use crate::square::Square;
+use crate::variant::Variant;
+
+//#################################################################################################
+//
+//                                      struct BoardBuilder
+//
+//#################################################################################################
+
+/// A builder of positions, placing pieces and setting up the state piece by piece.
+/// The position is validated when the board is built.
+#[derive(Clone, Debug)]
+pub struct BoardBuilder {
+    pub(crate) variant: Variant,
+    pub(crate) mailbox: [Option<(Color, Piece)>; 64],
+    pub(crate) side_to_move: Color,
+    pub(crate) castle_rights: CastleRights,
+    pub(crate) ep_square: EnPassantSquare,
+    pub(crate) halfmove: u8,
+    pub(crate) fullmove: u16,
+    pub(crate) checks: [u8; 2],
+    pub(crate) pockets: [[u8; 5]; 2],
+    pub(crate) promoted: BitBoard,
+}
+
+// ================================ pub impl
+
+impl BoardBuilder {
+    /// Creates a builder of a standard chess position, with an empty board,
+    /// white to move and no castle rights.
+    pub fn new() -> BoardBuilder {
+        BoardBuilder::new_variant(Variant::Standard)
+    }
+
+    /// Creates a builder of a position of the given variant, with an empty board,
+    /// white to move and no castle rights.
+    pub fn new_variant(variant: Variant) -> BoardBuilder {
+        BoardBuilder {
+            variant,
+            mailbox: [None; 64],
+            side_to_move: Color::White,
+            castle_rights: CastleRights::from_raw(0),
+            ep_square: EnPassantSquare::None,
+            halfmove: 0,
+            fullmove: 1,
+            checks: [0; 2],
+            pockets: [[0; 5]; 2],
+            promoted: BitBoard::EMPTY,
+        }
+    }
+
+    /// Returns the piece on that square, if any.
+    #[inline]
+    pub fn get_piece(&self, sq: Square) -> Option<(Color, Piece)> {
+        self.mailbox[usize::from(sq)]
+    }
+
+    /// Puts a piece of that color on that square, replacing the previous one.
+    pub fn put(&mut self, sq: Square, color: Color, piece: Piece) -> &mut BoardBuilder {
+        self.mailbox[usize::from(sq)] = Some((color, piece));
+        self.promoted &= !BitBoard::from(sq);
+        self
+    }
+
+    /// Removes the piece on that square, if any.
+    pub fn remove(&mut self, sq: Square) -> &mut BoardBuilder {
+        self.mailbox[usize::from(sq)] = None;
+        self.promoted &= !BitBoard::from(sq);
+        self
+    }
+
+    /// Sets the side to move.
+    pub fn side_to_move(&mut self, color: Color) -> &mut BoardBuilder {
+        self.side_to_move = color;
+        self
+    }
+
+    /// Sets the castle rights of that color, on the king and queen sides.
+    pub fn castle_rights(&mut self, color: Color, king_side: bool, queen_side: bool) -> &mut BoardBuilder {
+        let (oo, ooo) = match color {
+            Color::White => (CastleMask::WhiteOO as u8, CastleMask::WhiteOOO as u8),
+            Color::Black => (CastleMask::BlackOO as u8, CastleMask::BlackOOO as u8),
+        };
+
+        let mut raw = self.castle_rights.get_raw() & !(oo | ooo);
+        if king_side {
+            raw |= oo;
+        }
+        if queen_side {
+            raw |= ooo;
+        }
+
+        self.castle_rights = CastleRights::from_raw(raw);
+        self
+    }
+
+    /// Sets the en passant square, the square behind the pawn that just moved two squares.
+    pub fn ep_square(&mut self, sq: Option<Square>) -> &mut BoardBuilder {
+        self.ep_square = match sq {
+            Some(sq) => EnPassantSquare::Some(sq),
+            None => EnPassantSquare::None,
+        };
+        self
+    }
+
+    /// Sets the halfmove clock, the number of plies since the last capture or pawn move.
+    pub fn halfmove(&mut self, halfmove: u8) -> &mut BoardBuilder {
+        self.halfmove = halfmove;
+        self
+    }
+
+    /// Sets the fullmove number, starting at 1 and incremented after black's moves.
+    pub fn fullmove(&mut self, fullmove: u16) -> &mut BoardBuilder {
+        self.fullmove = fullmove.max(1);
+        self
+    }
+
+    /// Sets the number of checks given by that color, in three-check.
+    pub fn checks(&mut self, color: Color, checks: u8) -> &mut BoardBuilder {
+        self.checks[usize::from(color)] = checks;
+        self
+    }
+
+    /// Sets the number of pieces of that kind in the pocket of that color, in crazyhouse.
+    pub fn pocket(&mut self, color: Color, piece: Piece, count: u8) -> &mut BoardBuilder {
+        if piece != Piece::King {
+            self.pockets[usize::from(color)][usize::from(piece)] = count;
+        }
+        self
+    }
+
+    /// Validates the position and builds the board.
+    pub fn build(&self) -> Result<Board> {
+        let variant = self.variant;
+        let pieces = |color, piece| {
+            Square::SQUARES.iter().copied().filter(move |&sq| self.get_piece(sq) == Some((color, piece)))
+        };
+
+        // The king is royal, there must be exactly one of each color.
+        if variant.has_standard_checks() && Color::COLORS.iter().any(|&color| pieces(color, Piece::King).count() != 1) {
+            return Err(Error::msg("There must be exactly one king of each color."));
+        }
+
+        if Square::SQUARES.iter().any(|&sq| sq.y() % 7 == 0 && matches!(self.get_piece(sq), Some((_, Piece::Pawn)))) {
+            return Err(Error::msg("Pawns can't stand on the first or last rank."));
+        }
+
+        // Each castle right needs the king and the rook on their initial squares.
+        if variant == Variant::Antichess && self.castle_rights.get_raw() != 0 {
+            return Err(Error::msg("Castling is not allowed in antichess."));
+        }
+        for (mask, color, king, rook) in [
+            (CastleMask::WhiteOO, Color::White, Square::E1, Square::H1),
+            (CastleMask::WhiteOOO, Color::White, Square::E1, Square::A1),
+            (CastleMask::BlackOO, Color::Black, Square::E8, Square::H8),
+            (CastleMask::BlackOOO, Color::Black, Square::E8, Square::A8),
+        ] {
+            if self.castle_rights.has(mask) && (self.get_piece(king) != Some((color, Piece::King)) || self.get_piece(rook) != Some((color, Piece::Rook))) {
+                return Err(Error::msg("Castle rights without the king and rook on their initial squares."));
+            }
+        }
+
+        // The en passant square is behind a pawn that just moved two squares.
+        if self.ep_square.is_some() {
+            let sq = self.ep_square.unwrap();
+            let them = self.side_to_move.invert();
+            let dy = if them == Color::White {1} else {-1};
+            let (from, to) = (sq.displace((0, -dy)), sq.displace((0, dy)));
+
+            if sq.y() != if them == Color::White {2} else {5}
+                || self.get_piece(sq).is_some()
+                || from.and_then(|from| self.get_piece(from)).is_some()
+                || to.and_then(|to| self.get_piece(to)) != Some((them, Piece::Pawn)) {
+                return Err(Error::msg("Invalid en passant square."));
+            }
+        }
+
+        if variant != Variant::ThreeCheck && self.checks != [0; 2] {
+            return Err(Error::msg("Checks are only counted in three-check."));
+        }
+        if self.checks.iter().any(|&checks| checks > 3) {
+            return Err(Error::msg("Too many checks given."));
+        }
+
+        if variant != Variant::Crazyhouse && self.pockets != [[0; 5]; 2] {
+            return Err(Error::msg("Pockets are only allowed in crazyhouse."));
+        }
+        if self.pockets.iter().flatten().any(|&count| count > 16) {
+            return Err(Error::msg("Too many pieces in a pocket."));
+        }
+
+        let board = Board::from_builder(self);
+
+        // The side which just moved can't have left its king in check.
+        let them = board.get_other_side();
+        if variant.has_standard_checks() && board.is_attacked(pieces(them, Piece::King).next().unwrap(), board.get_side_to_move()) {
+            return Err(Error::msg("The side not to move is in check."));
+        }
+
+        Ok(board)
+    }
+}
+
+// ================================ traits impl
+
+impl Default for BoardBuilder {
+    /// Returns the builder of an empty standard chess position.
+    fn default() -> BoardBuilder {
+        BoardBuilder::new()
+    }
+}
+
+impl From<&Board> for BoardBuilder {
+    /// Returns a builder of that position, to modify it.
+    fn from(board: &Board) -> BoardBuilder {
+        let mut builder = BoardBuilder::new_variant(board.get_variant());
+
+        for sq in Square::SQUARES {
+            builder.mailbox[usize::from(sq)] = board.get_piece(sq);
+        }
+        builder.side_to_move = board.get_side_to_move();
+        builder.castle_rights = board.get_castle_rights();
+        builder.ep_square = board.get_ep_square();
+        builder.halfmove = board.get_halfmove();
+        builder.fullmove = board.get_ply() / 2 + 1;
+        for color in Color::COLORS {
+            builder.checks[usize::from(color)] = board.get_checks(color);
+            for piece in Piece::PIECES.iter().copied().take(5) {
+                builder.pockets[usize::from(color)][usize::from(piece)] = board.get_pocket(color, piece);
+            }
+        }
+        builder.promoted = board.get_promoted();
+
+        builder
+    }
+}
@@ -1,8 +1,7 @@
-use std::fmt;
-use std::str::FromStr;
-
-use anyhow::{Error, Result};
+use core::fmt;
+use core::str::FromStr;
 
+use crate::error::{Error, Result};
 use crate::square::Square;
 
 //#################################################################################################
@@ -34,12 +33,24 @@ pub(crate) struct CastleRights(u8);
 // ================================ pub(crate) impl
 
 impl CastleRights {
+    /// Creates castle rights from a raw 4 bits mask.
+    #[inline]
+    pub(crate) fn from_raw(raw: u8) -> CastleRights {
+        CastleRights(raw & 0b1111)
+    }
+
     /// Returns true if those rights contain that mask.
     #[inline]
     pub(crate) fn has(self, mask: CastleMask) -> bool {
         (self.0 & mask as u8) != 0
     }
 
+    /// Returns the raw value of those rights, a 4 bits mask.
+    #[inline]
+    pub(crate) fn get_raw(self) -> u8 {
+        self.0
+    }
+
     /// Updates the rights with the given from and to squares of the move.
     #[inline]
     pub(crate) fn update(&mut self, from: Square, to: Square) {
@@ -113,7 +124,7 @@ impl fmt::Display for CastleRights {
     }
 }
 
-impl<'a> FromStr for CastleRights {
+impl FromStr for CastleRights {
     type Err = Error;
 
     /// From fen notation for castle rights.
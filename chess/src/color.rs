@@ -1,7 +1,10 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
-use anyhow::{Error, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
 
 //#################################################################################################
 //
@@ -54,7 +57,7 @@ impl fmt::Display for Color {
     }
 }
 
-impl<'a> FromStr for Color {
+impl FromStr for Color {
     type Err = Error;
 
     /// From fen color notation.
@@ -73,4 +76,20 @@ impl From<Color> for usize {
     fn from(color: Color) -> usize {
         color as usize
     }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Color {
+    /// Serializes the color in fen color notation.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Color {
+    /// Deserializes the color from fen color notation.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        crate::serde_utils::deserialize_from_str(deserializer, "a color, either \"w\" or \"b\"")
+    }
 }
\ No newline at end of file
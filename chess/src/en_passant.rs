@@ -1,8 +1,7 @@
-use std::fmt;
-use std::str::FromStr;
-
-use anyhow::{Error, Result};
+use core::fmt;
+use core::str::FromStr;
 
+use crate::error::{Error, Result};
 use crate::square::Square;
 
 //#################################################################################################
@@ -57,7 +56,7 @@ impl fmt::Display for EnPassantSquare {
     }
 }
 
-impl<'a> FromStr for EnPassantSquare {
+impl FromStr for EnPassantSquare {
     type Err = Error;
 
     /// From fen en passant square notation.
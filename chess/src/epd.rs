@@ -0,0 +1,262 @@
+use core::fmt;
+use core::str::FromStr;
+
+use alloc::{format, string::{String, ToString}, vec::Vec};
+
+use crate::board::Board;
+use crate::error::{Error, Result};
+use crate::moves::Move;
+use crate::variant::Variant;
+
+//#################################################################################################
+//
+//                                          struct Epd
+//
+//#################################################################################################
+
+/// An extended position description record: the first four fields of a fen string,
+/// followed by operations, like `bm Nf3; id "test.001";`. The perft suites' format,
+/// with the halfmove and fullmove numbers and `;D1 20 ;D2 400` operations, is also accepted.
+#[derive(Clone, Debug)]
+pub struct Epd {
+    board: Board,
+    operations: Vec<(String, Vec<String>)>,
+
+    best_moves: Vec<Move>,
+    avoid_moves: Vec<Move>,
+    pv: Vec<Move>,
+    depth: Option<u32>,
+    eval: Option<i32>,
+    perft: Vec<(usize, u64)>,
+}
+
+// ================================ pub impl
+
+impl Epd {
+    /// Tries to parse the epd record.
+    pub fn new(epd: &str) -> Result<Epd> {
+        Epd::from_str(epd)
+    }
+
+    /// Tries to parse the epd record, with a position of the given variant.
+    pub fn new_variant(variant: Variant, epd: &str) -> Result<Epd> {
+        let mut fields = epd.trim().splitn(5, char::is_whitespace);
+        let mut next_field = || fields.next().ok_or_else(|| Error::msg("not enough fields in epd record"));
+        let position = [next_field()?, next_field()?, next_field()?, next_field()?].join(" ");
+        let mut operations = Epd::tokenize(fields.next().unwrap_or(""))?;
+
+        // The halfmove and fullmove numbers, either as operations or in the perft suites' format.
+        let mut counters = ("0".to_string(), "1".to_string());
+        if let Some((opcode, operands)) = operations.first() {
+            if operands.len() == 1 && opcode.parse::<u8>().is_ok() && operands[0].parse::<u16>().is_ok() {
+                counters = (opcode.clone(), operands[0].clone());
+                operations.remove(0);
+            }
+        }
+        for (opcode, operands) in operations.iter() {
+            match (opcode.as_str(), operands.as_slice()) {
+                ("hmvc", [n]) => counters.0 = n.clone(),
+                ("fmvn", [n]) => counters.1 = n.clone(),
+                _ => (),
+            }
+        }
+
+        let board = Board::new_variant(variant, &format!("{} {} {}", position, counters.0, counters.1))?;
+
+        let mut epd = Epd {
+            board,
+            operations: Vec::new(),
+            best_moves: Vec::new(),
+            avoid_moves: Vec::new(),
+            pv: Vec::new(),
+            depth: None,
+            eval: None,
+            perft: Vec::new(),
+        };
+
+        for (opcode, operands) in operations.iter() {
+            match opcode.as_str() {
+                "bm" => epd.best_moves = epd.parse_moves(operands)?,
+                "am" => epd.avoid_moves = epd.parse_moves(operands)?,
+                "pv" => {
+                    // The moves of the principal variation follow each other.
+                    let mut board = epd.board.clone();
+                    for s in operands {
+                        let mv = board.parse_move(s).or_else(|_| board.parse_san(s))?;
+                        board.do_move(mv);
+                        epd.pv.push(mv);
+                    }
+                },
+                "acd" => epd.depth = Some(Epd::single_operand(operands)?),
+                "ce" => epd.eval = Some(Epd::single_operand(operands)?),
+                _ => if let Some(depth) = opcode.strip_prefix('D').and_then(|d| usize::from_str(d).ok()) {
+                    epd.perft.push((depth, Epd::single_operand(operands)?));
+                },
+            }
+        }
+
+        epd.operations = operations;
+        Ok(epd)
+    }
+
+    /// Returns the position of the record.
+    #[inline]
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the operands of the operation with that opcode, if present.
+    pub fn get_operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations.iter()
+            .find(|(other, _)| other == opcode)
+            .map(|(_, operands)| operands.as_slice())
+    }
+
+    /// Returns the identifier of the record (id).
+    pub fn get_id(&self) -> Option<&str> {
+        self.get_operation("id").and_then(|operands| operands.first()).map(String::as_str)
+    }
+
+    /// Returns the comment of the record (c0).
+    pub fn get_comment(&self) -> Option<&str> {
+        self.get_operation("c0").and_then(|operands| operands.first()).map(String::as_str)
+    }
+
+    /// Returns the best moves of the position (bm).
+    #[inline]
+    pub fn get_best_moves(&self) -> &[Move] {
+        &self.best_moves
+    }
+
+    /// Returns the moves to avoid in the position (am).
+    #[inline]
+    pub fn get_avoid_moves(&self) -> &[Move] {
+        &self.avoid_moves
+    }
+
+    /// Returns the principal variation of the position (pv).
+    #[inline]
+    pub fn get_pv(&self) -> &[Move] {
+        &self.pv
+    }
+
+    /// Returns the depth the position was analyzed to (acd).
+    #[inline]
+    pub fn get_depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    /// Returns the evaluation of the position in centipawns (ce).
+    #[inline]
+    pub fn get_eval(&self) -> Option<i32> {
+        self.eval
+    }
+
+    /// Returns the expected perft results (D1, D2, ...), as (depth, nodes) pairs.
+    #[inline]
+    pub fn get_perft(&self) -> &[(usize, u64)] {
+        &self.perft
+    }
+
+    /// Returns true if the move is consistent with the best moves and the moves to avoid.
+    /// Without any of them, every move is.
+    pub fn is_solution(&self, mv: Move) -> bool {
+        (self.best_moves.is_empty() || self.best_moves.contains(&mv)) && !self.avoid_moves.contains(&mv)
+    }
+}
+
+// ================================ impl
+
+impl Epd {
+    /// Splits the operations into opcodes and operands. Operations end with
+    /// a semicolon, and quoted operands may contain spaces and semicolons.
+    fn tokenize(s: &str) -> Result<Vec<(String, Vec<String>)>> {
+        let mut operations = Vec::new();
+        let mut tokens = Vec::new();
+        let mut token = String::new();
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    for c in chars.by_ref().take_while(|&c| c != '"') {
+                        token.push(c);
+                    }
+                    tokens.push(core::mem::take(&mut token));
+                },
+                ';' | ' ' | '\t' => {
+                    if !token.is_empty() {
+                        tokens.push(core::mem::take(&mut token));
+                    }
+                    if c == ';' && !tokens.is_empty() {
+                        let opcode = tokens.remove(0);
+                        operations.push((opcode, core::mem::take(&mut tokens)));
+                    }
+                },
+                _ => token.push(c),
+            }
+        }
+
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+        if !tokens.is_empty() {
+            let opcode = tokens.remove(0);
+            operations.push((opcode, tokens));
+        }
+
+        if operations.iter().any(|(opcode, _)| opcode.is_empty()) {
+            return Err(Error::msg("Empty opcode in epd record."));
+        }
+
+        Ok(operations)
+    }
+
+    /// Parses the moves, in standard algebraic or pure algebraic coordinate notation.
+    fn parse_moves(&self, operands: &[String]) -> Result<Vec<Move>> {
+        operands.iter()
+            .map(|s| self.board.parse_san(s).or_else(|_| self.board.parse_move(s)))
+            .collect()
+    }
+
+    /// Parses the single operand of that operation.
+    fn single_operand<T: FromStr>(operands: &[String]) -> Result<T> {
+        match operands {
+            [operand] => operand.parse().map_err(|_| Error::msg("Invalid operand in epd record.")),
+            _ => Err(Error::msg("Expected a single operand in epd record.")),
+        }
+    }
+}
+
+// ================================ traits impl
+
+impl fmt::Display for Epd {
+    /// Formats the record, with the position's four fen fields and the operations.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fen = self.board.to_string();
+        write!(f, "{}", fen.split(' ').take(4).collect::<Vec<_>>().join(" "))?;
+
+        for (opcode, operands) in self.operations.iter() {
+            write!(f, " {}", opcode)?;
+            for operand in operands {
+                if operand.is_empty() || operand.contains([' ', ';']) || opcode == "id" || matches!(opcode.as_bytes(), [b'c', b'0'..=b'9']) {
+                    write!(f, " \"{}\"", operand)?;
+                } else {
+                    write!(f, " {}", operand)?;
+                }
+            }
+            write!(f, ";")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Epd {
+    type Err = Error;
+
+    /// Tries to parse an epd record of a standard chess position.
+    fn from_str(s: &str) -> Result<Epd> {
+        Epd::new_variant(Variant::Standard, s)
+    }
+}
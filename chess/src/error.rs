@@ -0,0 +1,51 @@
+use core::fmt;
+use core::num::ParseIntError;
+
+//#################################################################################################
+//
+//                                         struct Error
+//
+//#################################################################################################
+
+/// The error returned when parsing or validating chess data fails, with a static
+/// message so that it does not need any allocation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Error(&'static str);
+
+/// A result with the chess crate's error.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+// ================================ pub impl
+
+impl Error {
+    /// Creates an error with that message.
+    #[inline]
+    pub const fn msg(msg: &'static str) -> Error {
+        Error(msg)
+    }
+
+    /// Returns the message of the error.
+    #[inline]
+    pub fn get_msg(&self) -> &'static str {
+        self.0
+    }
+}
+
+// ================================ traits impl
+
+impl fmt::Display for Error {
+    /// Writes the message of the error.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<ParseIntError> for Error {
+    /// Numbers of fen strings and epd records are parsed with the standard parser.
+    fn from(_: ParseIntError) -> Error {
+        Error::msg("Invalid number.")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
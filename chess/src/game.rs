@@ -0,0 +1,225 @@
+use core::time::Duration;
+
+use alloc::{string::{String, ToString}, vec::Vec};
+
+use crate::board::{Board, Status};
+use crate::error::{Error, Result};
+use crate::moves::Move;
+
+//#################################################################################################
+//
+//                                         struct Game
+//
+//#################################################################################################
+
+/// The record of a game: the start position and the moves played from it, with a
+/// cursor allowing to undo and redo them, the headers and the clocks of the players.
+#[derive(Clone, Debug)]
+pub struct Game {
+    start: Board,
+    board: Board,
+    cursor: usize,
+
+    moves: Vec<Move>,
+    sans: Vec<String>,
+    clocks: Vec<Option<Duration>>,
+
+    headers: Vec<(String, String)>,
+    result: Option<Status>,
+}
+
+// ================================ pub impl
+
+impl Game {
+    /// Creates a new game starting from that position.
+    pub fn new(start: Board) -> Game {
+        Game {
+            board: start.clone(),
+            start,
+            cursor: 0,
+
+            moves: Vec::new(),
+            sans: Vec::new(),
+            clocks: Vec::new(),
+
+            headers: Vec::new(),
+            result: None,
+        }
+    }
+
+    // ================================ Accessers
+
+    /// Returns the start position of the game.
+    #[inline]
+    pub fn get_start(&self) -> &Board {
+        &self.start
+    }
+
+    /// Returns the position at the cursor.
+    #[inline]
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the cursor, the number of moves currently played.
+    #[inline]
+    pub fn get_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns all the moves of the game, undone ones included.
+    #[inline]
+    pub fn get_moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Returns the moves of the game in standard algebraic notation, undone ones included.
+    #[inline]
+    pub fn get_sans(&self) -> &[String] {
+        &self.sans
+    }
+
+    /// Returns the moves currently played, up to the cursor.
+    #[inline]
+    pub fn played(&self) -> &[Move] {
+        &self.moves[..self.cursor]
+    }
+
+    /// Returns the remaining time of the player of the move at that index, if recorded.
+    #[inline]
+    pub fn get_clock(&self, index: usize) -> Option<Duration> {
+        self.clocks.get(index).copied().flatten()
+    }
+
+    /// Returns the headers of the game, in insertion order.
+    #[inline]
+    pub fn get_headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Returns the value of the header with that name, if any.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the result of the game: the one set if any, else the
+    /// status of the position after the last move.
+    pub fn get_result(&self) -> Status {
+        match self.result {
+            Some(result) => result,
+            None if self.cursor == self.moves.len() => self.board.status(),
+            None => self.board_at(self.moves.len()).unwrap().status(),
+        }
+    }
+
+    // ================================ Methods
+
+    /// Sets the header with that name, replacing the previous value.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        match self.headers.iter_mut().find(|(other, _)| other == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.headers.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Sets the result of the game, when it did not end on the board
+    /// (resignation, agreement, time forfeit...). None clears it.
+    pub fn set_result(&mut self, result: Option<Status>) {
+        self.result = result;
+    }
+
+    /// Plays the move at the cursor, checking its legality. If moves were undone,
+    /// they are lost, unless the move is the next one, which is then redone.
+    pub fn play(&mut self, mv: Move) -> Result<()> {
+        self.play_clocked(mv, None)
+    }
+
+    /// Plays the move like play(), recording the remaining time of the player.
+    pub fn play_clocked(&mut self, mv: Move, clock: Option<Duration>) -> Result<()> {
+        if !self.board.is_pseudo_legal(mv) || !self.board.is_legal(mv) {
+            return Err(Error::msg("Move is illegal in this context."));
+        }
+
+        if self.cursor != self.moves.len() {
+            // The move was already played in that timeline, redo it.
+            if self.moves[self.cursor] == mv {
+                self.clocks[self.cursor] = clock.or(self.clocks[self.cursor]);
+                self.redo()?;
+                return Ok(());
+            }
+
+            // Throw out the undone moves, we are changing timeline.
+            self.moves.truncate(self.cursor);
+            self.sans.truncate(self.cursor);
+            self.clocks.truncate(self.cursor);
+        }
+
+        self.sans.push(self.board.to_san(mv));
+        self.moves.push(mv);
+        self.clocks.push(clock);
+
+        self.board.do_move(mv);
+        self.cursor += 1;
+
+        Ok(())
+    }
+
+    /// Undoes the move before the cursor, and returns it.
+    pub fn undo(&mut self) -> Result<Move> {
+        if self.cursor == 0 {
+            return Err(Error::msg("There is no move to undo."));
+        }
+
+        self.cursor -= 1;
+        let mv = self.moves[self.cursor];
+        self.board.undo_move(mv);
+
+        Ok(mv)
+    }
+
+    /// Redoes the move after the cursor, and returns it.
+    pub fn redo(&mut self) -> Result<Move> {
+        if self.cursor == self.moves.len() {
+            return Err(Error::msg("There is no move to redo."));
+        }
+
+        let mv = self.moves[self.cursor];
+        self.board.do_move(mv);
+        self.cursor += 1;
+
+        Ok(mv)
+    }
+
+    /// Moves the cursor to that number of moves played, undoing or redoing moves.
+    pub fn goto(&mut self, ply: usize) -> Result<()> {
+        if ply > self.moves.len() {
+            return Err(Error::msg("There are not that many moves in the game."));
+        }
+
+        while self.cursor > ply {
+            self.undo()?;
+        }
+        while self.cursor < ply {
+            self.redo()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the position after that number of moves were played from the start,
+    /// if there are enough moves. The cursor is left untouched.
+    pub fn board_at(&self, ply: usize) -> Option<Board> {
+        if ply > self.moves.len() {
+            return None;
+        }
+
+        let mut board = self.start.clone();
+        for &mv in &self.moves[..ply] {
+            board.do_move(mv);
+        }
+
+        Some(board)
+    }
+}
@@ -0,0 +1,146 @@
+use core::ops::Index;
+
+#[cfg(all(feature = "alloc", not(feature = "fixed-history")))]
+use alloc::vec::Vec;
+
+//#################################################################################################
+//
+//                                        struct History
+//
+//#################################################################################################
+
+/// A stack of the previous states of a board, growing as needed with the allocator.
+#[cfg(all(feature = "alloc", not(feature = "fixed-history")))]
+#[derive(Clone, Debug)]
+pub(crate) struct History<T, const N: usize> {
+    items: Vec<T>,
+}
+
+/// A stack of the previous states of a board, of fixed capacity. When it is full, the
+/// oldest elements are dropped: moves are then only undoable N plies back.
+#[cfg(not(all(feature = "alloc", not(feature = "fixed-history"))))]
+#[derive(Clone, Debug)]
+pub(crate) struct History<T, const N: usize> {
+    items: [Option<T>; N],
+    start: usize,
+    len: usize,
+}
+
+// ================================ pub(crate) impl
+
+#[cfg(all(feature = "alloc", not(feature = "fixed-history")))]
+impl<T: Copy, const N: usize> History<T, N> {
+    /// Creates an empty history.
+    #[inline]
+    pub(crate) fn new() -> History<T, N> {
+        History {
+            items: Vec::new(),
+        }
+    }
+
+    /// Returns the number of elements in the history.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Pushes an element on top of the history.
+    #[inline]
+    pub(crate) fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    /// Pops the element on top of the history, if any.
+    #[inline]
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+
+    /// Removes every element of the history.
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Returns the element at that index, the oldest being at 0.
+    #[inline]
+    fn get(&self, i: usize) -> &T {
+        &self.items[i]
+    }
+}
+
+#[cfg(not(all(feature = "alloc", not(feature = "fixed-history"))))]
+impl<T: Copy, const N: usize> History<T, N> {
+    /// Creates an empty history.
+    #[inline]
+    pub(crate) fn new() -> History<T, N> {
+        History {
+            items: [None; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements in the history.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Pushes an element on top of the history, dropping the oldest one if it is full.
+    #[inline]
+    pub(crate) fn push(&mut self, item: T) {
+        self.items[(self.start + self.len) % N] = Some(item);
+
+        if self.len == N {
+            self.start = (self.start + 1) % N;
+        } else {
+            self.len += 1;
+        }
+    }
+
+    /// Pops the element on top of the history, if any.
+    #[inline]
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.items[(self.start + self.len) % N].take()
+    }
+
+    /// Removes every element of the history.
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Returns the element at that index, the oldest being at 0.
+    #[inline]
+    fn get(&self, i: usize) -> &T {
+        assert!(i < self.len, "Index out of the history.");
+        self.items[(self.start + i) % N].as_ref().unwrap()
+    }
+}
+
+impl<T: Copy, const N: usize> History<T, N> {
+    /// Iterates over the elements of the history, from the oldest to the newest.
+    #[inline]
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+}
+
+// ================================ traits impl
+
+impl<T: Copy, const N: usize> Index<usize> for History<T, N> {
+    type Output = T;
+
+    /// Returns the element at that index, the oldest being at 0.
+    #[inline]
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+    }
+}
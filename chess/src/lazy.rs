@@ -0,0 +1,102 @@
+use core::cell::UnsafeCell;
+use core::hint;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+// The states of the value.
+const UNINIT: u8 = 0;
+const RUNNING: u8 = 1;
+const READY: u8 = 2;
+const POISONED: u8 = 3;
+
+//#################################################################################################
+//
+//                                         struct Lazy
+//
+//#################################################################################################
+
+/// A value initialized in place on first use, available without the standard library.
+/// The value starts as a constant, usually zeroed so that it lives in the bss, and is
+/// filled by the init function, which runs once while other threads spin. If the init
+/// function panics, the value is poisoned and every access panics as well.
+pub(crate) struct Lazy<T> {
+    state: AtomicU8,
+    value: UnsafeCell<T>,
+    init: fn(&mut T),
+}
+
+// SAFE: the value is only mutated once, by the thread winning the state,
+// and only read once the state is READY.
+unsafe impl<T: Sync> Sync for Lazy<T> {}
+
+// ================================ pub(crate) impl
+
+impl<T> Lazy<T> {
+    /// Creates a lazy value, starting as value and filled by init on first use.
+    pub(crate) const fn new(value: T, init: fn(&mut T)) -> Lazy<T> {
+        Lazy {
+            state: AtomicU8::new(UNINIT),
+            value: UnsafeCell::new(value),
+            init,
+        }
+    }
+}
+
+// ================================ impl
+
+impl<T> Lazy<T> {
+    /// Runs the init function if no other thread did, else waits for it to be done.
+    #[cold]
+    fn force(&self) {
+        match self.state.compare_exchange(UNINIT, RUNNING, Ordering::Acquire, Ordering::Acquire) {
+            Ok(_) => {
+                // Poisons the value if the init function unwinds.
+                let guard = Poison(&self.state);
+                // SAFE: we won the state, no other thread accesses the value until it is READY.
+                (self.init)(unsafe {&mut *self.value.get()});
+                core::mem::forget(guard);
+                self.state.store(READY, Ordering::Release);
+            },
+            Err(_) => loop {
+                match self.state.load(Ordering::Acquire) {
+                    READY => break,
+                    POISONED => panic!("Lazy value poisoned by a panic in its init function."),
+                    _ => hint::spin_loop(),
+                }
+            },
+        }
+    }
+}
+
+// ================================ traits impl
+
+impl<T> Deref for Lazy<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        if self.state.load(Ordering::Acquire) != READY {
+            self.force();
+        }
+
+        // SAFE: the value is READY, it will never be mutated again.
+        unsafe {&*self.value.get()}
+    }
+}
+
+//#################################################################################################
+//
+//                                         struct Poison
+//
+//#################################################################################################
+
+/// A guard poisoning the state of a lazy value when dropped, on unwinding.
+struct Poison<'a>(&'a AtomicU8);
+
+// ================================ traits impl
+
+impl Drop for Poison<'_> {
+    fn drop(&mut self) {
+        self.0.store(POISONED, Ordering::Release);
+    }
+}
\ No newline at end of file
@@ -1,40 +1,62 @@
+//! Chess primitives, boards and move generation. Without the default std feature,
+//! the crate is no_std: the alloc feature enables the types needing an allocator,
+//! and without it the history of boards has a fixed capacity.
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+// Errors.
+pub mod error;
+
 // Primitive types.
 pub mod bitboard;
 pub mod color;
 pub mod moves;
 pub mod piece;
 pub mod square;
+pub mod variant;
 pub mod zobrist;
 
 // Logic modules.
-mod attacks;
+pub mod attacks;
 mod castle_rights;
 mod en_passant;
 mod cuckoo;
+mod history;
+mod lazy;
+
+// Serde support.
+#[cfg(feature = "serde")]
+mod serde_utils;
 
 // Board type.
 pub mod board;
+pub mod builder;
 pub mod movegen;
 
+// Game record.
+#[cfg(feature = "alloc")]
+pub mod game;
+
+// Move generation testing.
+#[cfg(feature = "std")]
+pub mod perft;
+
+// Test suites.
+#[cfg(feature = "alloc")]
+pub mod epd;
+
+// Rendering.
+#[cfg(feature = "alloc")]
+pub mod svg;
+
 pub mod prelude {
     pub use crate::board::{Board, Status}; 
     pub use crate::color::Color;
     pub use crate::moves::Move;
+    pub use crate::variant::Variant;
     pub use crate::movegen;
 }
-
-/// Initializes the components of the chess lib.
-/// Must be called before using the methods of the chess lib.
-#[cold]
-pub fn init() {
-    use std::sync::Once;
-
-    static INIT: Once = Once::new();
-
-    INIT.call_once(|| unsafe {
-        bitboard::init();
-        zobrist::init();
-        attacks::init();
-        cuckoo::init();
-    });
-}
\ No newline at end of file
@@ -1,3 +1,6 @@
+use core::fmt;
+use core::ops::Deref;
+
 use crate::attacks;
 use crate::bitboard::BitBoard;
 use crate::board::Board;
@@ -105,7 +108,7 @@ pub fn gen_pushes(board: &Board, mut gen: impl FnMut(Square, Square, bool)) {
 
 // ================================ king pseudo-legals
 
-/// Gives all pseudo-legals captures from the king.
+/// Gives all pseudo-legals captures from the king (or kings, in antichess).
 /// The provided closure takes three arguments: from square, to square
 /// and the captured piece.
 /// It is called for each pseudo-legal capture from the king.
@@ -113,22 +116,24 @@ pub fn gen_pushes(board: &Board, mut gen: impl FnMut(Square, Square, bool)) {
 pub fn gen_king_captures(board: &Board, mut gen: impl FnMut(Square, Square, Piece)) {
     let them_occ = board.get_occupancy().colored(board.get_other_side());
 
-    let from = board.king_sq();
-    for to in (attacks::king(from) & them_occ).iter_squares() {
-        gen(from, to, board.get_piece_unchecked(to));
+    for from in board.get_bitboard(board.get_side_to_move(), Piece::King).iter_squares() {
+        for to in (attacks::king(from) & them_occ).iter_squares() {
+            gen(from, to, board.get_piece_unchecked(to));
+        }
     }
 }
 
-/// Gives all pseudo-legals quiets from the king.
+/// Gives all pseudo-legals quiets from the king (or kings, in antichess).
 /// The provided closure takes two arguments: from square and to square.
 /// It is called for each pseudo-legal quiets from the king.
 #[inline]
 pub fn gen_king_quiets(board: &Board, mut gen: impl FnMut(Square, Square)) {
     let free = board.get_occupancy().free();
 
-    let from = board.king_sq();
-    for to in (attacks::king(from) & free).iter_squares() {
-        gen(from, to);
+    for from in board.get_bitboard(board.get_side_to_move(), Piece::King).iter_squares() {
+        for to in (attacks::king(from) & free).iter_squares() {
+            gen(from, to);
+        }
     }
 }
 
@@ -228,24 +233,58 @@ pub fn gen_quiets(board: &Board, mut gen: impl FnMut(Square, Square)) {
     }
 }
 
+// ================================ drops
+
+/// Gives all pseudo-legals drops of the pieces in our pocket, in crazyhouse.
+/// The provided closure takes two arguments: the dropped piece and to square.
+/// It is called for each pseudo-legal drop.
+#[inline]
+pub fn gen_drops(board: &Board, mut gen: impl FnMut(Piece, Square)) {
+    let us = board.get_side_to_move();
+    let free = board.get_occupancy().free();
+
+    for &piece in &Piece::PIECES[..5] {
+        if board.get_pocket(us, piece) == 0 {
+            continue;
+        }
+
+        // Pawns can't be dropped on the first and last ranks.
+        let targets = if piece == Piece::Pawn {free & !(BitBoard::RANK_1 | BitBoard::RANK_8)} else {free};
+        for to in targets.iter_squares() {
+            gen(piece, to);
+        }
+    }
+}
+
 //#################################################################################################
 //
 //                                         Legals Generation
 //
 //#################################################################################################
 
-/// Generates all legal moves for the current position.
+/// Generates all legal moves for the current position, into a Vec or a MoveList.
 /// This function is rather slow. Use the other movegen functions
 /// for more control over generation and better performance.
-pub fn legals(board: &Board, list: &mut Vec<Move>) {
+pub fn legals(board: &Board, list: &mut impl Extend<Move>) {
+    // The game was won through a rule of the variant, there are no moves left.
+    if board.get_variant_winner().is_some() {
+        return;
+    }
+
     // Bitboard of the checkers.
     let checkers = board.get_checkers();
+
+    // The pieces a pawn may promote to.
+    let promotes = board.get_variant().promotes();
     
     // A closure to check that the move is legal before pushing it
     // to the list.
-    let mut push = |mv| if board.is_legal(mv) {list.push(mv)};
+    let mut push = |mv| if board.is_legal(mv) {list.extend(Some(mv))};
+
+    // Without the usual check rules, every pseudo-legal move is tried.
+    let checkers_count = if board.get_variant().has_standard_checks() {checkers.count()} else {0};
 
-    match checkers.count() {
+    match checkers_count {
         0 => {
             // King moves.
             gen_castles(board, |from, to| push(Move::castle(from, to)));
@@ -254,14 +293,14 @@ pub fn legals(board: &Board, list: &mut Vec<Move>) {
 
             // Pawn moves.
             gen_promote_captures(board, |from, to, capture| {
-                for promote in Piece::PROMOTES {
+                for &promote in promotes {
                     push(Move::promote_capture(from, to, capture, promote));
                 }
             });
             gen_en_passant(board, |from, to| push(Move::en_passant(from, to)));
             gen_pawn_captures(board, |from, to, capture| push(Move::capture(from, to, capture)));
             gen_promotes(board, |from, to| {
-                for promote in Piece::PROMOTES {
+                for &promote in promotes {
                     push(Move::promote(from, to, promote));
                 }
             });
@@ -276,6 +315,7 @@ pub fn legals(board: &Board, list: &mut Vec<Move>) {
             // Other moves.
             gen_captures(board, |from, to, capture| push(Move::capture(from, to, capture)));
             gen_quiets(board, |from, to| push(Move::quiet(from, to)));
+            gen_drops(board, |piece, to| push(Move::drop(piece, to)));
         },
         1 => {
             // King moves.
@@ -287,18 +327,18 @@ pub fn legals(board: &Board, list: &mut Vec<Move>) {
             // Or that the king itself is moving.
             let checker = unsafe {checkers.as_square_unchecked()};
             let mask = BitBoard::between(board.king_sq(), checker) | checkers;
-            let mut push = |mv: Move| if mask.contains(mv.to()) && board.is_legal(mv) {list.push(mv)};
+            let mut push = |mv: Move| if mask.contains(mv.to()) && board.is_legal(mv) {list.extend(Some(mv))};
 
             // Pawn moves.
             gen_promote_captures(board, |from, to, capture| {
-                for promote in Piece::PROMOTES {
+                for &promote in promotes {
                     push(Move::promote_capture(from, to, capture, promote));
                 }
             });
             gen_en_passant(board, |from, to| push(Move::en_passant(from, to)));
             gen_pawn_captures(board, |from, to, capture| push(Move::capture(from, to, capture)));
             gen_promotes(board, |from, to| {
-                for promote in Piece::PROMOTES {
+                for &promote in promotes {
                     push(Move::promote(from, to, promote));
                 }
             });
@@ -313,6 +353,7 @@ pub fn legals(board: &Board, list: &mut Vec<Move>) {
             // Other moves.
             gen_captures(board, |from, to, capture| push(Move::capture(from, to, capture)));
             gen_quiets(board, |from, to| push(Move::quiet(from, to)));
+            gen_drops(board, |piece, to| push(Move::drop(piece, to)));
         },
         2 => {
             // King captures.
@@ -322,4 +363,83 @@ pub fn legals(board: &Board, list: &mut Vec<Move>) {
         },
         _ => unreachable!(),
     }
-}
\ No newline at end of file
+}
+
+//#################################################################################################
+//
+//                                        struct MoveList
+//
+//#################################################################################################
+
+/// A list of moves of fixed capacity, large enough for the legal moves of any position,
+/// drops included. It does not need an allocator.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MoveList::CAPACITY],
+    len: usize,
+}
+
+// ================================ pub impl
+
+impl MoveList {
+    /// The capacity of a list.
+    pub const CAPACITY: usize = 512;
+
+    /// Creates an empty list.
+    #[inline]
+    pub fn new() -> MoveList {
+        MoveList {
+            moves: [Move::quiet(Square::A1, Square::A1); MoveList::CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Pushes a move at the end of the list. Panics if the list is full.
+    #[inline]
+    pub fn push(&mut self, mv: Move) {
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    /// Removes all the moves of the list.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+// ================================ traits impl
+
+impl Default for MoveList {
+    /// Returns an empty list.
+    fn default() -> MoveList {
+        MoveList::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    /// Returns the moves of the list.
+    #[inline]
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl Extend<Move> for MoveList {
+    /// Pushes the moves at the end of the list.
+    #[inline]
+    fn extend<I: IntoIterator<Item = Move>>(&mut self, iter: I) {
+        for mv in iter {
+            self.push(mv);
+        }
+    }
+}
+
+impl fmt::Debug for MoveList {
+    /// Formats the moves of the list.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
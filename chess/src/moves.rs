@@ -1,7 +1,14 @@
-use std::fmt;
-use std::sync::atomic::AtomicU32;
-use std::sync::atomic::Ordering;
+use core::fmt;
+use core::sync::atomic::AtomicU32;
+use core::sync::atomic::Ordering;
 
+#[cfg(feature = "serde")]
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+#[cfg(feature = "alloc")]
+use crate::board::Board;
 use crate::piece::Piece;
 use crate::prelude::Color;
 use crate::square::Square;
@@ -20,9 +27,10 @@ const fn base(flags: u32, from: Square, to: Square) -> u32 {
 
 /// A move, encoded in a compact 32 bits representation. 
 /// In big endian, the encoding is done like that:
-/// pppcccttttttffffffmmmmm, where m is the type of the move, 
-/// f is the from square, t is the to square, c is the captured piece
-/// and p is the promote piece.
+/// dpppcccttttttffffffmmmmm, where m is the type of the move, 
+/// f is the from square, t is the to square, c is the captured piece,
+/// p is the promote piece and d is set for drops. A drop stores the
+/// dropped piece as the promote piece, and its square as both from and to.
 #[repr(transparent)]
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub struct Move(u32);
@@ -72,10 +80,16 @@ impl Move {
         Move(base(Move::CASTLE, from, to))
     }
 
+    /// Creates a drop move, in crazyhouse.
+    #[inline]
+    pub const fn drop(piece: Piece, to: Square) -> Move {
+        Move(base(Move::DROP, to, to) | (piece as u32) << 20)
+    }
+
     /// Returns true if the move is quiet.
     #[inline]
     pub const fn is_quiet(self) -> bool {
-        self.0 & 0b11111 == 0
+        self.0 & (0b11111 | Move::DROP) == 0
     }
 
     /// Returns true if the move is a capture.
@@ -108,6 +122,12 @@ impl Move {
         self.0 & Move::DOUBLE_PUSH != 0
     }
 
+    /// Returns true if the move is a drop.
+    #[inline]
+    pub const fn is_drop(self) -> bool {
+        self.0 & Move::DROP != 0
+    }
+
     /// Returns the from square of the move.
     #[inline]
     pub fn from(self) -> Square {
@@ -149,11 +169,28 @@ impl Move {
         }
     }
 
+    /// Returns the dropped piece of the move.
+    #[inline]
+    pub fn get_drop(self) -> Piece {
+        self.get_promote()
+    }
+
     /// Returns the raw value of the move.
     #[inline]
     pub fn get_raw(self) -> u32 {
         self.0
     }
+
+    /// Binds the move to the board it is played on, so that it may be displayed
+    /// or serialized in standard algebraic notation. Assumes the move is legal.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn san(self, board: &Board) -> San<'_> {
+        San {
+            board,
+            mv: self,
+        }
+    }
 }
 
 // ================================ impl
@@ -165,7 +202,8 @@ impl Move {
     const PROMOTE     : u32 = 0b00010;
     const CASTLE      : u32 = 0b00100;
     const EN_PASSANT  : u32 = 0b01000;
-    const DOUBLE_PUSH : u32 = 0b10000;    
+    const DOUBLE_PUSH : u32 = 0b10000;
+    const DROP        : u32 = 1 << 23;
 }
 
 // ================================ traits impl
@@ -173,7 +211,9 @@ impl Move {
 impl fmt::Display for Move {
     /// Displays a move using pure algebraic coordinate notation.
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        if self.is_promote() {
+        if self.is_drop() {
+            write!(fmt, "{}@{}", self.get_drop().as_char(Color::White), self.to())
+        } else if self.is_promote() {
             write!(fmt, "{}{}{}", self.from(), self.to(), self.get_promote().as_char(Color::Black))
         } else {
             write!(fmt, "{}{}", self.from(), self.to())
@@ -193,10 +233,91 @@ impl fmt::Debug for Move {
             .field("is_castle", &self.is_castle())
             .field("is_en_passant", &self.is_en_passant())
             .field("is_double_push", &self.is_double_push())
+            .field("is_drop", &self.is_drop())
             .finish()
     }
 }
 
+#[cfg(feature = "serde")]
+impl Serialize for Move {
+    /// Serializes the move using pure algebraic coordinate notation.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+//#################################################################################################
+//
+//                                         struct San
+//
+//#################################################################################################
+
+/// A move bound to the board it is played on, displayed and serialized
+/// in standard algebraic notation.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug)]
+pub struct San<'a> {
+    board: &'a Board,
+    mv: Move,
+}
+
+// ================================ traits impl
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for San<'_> {
+    /// Displays the move using standard algebraic notation.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.board.to_san(self.mv))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for San<'_> {
+    /// Serializes the move using standard algebraic notation.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+//#################################################################################################
+//
+//                                       struct MoveSeed
+//
+//#################################################################################################
+
+/// Since a move can only be decoded in the context of a board, moves are deserialized
+/// through this seed. It accepts both pure algebraic coordinate notation and standard
+/// algebraic notation, and only yields moves that are legal on the board.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug)]
+pub struct MoveSeed<'a>(pub &'a Board);
+
+// ================================ traits impl
+
+#[cfg(feature = "serde")]
+impl<'de> DeserializeSeed<'de> for MoveSeed<'_> {
+    type Value = Move;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Move, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for MoveSeed<'_> {
+    type Value = Move;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a legal move in pure algebraic coordinate or standard algebraic notation")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Move, E> {
+        self.0.parse_move(s)
+            .or_else(|_| self.0.parse_san(s))
+            .map_err(E::custom)
+    }
+}
+
 //#################################################################################################
 //
 //                                    struct AtomicMove
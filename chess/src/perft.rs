@@ -1,139 +1,367 @@
-use std::env;
-use std::str::FromStr;
+use std::ops::{Add, AddAssign};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::thread;
+use std::vec::Vec;
 
-use chess::prelude::*;
-
-/// The usage/help of this binary.
-const USAGE: &str = r#"The goal of this binary is to be used by perftree (https://github.com/agausmann/perftree) and to help debug the move generator.
-It can also be used for profiling.
-
-Usage: 
-  ./target/release/perft <depth> <fen> <moves>
-    <depth> : The depth at which the perft needs to be carried.
-    <fen>   : the fen string to be used, put it into quotes.
-    <moves> : (optional) a list of space seperated moves, in pure algebraic
-               coordinates notation, to be performed before node counting.
-               Needs to be a single arguments, use quotes.
-
-Example:
-  ./target/release/perft 3 "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
-
-For profiling with perf:
-  cargo build --bin perft --release
-  perf record --call-graph dwarf target/release/perft 3 "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
-  perf report"#;
-
-// The perft algorithm, counting the number of leaf nodes.
-fn perft(board: &mut Board, depth: usize) -> u64 {
-    let mut list = Vec::new();
-    movegen::legals(board, &mut list);
-    
-    if depth == 1 {
-        return list.len() as u64;
+use crate::bitboard::BitBoard;
+use crate::board::Board;
+use crate::en_passant::EnPassantSquare;
+use crate::movegen;
+use crate::moves::Move;
+use crate::square::Square;
+
+//#################################################################################################
+//
+//                                       struct PerftStats
+//
+//#################################################################################################
+
+/// The statistics gathered by a perft run, counted at the leaves of the tree.
+/// When statistics are not requested, only the nodes field is filled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passants: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub discovered_checks: u64,
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
+
+// ================================ impl
+
+impl PerftStats {
+    /// Creates statistics counting only nodes.
+    #[inline]
+    fn from_nodes(nodes: u64) -> PerftStats {
+        PerftStats {
+            nodes,
+            ..PerftStats::default()
+        }
     }
 
-    let mut nodes = 0;
-    
-    for &mv in list.iter() {
+    /// Records a leaf move, played on the given board.
+    fn record(&mut self, board: &mut Board, mv: Move) {
+        self.nodes += 1;
+
+        if mv.is_capture() || mv.is_en_passant() {
+            self.captures += 1;
+        }
+        if mv.is_en_passant() {
+            self.en_passants += 1;
+        }
+        if mv.is_castle() {
+            self.castles += 1;
+        }
+        if mv.is_promote() {
+            self.promotions += 1;
+        }
+
+        // The squares of the pieces that moved: a check from any other
+        // square was discovered.
+        let moved = BitBoard::from(mv.to()) | if mv.is_castle() {
+            match mv.to() {
+                Square::G1 => Square::F1,
+                Square::G8 => Square::F8,
+                Square::C1 => Square::D1,
+                Square::C8 => Square::D8,
+                _ => unreachable!(),
+            }.into()
+        } else {
+            BitBoard::EMPTY
+        };
+
         board.do_move(mv);
-        nodes += perft(board, depth - 1);
+
+        let checkers = board.get_checkers();
+        if checkers.not_empty() {
+            self.checks += 1;
+
+            // Double checks are not counted as discovered checks.
+            if checkers.more_than_one() {
+                self.double_checks += 1;
+            } else if (checkers & !moved).not_empty() {
+                self.discovered_checks += 1;
+            }
+
+            let mut list = Vec::new();
+            movegen::legals(board, &mut list);
+            if list.is_empty() {
+                self.checkmates += 1;
+            }
+        }
+
         board.undo_move(mv);
     }
+}
+
+// ================================ traits impl
+
+impl Add<PerftStats> for PerftStats {
+    type Output = PerftStats;
 
-    nodes
+    #[inline]
+    fn add(mut self, rhs: PerftStats) -> PerftStats {
+        self += rhs;
+        self
+    }
 }
 
-fn main() {
-    // Initialize the chess library.
-    chess::init();
-
-    // Get the arguments.
-    let mut args = env::args();
-    
-    // Executable path.
-    args.next().expect("Can't get executable path.");
-
-    // Parse depth.
-    let arg = args.next();
-    if arg.is_none() {
-        println!("{}", USAGE);
-        return;
+impl AddAssign<PerftStats> for PerftStats {
+    #[inline]
+    fn add_assign(&mut self, rhs: PerftStats) {
+        self.nodes += rhs.nodes;
+        self.captures += rhs.captures;
+        self.en_passants += rhs.en_passants;
+        self.castles += rhs.castles;
+        self.promotions += rhs.promotions;
+        self.checks += rhs.checks;
+        self.discovered_checks += rhs.discovered_checks;
+        self.double_checks += rhs.double_checks;
+        self.checkmates += rhs.checkmates;
     }
-    let depth = match usize::from_str(arg.unwrap().as_str()) {
-        Ok(n) => n,
-        Err(err) => {
-            println!("{}", err);
-            return;
+}
+
+//#################################################################################################
+//
+//                                       struct PerftTable
+//
+//#################################################################################################
+
+/// An entry of the perft table. The key is stored xored with the node count,
+/// so that an entry torn by concurrent writes is detected on probing.
+#[derive(Debug, Default)]
+struct PerftEntry {
+    check: AtomicU64,
+    nodes: AtomicU64,
+}
+
+/// A lock-less hash table of node counts, shared between the perft threads.
+#[derive(Debug)]
+struct PerftTable {
+    entries: Vec<PerftEntry>,
+}
+
+// ================================ impl
+
+impl PerftTable {
+    /// Creates a new table using at most the given number of megabytes.
+    /// A size of 0 disables the table.
+    fn new(megabytes: usize) -> PerftTable {
+        let len = (megabytes << 20) / std::mem::size_of::<PerftEntry>();
+        let len = if len == 0 {0} else {1 << (usize::BITS - 1 - len.leading_zeros())};
+
+        PerftTable {
+            entries: (0..len).map(|_| PerftEntry::default()).collect(),
         }
-    };
-    if !(1..12).contains(&depth) {
-        println!("<depth>, must be comprised between 1 and 12 inclusive.");
-        return;
     }
 
-    // fen position.
-    let arg = args.next();
-    if arg.is_none() {
-        println!("{}", USAGE);
-        return;
+    /// Computes the key of the board for a perft of that depth. The board's zobrist
+    /// key does not include castling rights, en passant nor the variant, so we mix them in.
+    #[inline]
+    fn key(board: &Board, depth: usize) -> u64 {
+        let ep = match board.get_ep_square() {
+            EnPassantSquare::Some(sq) => 1 + usize::from(sq) as u64,
+            EnPassantSquare::None => 0,
+        };
+        let extra = u64::from(board.get_castle_rights().get_raw()) | ep << 4 | (board.get_variant() as u64) << 11 | (depth as u64) << 14;
+
+        // The splitmix64 finalizer, to spread the extra bits over the whole key.
+        let mut x = extra.wrapping_add(0x9E3779B97F4A7C15);
+        x = (x ^ x >> 30).wrapping_mul(0xBF58476D1CE4E5B9);
+        x = (x ^ x >> 27).wrapping_mul(0x94D049BB133111EB);
+        board.get_zobrist().get_raw() ^ x ^ x >> 31
+    }
+
+    /// Probes the table for the node count associated with that key.
+    #[inline]
+    fn probe(&self, key: u64) -> Option<u64> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let entry = &self.entries[key as usize & (self.entries.len() - 1)];
+        let nodes = entry.nodes.load(Ordering::Relaxed);
+
+        if entry.check.load(Ordering::Relaxed) ^ nodes == key {
+            Some(nodes)
+        } else {
+            None
+        }
     }
-    let fen = arg.unwrap();
-    let mut board = match Board::new(&fen) {
-        Ok(board) => board,
-        Err(err) => {
-            println!("{}", err);
+
+    /// Stores the node count associated with that key, always replacing the previous entry.
+    #[inline]
+    fn store(&self, key: u64, nodes: u64) {
+        if self.entries.is_empty() {
             return;
         }
-    };
 
-    // Moves to apply.
-    if let Some(arg) = args.next() {
-        for s in arg.split(' ') {
-            match board.parse_move(s) {
-                Ok(mv) => board.do_move(mv),
-                Err(err) => {
-                    println!("{}", err);
-                    return;
+        let entry = &self.entries[key as usize & (self.entries.len() - 1)];
+        entry.nodes.store(nodes, Ordering::Relaxed);
+        entry.check.store(key ^ nodes, Ordering::Relaxed);
+    }
+}
+
+//#################################################################################################
+//
+//                                          struct Perft
+//
+//#################################################################################################
+
+/// A perft runner, counting the leaves of the legal move tree of a position.
+/// The root moves are split between the threads, and node counts are cached
+/// in a shared hash table. Leaves are bulk-counted when statistics are not requested.
+#[derive(Debug)]
+pub struct Perft {
+    threads: usize,
+    stats: bool,
+    table: PerftTable,
+}
+
+// ================================ pub impl
+
+impl Perft {
+    /// Creates a new perft runner, using the given number of threads and megabytes
+    /// of hash table (0 disables the hash table). If stats is true, the full statistics
+    /// are gathered. In that case every leaf must be played, so the hash table is not allocated.
+    pub fn new(threads: usize, hash: usize, stats: bool) -> Perft {
+        Perft {
+            threads: threads.max(1),
+            stats,
+            table: PerftTable::new(if stats {0} else {hash}),
+        }
+    }
+
+    /// Returns true if the node counts are cached in a hash table.
+    #[inline]
+    pub fn has_table(&self) -> bool {
+        !self.table.entries.is_empty()
+    }
+
+    /// Runs the perft to the given depth, and returns the statistics of the subtree
+    /// of each root move, in the order of generation.
+    pub fn divide(&self, board: &Board, depth: usize) -> Vec<(Move, PerftStats)> {
+        let mut list = Vec::new();
+        movegen::legals(board, &mut list);
+
+        if depth <= 1 {
+            return list.into_iter().map(|mv| {
+                let mut stats = PerftStats::default();
+                if self.stats {
+                    stats.record(&mut board.clone(), mv);
+                } else {
+                    stats.nodes = 1;
                 }
-            };
+                (mv, stats)
+            }).collect();
         }
+
+        // Each thread picks the next unexplored root move until there are none.
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(std::vec![PerftStats::default(); list.len()]);
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(list.len()) {
+                scope.spawn(|| {
+                    let mut board = board.clone();
+
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= list.len() {
+                            break;
+                        }
+
+                        board.do_move(list[i]);
+                        let stats = self.subtree(&mut board, depth - 1);
+                        board.undo_move(list[i]);
+
+                        results.lock().unwrap()[i] = stats;
+                    }
+                });
+            }
+        });
+
+        list.into_iter().zip(results.into_inner().unwrap()).collect()
     }
 
-    // Compute the legal moves of the starting position.
-    let mut list = Vec::new();
-    movegen::legals(&board, &mut list);
+    /// Runs the perft to the given depth and returns the statistics of the whole tree.
+    pub fn run(&self, board: &Board, depth: usize) -> PerftStats {
+        if depth == 0 {
+            return PerftStats::from_nodes(1);
+        }
 
-    // The total number of nodes.
-    let mut total = 0;
-    
-    if depth == 1 {
-        // Special case if depth is only one.
-        for &mv in list.iter() {
-            println!("{} 1", mv);
+        self.divide(board, depth).into_iter()
+            .fold(PerftStats::default(), |acc, (_, stats)| acc + stats)
+    }
+}
+
+// ================================ impl
+
+impl Perft {
+    /// Returns the statistics of the tree of given depth rooted at the board.
+    fn subtree(&self, board: &mut Board, depth: usize) -> PerftStats {
+        if self.stats {
+            let mut stats = PerftStats::default();
+            Perft::gather(board, depth, &mut stats);
+            stats
+        } else {
+            PerftStats::from_nodes(self.count(board, depth))
+        }
+    }
+
+    /// Counts the leaves of the tree, bulk-counting at depth 1 and
+    /// using the hash table for the inner nodes.
+    fn count(&self, board: &mut Board, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
         }
 
-        total = list.len() as u64;
-    } else {
-        // Launch a thread for each move.
-        let mut handles = Vec::new();
+        let mut list = Vec::new();
+        movegen::legals(board, &mut list);
 
-        for &mv in list.iter() {
-            let mut board = board.clone();
+        if depth == 1 {
+            return list.len() as u64;
+        }
 
-            handles.push(thread::spawn(move || {
-                board.do_move(mv);
-                perft(&mut board, depth - 1)
-            }));
+        let key = PerftTable::key(board, depth);
+        if let Some(nodes) = self.table.probe(key) {
+            return nodes;
         }
 
-        for (handle, mv) in handles.into_iter().zip(list) {
-            let count = handle.join().unwrap();
-            println!("{} {}", mv, count);
-            total += count;
+        let mut nodes = 0;
+        for &mv in list.iter() {
+            board.do_move(mv);
+            nodes += self.count(board, depth - 1);
+            board.undo_move(mv);
         }
+
+        self.table.store(key, nodes);
+        nodes
     }
 
-    // Print the total after an empty line.
-    println!("\n{}", total);
+    /// Gathers the full statistics of the tree, playing every leaf move.
+    fn gather(board: &mut Board, depth: usize, stats: &mut PerftStats) {
+        if depth == 0 {
+            stats.nodes += 1;
+            return;
+        }
+
+        let mut list = Vec::new();
+        movegen::legals(board, &mut list);
+
+        for &mv in list.iter() {
+            if depth == 1 {
+                stats.record(board, mv);
+            } else {
+                board.do_move(mv);
+                Perft::gather(board, depth - 1, stats);
+                board.undo_move(mv);
+            }
+        }
+    }
 }
@@ -1,6 +1,10 @@
-use anyhow::{Error, Result};
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::color::Color;
+use crate::error::{Error, Result};
 
 //#################################################################################################
 //
@@ -98,4 +102,34 @@ impl From<Piece> for usize {
     fn from(piece: Piece) -> usize {
         piece as usize
     }
+}
+
+impl FromStr for Piece {
+    type Err = Error;
+
+    /// Tries to parse a piece from it's letter, in either case.
+    fn from_str(s: &str) -> Result<Piece> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(Piece::from_char(c)?.1),
+            _ => Err(Error::msg("a piece should be exactly one character long")),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Piece {
+    /// Serializes the piece as it's lower case letter.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.as_char(Color::Black))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Piece {
+    /// Deserializes the piece from it's letter, in either case.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Piece, D::Error> {
+        crate::serde_utils::deserialize_from_str(deserializer, "a piece letter such as \"q\"")
+    }
 }
\ No newline at end of file
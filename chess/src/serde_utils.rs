@@ -0,0 +1,51 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+use serde::de::{self, Deserializer, Visitor};
+
+//#################################################################################################
+//
+//                                     struct FromStrVisitor
+//
+//#################################################################################################
+
+/// A visitor parsing a string into any type implementing FromStr.
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    phantom: PhantomData<T>,
+}
+
+// ================================ traits impl
+
+impl<'de, T> Visitor<'de> for FromStrVisitor<T> where T: FromStr, T::Err: fmt::Display {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<T, E> {
+        T::from_str(s).map_err(E::custom)
+    }
+}
+
+//#################################################################################################
+//
+//                                          functions
+//
+//#################################################################################################
+
+/// Deserializes a value from a string, using it's FromStr implementation.
+/// The expecting string is used in error messages.
+pub(crate) fn deserialize_from_str<'de, D, T>(deserializer: D, expecting: &'static str) -> Result<T, D::Error> 
+where 
+    D: Deserializer<'de>, 
+    T: FromStr, 
+    T::Err: fmt::Display,
+{
+    deserializer.deserialize_str(FromStrVisitor {
+        expecting,
+        phantom: PhantomData,
+    })
+}
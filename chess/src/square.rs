@@ -1,10 +1,12 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
-use anyhow::{Error, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::bitboard::BitBoard;
 use crate::color::Color;
+use crate::error::{Error, Result};
 
 //#################################################################################################
 //
@@ -80,12 +82,12 @@ impl Square {
     }
 
     /// Displaces the square by dx, dy, return None if the square is off the board.
-    pub fn displace(self, (dx, dy): (i8, i8)) -> Option<Square> {
-        let x = self.x() as i8 + dx;
-        let y = self.y() as i8 + dy;
+    pub const fn displace(self, (dx, dy): (i8, i8)) -> Option<Square> {
+        let x = self.x() + dx;
+        let y = self.y() + dy;
 
-        if (0..8).contains(&x) && (0..8).contains(&y) {
-            Some(Square::from((x, y)))
+        if 0 <= x && x < 8 && 0 <= y && y < 8 {
+            Some(Square::SQUARES[(x + 8*y) as usize])
         } else {
             None
         }
@@ -166,4 +168,20 @@ impl FromStr for Square {
             Err(Error::msg("a square should be exactly 2 characters long"))
         }
     }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Square {
+    /// Serializes the square in pure algebraic coordinates notation.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Square {
+    /// Deserializes the square from pure algebraic coordinates notation.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Square, D::Error> {
+        crate::serde_utils::deserialize_from_str(deserializer, "a square such as \"e4\"")
+    }
 }
\ No newline at end of file
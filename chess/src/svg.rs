@@ -0,0 +1,241 @@
+use core::fmt;
+
+use alloc::{string::{String, ToString}, vec::Vec};
+
+use crate::board::Board;
+use crate::color::Color;
+use crate::moves::Move;
+use crate::square::Square;
+
+// The size of a square, in the units of the view box.
+const SQUARE: i32 = 45;
+// The glyphs of the pieces, filled with the color of the piece. There is no piece artwork
+// in www/public/svg to embed, only the logo and the favicon, so the pieces are text: their
+// look depends on the fonts of the viewer. Each glyph is followed by the text presentation
+// selector, lest some platforms draw the pawn as an emoji.
+const GLYPHS: [&str; 6] = ["♟\u{fe0e}", "♜\u{fe0e}", "♞\u{fe0e}", "♝\u{fe0e}", "♛\u{fe0e}", "♚\u{fe0e}"];
+// The fonts tried for the glyphs, the ones known to draw them first.
+const FONTS: &str = "DejaVu Sans, Segoe UI Symbol, Noto Sans Symbols 2, serif";
+
+//#################################################################################################
+//
+//                                         struct Theme
+//
+//#################################################################################################
+
+/// The colors used to render a board, as svg colors like "#f0d9b5".
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Theme {
+    pub light: String,
+    pub dark: String,
+    pub white_pieces: String,
+    pub black_pieces: String,
+    pub coordinates: String,
+    pub last_move: String,
+    pub check: String,
+    pub arrow: String,
+    pub mark: String,
+}
+
+// ================================ traits impl
+
+impl Default for Theme {
+    /// Returns the usual brown theme.
+    fn default() -> Theme {
+        Theme {
+            light: "#f0d9b5".to_string(),
+            dark: "#b58863".to_string(),
+            white_pieces: "#fff".to_string(),
+            black_pieces: "#000".to_string(),
+            coordinates: "#6b5040".to_string(),
+            last_move: "#cdd16a".to_string(),
+            check: "#e83c3c".to_string(),
+            arrow: "#15781b".to_string(),
+            mark: "#15781b".to_string(),
+        }
+    }
+}
+
+//#################################################################################################
+//
+//                                          struct Svg
+//
+//#################################################################################################
+
+/// A renderer of a board to a standalone svg image, with optional highlights of the
+/// last move and of the king in check, arrows and marked squares. The pieces are drawn
+/// with the unicode chess glyphs, so they look like the fonts of the viewer draw them.
+#[derive(Clone, Debug)]
+pub struct Svg<'a> {
+    board: &'a Board,
+    size: u32,
+    flipped: bool,
+    coordinates: bool,
+    last_move: Option<Move>,
+    arrows: Vec<(Square, Square)>,
+    marks: Vec<Square>,
+    theme: Theme,
+}
+
+// ================================ pub impl
+
+impl<'a> Svg<'a> {
+    /// Creates a renderer of that board, seen from white's side,
+    /// with coordinates and the default theme.
+    pub fn new(board: &'a Board) -> Svg<'a> {
+        Svg {
+            board,
+            size: 360,
+            flipped: false,
+            coordinates: true,
+            last_move: None,
+            arrows: Vec::new(),
+            marks: Vec::new(),
+            theme: Theme::default(),
+        }
+    }
+
+    /// Sets the width and height of the image, in pixels.
+    pub fn size(&mut self, size: u32) -> &mut Svg<'a> {
+        self.size = size;
+        self
+    }
+
+    /// Shows the board from black's side if true.
+    pub fn flipped(&mut self, flipped: bool) -> &mut Svg<'a> {
+        self.flipped = flipped;
+        self
+    }
+
+    /// Shows the coordinates on the edge squares if true.
+    pub fn coordinates(&mut self, coordinates: bool) -> &mut Svg<'a> {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Highlights the squares of the last move played.
+    pub fn last_move(&mut self, mv: Move) -> &mut Svg<'a> {
+        self.last_move = Some(mv);
+        self
+    }
+
+    /// Draws an arrow between the two squares.
+    pub fn arrow(&mut self, from: Square, to: Square) -> &mut Svg<'a> {
+        self.arrows.push((from, to));
+        self
+    }
+
+    /// Marks the square with a circle.
+    pub fn mark(&mut self, sq: Square) -> &mut Svg<'a> {
+        self.marks.push(sq);
+        self
+    }
+
+    /// Sets the colors of the image.
+    pub fn theme(&mut self, theme: Theme) -> &mut Svg<'a> {
+        self.theme = theme;
+        self
+    }
+
+    /// Renders the svg image.
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ================================ impl
+
+impl<'a> Svg<'a> {
+    /// Returns the coordinates of the top left corner of that square in the image.
+    fn corner(&self, sq: Square) -> (i32, i32) {
+        let (x, y) = (i32::from(sq.x()), i32::from(sq.y()));
+
+        if self.flipped {
+            ((7 - x) * SQUARE, y * SQUARE)
+        } else {
+            (x * SQUARE, (7 - y) * SQUARE)
+        }
+    }
+
+    /// Returns the coordinates of the center of that square in the image.
+    fn center(&self, sq: Square) -> (i32, i32) {
+        let (x, y) = self.corner(sq);
+        (x + SQUARE / 2, y + SQUARE / 2)
+    }
+}
+
+// ================================ traits impl
+
+impl<'a> fmt::Display for Svg<'a> {
+    /// Writes the svg image.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = &self.theme;
+        let width = 8 * SQUARE;
+
+        writeln!(f, r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1" width="{0}" height="{0}" viewBox="0 0 {1} {1}">"#, self.size, width)?;
+        writeln!(f, r#"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="4" markerHeight="4" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{}"/></marker>"#, theme.arrow)?;
+        writeln!(f, r#"<radialGradient id="check"><stop offset="0%" stop-color="{0}"/><stop offset="100%" stop-color="{0}" stop-opacity="0"/></radialGradient></defs>"#, theme.check)?;
+
+        // The squares, with the highlights of the last move.
+        let highlighted = |sq| self.last_move.is_some_and(|mv| mv.from() == sq || mv.to() == sq);
+        for sq in Square::SQUARES {
+            let (x, y) = self.corner(sq);
+            let fill = if sq.parity() == Color::Black {&theme.dark} else {&theme.light};
+            writeln!(f, r#"<rect x="{0}" y="{1}" width="{2}" height="{2}" fill="{3}"/>"#, x, y, SQUARE, fill)?;
+
+            if highlighted(sq) {
+                writeln!(f, r#"<rect x="{0}" y="{1}" width="{2}" height="{2}" fill="{3}" fill-opacity="0.6"/>"#, x, y, SQUARE, theme.last_move)?;
+            }
+        }
+
+        // The coordinates, on the bottom rank and on the left file.
+        if self.coordinates {
+            for i in 0..8 {
+                let (file, rank) = if self.flipped {(7 - i, i)} else {(i, 7 - i)};
+                let (file, rank) = ((b'a' + file as u8) as char, (b'1' + rank as u8) as char);
+                writeln!(f, r#"<text x="{}" y="{}" font-size="9" font-family="sans-serif" fill="{}">{}</text>"#, i * SQUARE + SQUARE - 8, width - 2, theme.coordinates, file)?;
+                writeln!(f, r#"<text x="2" y="{}" font-size="9" font-family="sans-serif" fill="{}">{}</text>"#, i * SQUARE + 10, theme.coordinates, rank)?;
+            }
+        }
+
+        // The king in check.
+        if self.board.get_checkers().not_empty() {
+            let (x, y) = self.center(self.board.king_sq());
+            writeln!(f, r#"<circle cx="{}" cy="{}" r="{}" fill="url(#check)"/>"#, x, y, SQUARE / 2)?;
+        }
+
+        // The pieces.
+        for sq in Square::SQUARES {
+            if let Some((color, piece)) = self.board.get_piece(sq) {
+                let (x, y) = self.center(sq);
+                let fill = if color == Color::White {&theme.white_pieces} else {&theme.black_pieces};
+                writeln!(
+                    f,
+                    r#"<text x="{}" y="{}" font-family="{}" font-size="38" text-anchor="middle" dominant-baseline="central" fill="{}" stroke="{}" stroke-width="1">{}</text>"#,
+                    x, y, FONTS, fill, theme.black_pieces, GLYPHS[usize::from(piece)],
+                )?;
+            }
+        }
+
+        for &sq in &self.marks {
+            let (x, y) = self.center(sq);
+            writeln!(f, r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="3" stroke-opacity="0.8"/>"#, x, y, SQUARE / 2 - 3, theme.mark)?;
+        }
+
+        // The arrows end a bit before the center of the target square, for their head to fit.
+        for &(from, to) in &self.arrows {
+            let ((x1, y1), (x2, y2)) = (self.center(from), self.center(to));
+            let length = ((x2 - x1).pow(2) + (y2 - y1).pow(2)).isqrt().max(1);
+            let shorten = f64::from(SQUARE) / 4.0 / f64::from(length);
+            let (x2, y2) = (f64::from(x2) - f64::from(x2 - x1) * shorten, f64::from(y2) - f64::from(y2 - y1) * shorten);
+
+            writeln!(
+                f,
+                r#"<line x1="{}" y1="{}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="8" stroke-opacity="0.8" stroke-linecap="round" marker-end="url(#arrowhead)"/>"#,
+                x1, y1, x2, y2, theme.arrow,
+            )?;
+        }
+
+        write!(f, "</svg>")
+    }
+}
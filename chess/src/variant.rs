@@ -0,0 +1,129 @@
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+use crate::piece::Piece;
+
+//#################################################################################################
+//
+//                                        enum Variant
+//
+//#################################################################################################
+
+/// The rules a board is played with.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Variant {
+    /// Orthodox chess.
+    Standard,
+    /// Bringing the king to one of the four central squares wins the game.
+    KingOfTheHill,
+    /// Checking the opponent three times wins the game.
+    ThreeCheck,
+    /// Captures are compulsory, the king is an ordinary piece, and losing
+    /// all pieces or being stalemated wins the game.
+    Antichess,
+    /// Captures explode every piece but pawns around the capture square,
+    /// exploding the opponent's king wins the game.
+    Atomic,
+    /// Captured pieces go to the capturer's pocket, from which
+    /// they may be dropped back on the board instead of moving.
+    Crazyhouse,
+}
+
+// ================================ pub impl
+
+impl Variant {
+    /// List of variants.
+    pub const VARIANTS: [Variant; 6] = [
+        Variant::Standard, Variant::KingOfTheHill, Variant::ThreeCheck,
+        Variant::Antichess, Variant::Atomic, Variant::Crazyhouse,
+    ];
+
+    /// Returns the fen of the starting position of the variant.
+    pub fn start_fen(self) -> &'static str {
+        match self {
+            Variant::ThreeCheck => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1",
+            Variant::Antichess => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+            Variant::Crazyhouse => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+            _ => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        }
+    }
+
+    /// The pieces a pawn promotes to, in order from most to least interesting.
+    /// The queen always comes first.
+    pub fn promotes(self) -> &'static [Piece] {
+        match self {
+            Variant::Antichess => &[Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight, Piece::King],
+            _ => &Piece::PROMOTES,
+        }
+    }
+
+    /// Returns true if the king is royal and the orthodox check rules apply:
+    /// a king in check must move away, or the checker be blocked or captured.
+    pub fn has_standard_checks(self) -> bool {
+        matches!(self, Variant::Standard | Variant::KingOfTheHill | Variant::ThreeCheck | Variant::Crazyhouse)
+    }
+}
+
+// ================================ traits impl
+
+impl Default for Variant {
+    /// Returns Variant::Standard.
+    fn default() -> Self {
+        Variant::Standard
+    }
+}
+
+impl fmt::Display for Variant {
+    /// The name of the variant, as used by lichess.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Variant::Standard => "standard",
+            Variant::KingOfTheHill => "kingOfTheHill",
+            Variant::ThreeCheck => "threeCheck",
+            Variant::Antichess => "antichess",
+            Variant::Atomic => "atomic",
+            Variant::Crazyhouse => "crazyhouse",
+        })
+    }
+}
+
+impl FromStr for Variant {
+    type Err = Error;
+
+    /// From the name of the variant, case insensitive.
+    fn from_str(s: &str) -> Result<Variant, Error> {
+        const NAMES: [(&str, Variant); 10] = [
+            ("standard", Variant::Standard), ("chess", Variant::Standard),
+            ("kingofthehill", Variant::KingOfTheHill), ("koth", Variant::KingOfTheHill),
+            ("threecheck", Variant::ThreeCheck), ("3check", Variant::ThreeCheck),
+            ("antichess", Variant::Antichess),
+            ("atomic", Variant::Atomic),
+            ("crazyhouse", Variant::Crazyhouse), ("zh", Variant::Crazyhouse),
+        ];
+
+        NAMES.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|&(_, variant)| variant)
+            .ok_or_else(|| Error::msg("unknown variant"))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Variant {
+    /// Serializes the variant as its name.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Variant {
+    /// Deserializes the variant from its name.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Variant, D::Error> {
+        crate::serde_utils::deserialize_from_str(deserializer, "a variant name")
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{BitXor, BitXorAssign, Not};
+use core::ops::{BitXor, BitXorAssign, Not};
 
 use crate::color::Color;
 use crate::piece::Piece;
@@ -11,12 +11,64 @@ use crate::square::Square;
 //#################################################################################################
 
 /// The zobrist keys.
-static mut KEYS: [[[Zobrist; 2]; 6]; 64] = [[[Zobrist::ZERO; 2]; 6]; 64];
+static KEYS: [[[Zobrist; 2]; 6]; 64] = {
+    // Changing the seed may make the cuckoo tables initialization non terminating.
+    let mut seed = 0x0C3B301A1AF7EE42;
+    let mut keys = [[[Zobrist::ZERO; 2]; 6]; 64];
+
+    let mut sq = 0;
+    while sq < 64 {
+        let mut piece = 0;
+        while piece < 6 {
+            keys[sq][piece][Color::White as usize] = xorshift(&mut seed);
+            keys[sq][piece][Color::Black as usize] = xorshift(&mut seed);
+            piece += 1;
+        }
+        sq += 1;
+    }
+
+    keys
+};
+
+/// The zobrist keys of the number of checks given by each color, for three-check.
+/// No check given hashes to zero, so that the keys of the other variants are unchanged.
+static CHECK_KEYS: [[Zobrist; 4]; 2] = {
+    let mut seed = 0x5D7C8E4F0B1A2963;
+    let mut keys = [[Zobrist::ZERO; 4]; 2];
+
+    let mut checks = 1;
+    while checks < 4 {
+        keys[Color::White as usize][checks] = xorshift(&mut seed);
+        keys[Color::Black as usize][checks] = xorshift(&mut seed);
+        checks += 1;
+    }
+
+    keys
+};
+
+/// The zobrist keys of the number of pieces in each pocket, for crazyhouse.
+/// Empty pockets hash to zero, so that the keys of the other variants are unchanged.
+static POCKET_KEYS: [[[Zobrist; 17]; 5]; 2] = {
+    let mut seed = 0x2F6B1E93C4D8A075;
+    let mut keys = [[[Zobrist::ZERO; 17]; 5]; 2];
+
+    let mut piece = 0;
+    while piece < 5 {
+        let mut count = 1;
+        while count < 17 {
+            keys[Color::White as usize][piece][count] = xorshift(&mut seed);
+            keys[Color::Black as usize][piece][count] = xorshift(&mut seed);
+            count += 1;
+        }
+        piece += 1;
+    }
+
+    keys
+};
 
 /// The xorshift* algorithm for 64 bits numbers, producing
 /// good enough pseudo-random numbers.
-#[cold]
-fn xorshift(seed: &mut u64) -> Zobrist {
+const fn xorshift(seed: &mut u64) -> Zobrist {
     let mut x = *seed;
     x ^= x.wrapping_shl(13);
     x ^= x.wrapping_shr(7);
@@ -25,20 +77,6 @@ fn xorshift(seed: &mut u64) -> Zobrist {
     Zobrist(x.wrapping_mul(0x2545F4914F6CDD1D))
 }
 
-/// Initializes the zobrist keys at the beginning of the program.
-#[cold]
-pub(crate) unsafe fn init() {
-    // Changing the seed may make the cuckoo init() non terminating.
-    let mut seed = 0x0C3B301A1AF7EE42;
-
-    for sq in Square::SQUARES {
-        for piece in Piece::PIECES {
-            KEYS[usize::from(sq)][usize::from(piece)][usize::from(Color::White)] = xorshift(&mut seed);
-            KEYS[usize::from(sq)][usize::from(piece)][usize::from(Color::Black)] = xorshift(&mut seed);
-        }
-    }
-}
-
 //#################################################################################################
 //
 //                                        struct Zobrist
@@ -75,6 +113,19 @@ impl Zobrist {
         (self.0 % MAX as u64) as isize
     }
 
+    /// Returns the key of the number of checks given by that color, for three-check.
+    #[inline]
+    pub fn checks(color: Color, checks: u8) -> Zobrist {
+        CHECK_KEYS[usize::from(color)][usize::from(checks.min(3))]
+    }
+
+    /// Returns the key of the number of pieces of that type in the pocket
+    /// of that color, for crazyhouse. The king can't be in a pocket.
+    #[inline]
+    pub fn pocket(color: Color, piece: Piece, count: u8) -> Zobrist {
+        POCKET_KEYS[usize::from(color)][usize::from(piece)][usize::from(count.min(16))]
+    }
+
     /// Returns the raw value of this zobrist.
     #[inline]
     pub fn get_raw(self) -> u64 {
@@ -88,6 +139,7 @@ impl From<(Color, Piece, Square)> for Zobrist {
     /// Hashes a color, piece, square triplet.
     #[inline]
     fn from((color, piece, sq): (Color, Piece, Square)) -> Zobrist {
+        // SAFE: every index is in range by construction of the types
         unsafe {
             *KEYS.get_unchecked(usize::from(sq)).get_unchecked(usize::from(piece)).get_unchecked(usize::from(color))
         }
@@ -0,0 +1,173 @@
+use chess::attacks;
+use chess::bitboard::BitBoard;
+use chess::piece::Piece;
+use chess::prelude::*;
+use chess::square::Square;
+
+// Positions used for testing, with their variant.
+const POSITIONS: [(Variant, &str); 8] = [
+    (Variant::Standard, "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+    (Variant::Standard, "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"),
+    (Variant::Standard, "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"),
+    (Variant::Standard, "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1"),
+    (Variant::Standard, "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1"),
+    (Variant::ThreeCheck, "rnbqkb1r/pppp1ppp/5n2/4p3/2B1P3/8/PPPP1PPP/RNBQK1NR w KQkq - 2+3 0 1"),
+    (Variant::Atomic, "rnbqkb1r/pppppppp/5n2/8/8/5N2/PPPPPPPP/RNBQKB1R w KQkq - 0 1"),
+    (Variant::Crazyhouse, "r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R[NBpp] w KQkq - 0 1"),
+];
+
+/// Compares gives_check with the checkers after playing the move, for the whole tree.
+fn check_tree(board: &mut Board, depth: usize) {
+    let mut list = Vec::new();
+    movegen::legals(board, &mut list);
+
+    for mv in list {
+        let gives_check = board.gives_check(mv);
+        board.do_move(mv);
+        assert_eq!(gives_check, board.get_checkers().not_empty(), "Error at {} after {}.", board, mv);
+        if depth > 1 {
+            check_tree(board, depth - 1);
+        }
+        board.undo_move(mv);
+    }
+}
+
+/// The directions of the bishops and the rooks.
+const BISHOP_DIRS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const ROOK_DIRS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Computes the attacks of a slider by walking its rays, up to the first occupied square.
+fn ray_walk(sq: Square, occ: BitBoard, dirs: &[(i8, i8)]) -> BitBoard {
+    let mut attacks = BitBoard::EMPTY;
+
+    for &dir in dirs {
+        let mut cur = sq;
+        while let Some(next) = cur.displace(dir) {
+            attacks |= next.into();
+            if occ.contains(next) {
+                break;
+            }
+            cur = next;
+        }
+    }
+
+    attacks
+}
+
+/// The offsets of the knights and the kings.
+const KNIGHT_JUMPS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_STEPS: [(i8, i8); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+
+/// Computes the attacks of the piece on that square from the offsets and rays of its
+/// kind, independently of the attack tables.
+fn naive_attacks(board: &Board, sq: Square) -> BitBoard {
+    let occ = board.get_occupancy().all();
+    let steps = |offsets: &[(i8, i8)]| offsets.iter()
+        .filter_map(|&offset| sq.displace(offset))
+        .fold(BitBoard::EMPTY, |bb, to| bb | to.into());
+
+    match board.get_piece(sq) {
+        Some((Color::White, Piece::Pawn)) => steps(&[(1, 1), (-1, 1)]),
+        Some((Color::Black, Piece::Pawn)) => steps(&[(1, -1), (-1, -1)]),
+        Some((_, Piece::Knight)) => steps(&KNIGHT_JUMPS),
+        Some((_, Piece::King)) => steps(&KING_STEPS),
+        Some((_, Piece::Bishop)) => ray_walk(sq, occ, &BISHOP_DIRS),
+        Some((_, Piece::Rook)) => ray_walk(sq, occ, &ROOK_DIRS),
+        Some((_, Piece::Queen)) => ray_walk(sq, occ, &BISHOP_DIRS) | ray_walk(sq, occ, &ROOK_DIRS),
+        None => BitBoard::EMPTY,
+    }
+}
+
+/// Compares the backends of a slider with a ray walk, for every subset of its rays
+/// on the square, and random occupancies outside of them.
+fn check_slider(sq: Square, dirs: &[(i8, i8)], seed: &mut u64, backends: [fn(Square, BitBoard) -> Option<BitBoard>; 3]) {
+    let rays = ray_walk(sq, BitBoard::EMPTY, dirs);
+
+    // Every subset of the rays, enumerated with the carry-rippler trick.
+    let mut subset = 0;
+    loop {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+
+        let occ = BitBoard(subset | *seed & !rays.0);
+        let attacks = ray_walk(sq, occ, dirs);
+
+        for backend in backends {
+            if let Some(lookup) = backend(sq, occ) {
+                assert_eq!(lookup, attacks, "Error at {} with {:?}.", sq, occ);
+            }
+        }
+
+        subset = subset.wrapping_sub(rays.0) & rays.0;
+        if subset == 0 {
+            break;
+        }
+    }
+}
+
+#[test]
+fn sliders_backends() {
+    // A xorshift seed, for the occupancy outside of the rays.
+    let mut seed = 0x2545F4914F6CDD1D;
+
+    // The magic and the pext lookups, if the cpu supports it, then the selected one.
+    for sq in Square::SQUARES {
+        check_slider(sq, &BISHOP_DIRS, &mut seed, [
+            |sq, occ| Some(attacks::magic_bishop(sq, occ)),
+            attacks::pext_bishop,
+            |sq, occ| Some(attacks::bishop(sq, occ)),
+        ]);
+        check_slider(sq, &ROOK_DIRS, &mut seed, [
+            |sq, occ| Some(attacks::magic_rook(sq, occ)),
+            attacks::pext_rook,
+            |sq, occ| Some(attacks::rook(sq, occ)),
+        ]);
+    }
+}
+
+#[test]
+fn gives_check() {
+    for (variant, fen) in POSITIONS {
+        check_tree(&mut Board::new_variant(variant, fen).unwrap(), 3);
+    }
+}
+
+#[test]
+fn attack_queries() {
+    for (variant, fen) in POSITIONS {
+        let board = Board::new_variant(variant, fen).unwrap();
+
+        for from in Square::SQUARES {
+            assert_eq!(board.attacks_from(from), naive_attacks(&board, from), "Error at {} from {}.", fen, from);
+        }
+
+        for color in Color::COLORS {
+            let mut map = BitBoard::EMPTY;
+
+            for sq in Square::SQUARES {
+                // The attackers are exactly the pieces of that color attacking the square, walking their offsets.
+                let attackers = board.get_occupancy().colored(color).iter_squares()
+                    .filter(|&from| naive_attacks(&board, from).contains(sq))
+                    .fold(BitBoard::EMPTY, |bb, from| bb | from.into());
+
+                assert_eq!(board.attackers_to(sq, color), attackers);
+                assert_eq!(board.is_attacked(sq, color), attackers.not_empty());
+                if attackers.not_empty() {
+                    map |= sq.into();
+                }
+            }
+
+            assert_eq!(board.attack_map(color), map);
+        }
+    }
+
+    // X-rays and pins.
+    let board = Board::new("4k3/4r3/8/8/4N3/8/1b6/R3K2Q w - - 0 1").unwrap();
+    assert_eq!(board.pins(Color::White), vec![(Square::E7, Square::E4)]);
+    assert!(board.pins(Color::Black).is_empty());
+    assert!(board.xray_attacks_from(Square::E7).contains(Square::E3));
+    assert!(!board.xray_attacks_from(Square::E7).contains(Square::E4));
+    assert!(board.xray_attacks_from(Square::A1).contains(Square::F1));
+    assert!(board.xray_attacks_from(Square::E4).empty());
+}
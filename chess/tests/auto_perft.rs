@@ -1,9 +1,10 @@
+use chess::perft::{Perft, PerftStats};
 use chess::prelude::*;
 
 // The perft algorithm, counting the number of leaf nodes.
 fn perft(board: &mut Board, depth: usize) -> u64 {    
     let mut list = Vec::new();
-    movegen::legals(&board, &mut list);
+    movegen::legals(board, &mut list);
     
     if depth == 1 {
         return list.len() as u64;
@@ -21,7 +22,7 @@ fn perft(board: &mut Board, depth: usize) -> u64 {
 }
 
 // FEN notations for testing.
-const FENS: [(&'static str, u64); 127] = [
+const FENS: [(&str, u64); 127] = [
     ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 197281),
     ("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N w - - 0 1", 182838),
     ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 4085603),
@@ -153,10 +154,50 @@ const FENS: [(&'static str, u64); 127] = [
 
 #[test]
 fn auto_perft() {
-    chess::init();
-
     for &(fen, res) in &FENS {
         let mut board = Board::new(fen).unwrap();
         assert_eq!(perft(&mut board, 4), res, "Error at {:?}.", fen);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn hashed_perft() {
+    let perft = Perft::new(4, 16, false);
+
+    for &(fen, res) in &FENS {
+        let board = Board::new(fen).unwrap();
+        assert_eq!(perft.run(&board, 4).nodes, res, "Error at {:?}.", fen);
+    }
+}
+
+#[test]
+fn perft_stats() {
+    // Reference statistics from https://www.chessprogramming.org/Perft_Results
+    let positions = [
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 4, [197281, 1576, 0, 0, 0, 469, 0, 0, 8]),
+        ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 4, [4085603, 757163, 1929, 128013, 15172, 25523, 42, 6, 43]),
+        ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 4, [43238, 3348, 123, 0, 0, 1680, 106, 0, 17]),
+    ];
+
+    let perft = Perft::new(4, 0, true);
+
+    for (fen, depth, res) in positions {
+        let board = Board::new(fen).unwrap();
+        let [nodes, captures, en_passants, castles, promotions, checks, discovered_checks, double_checks, checkmates] = res;
+
+        assert_eq!(perft.run(&board, depth), PerftStats {
+            nodes, captures, en_passants, castles, promotions, 
+            checks, discovered_checks, double_checks, checkmates,
+        }, "Error at {:?}.", fen);
+    }
+}
+
+#[test]
+fn perft_stats_hash() {
+    // Statistics need every leaf to be played, so no hash table is allocated for them.
+    let board = Board::new("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+    let perft = Perft::new(4, 16, true);
+    assert!(!perft.has_table());
+    assert!(Perft::new(4, 16, false).has_table());
+    assert_eq!(perft.run(&board, 3), Perft::new(4, 0, true).run(&board, 3));
+}
@@ -0,0 +1,48 @@
+use chess::builder::BoardBuilder;
+use chess::piece::Piece;
+use chess::prelude::*;
+use chess::square::Square;
+
+#[test]
+fn build_positions() {
+    let mut builder = BoardBuilder::new();
+    builder.put(Square::E1, Color::White, Piece::King)
+        .put(Square::H1, Color::White, Piece::Rook)
+        .put(Square::D4, Color::White, Piece::Pawn)
+        .put(Square::E4, Color::Black, Piece::Pawn)
+        .put(Square::E8, Color::Black, Piece::King)
+        .side_to_move(Color::Black)
+        .castle_rights(Color::White, true, false)
+        .ep_square(Some(Square::D3))
+        .halfmove(0)
+        .fullmove(12);
+
+    let board = builder.build().unwrap();
+    let fen = "4k3/8/8/8/3Pp3/8/8/4K2R b K d3 0 12";
+    assert_eq!(board.to_string(), fen);
+    assert_eq!(board.get_zobrist(), Board::new(fen).unwrap().get_zobrist());
+
+    // The pieces attacking the king are found.
+    builder.put(Square::B5, Color::White, Piece::Bishop).side_to_move(Color::Black).ep_square(None);
+    assert_eq!(builder.build().unwrap().get_checkers().count(), 1);
+
+    // Invalid positions are rejected.
+    assert!(builder.clone().side_to_move(Color::White).build().is_err());
+    assert!(builder.clone().remove(Square::E8).build().is_err());
+    assert!(builder.clone().remove(Square::H1).build().is_err());
+    assert!(builder.clone().put(Square::A8, Color::White, Piece::Pawn).build().is_err());
+    assert!(builder.clone().ep_square(Some(Square::E3)).build().is_err());
+    assert!(builder.clone().pocket(Color::White, Piece::Queen, 1).build().is_err());
+
+    // Boards are rebuilt identically.
+    for (variant, fen) in [
+        (Variant::Standard, "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"),
+        (Variant::ThreeCheck, "rnbqkb1r/pppp1ppp/5n2/4p3/2B1P3/8/PPPP1PPP/RNBQK1NR w KQkq - 2+3 4 3"),
+        (Variant::Crazyhouse, "4k3/1Q~6/8/8/4b3/8/Kpp5/8[Nqpp] b - - 0 1"),
+    ] {
+        let board = Board::new_variant(variant, fen).unwrap();
+        let rebuilt = BoardBuilder::from(&board).build().unwrap();
+        assert_eq!(rebuilt.to_string(), board.to_string());
+        assert_eq!(rebuilt.get_zobrist(), board.get_zobrist());
+    }
+}
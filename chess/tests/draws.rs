@@ -0,0 +1,37 @@
+use chess::prelude::*;
+
+/// Plays the moves, given in pure algebraic coordinates notation, on the board.
+fn play(board: &mut Board, moves: &[&str]) {
+    for s in moves {
+        let mv = board.parse_move(s).unwrap();
+        board.do_move(mv);
+    }
+}
+
+#[test]
+fn upcoming_repetition() {
+    // The kings shuffle back and forth, the side to move may repeat the first position.
+    let mut board = Board::new("4k3/8/8/8/8/8/8/4K3 w - - 10 40").unwrap();
+    play(&mut board, &["e1e2", "e8e7", "e2e1"]);
+    assert!(board.test_upcoming_repetition());
+
+    // A rook coming back to its square may repeat too.
+    let mut board = Board::new("4k3/8/8/8/8/8/8/R3K3 w - - 10 40").unwrap();
+    play(&mut board, &["a1a2", "e8d8", "a2a1"]);
+    assert!(board.test_upcoming_repetition());
+
+    // Without the king moving back, the position is new.
+    let mut board = Board::new("4k3/8/8/8/8/8/8/4K3 w - - 10 40").unwrap();
+    play(&mut board, &["e1e2", "e8e7", "e2e3"]);
+    assert!(!board.test_upcoming_repetition());
+}
+
+#[test]
+fn fifty_move_rule() {
+    // The game is drawn after 100 halfmoves without captures or pawn moves.
+    let board = Board::new("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+    assert!(board.status().is_playing());
+
+    let board = Board::new("4k3/8/8/8/8/8/8/R3K3 w - - 100 80").unwrap();
+    assert!(matches!(board.status(), Status::Draw));
+}
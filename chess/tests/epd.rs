@@ -0,0 +1,37 @@
+use chess::epd::Epd;
+use chess::perft::Perft;
+
+#[test]
+fn epd_operations() {
+    let epd = Epd::new(r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#).unwrap();
+    let board = epd.get_board();
+    assert_eq!(epd.get_id(), Some("WAC.001"));
+    assert_eq!(epd.get_best_moves(), &[board.parse_move("g3g6").unwrap()]);
+    assert!(epd.is_solution(board.parse_move("g3g6").unwrap()));
+    assert!(!epd.is_solution(board.parse_move("f1f2").unwrap()));
+    assert_eq!(epd.to_string(), r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#);
+
+    let epd = Epd::new(r#"r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - am Ke2; c0 "avoid; this"; acd 12; ce -35; pv Bb5 a6 Ba4; hmvc 2; fmvn 3;"#).unwrap();
+    assert_eq!(epd.get_board().get_halfmove(), 2);
+    assert_eq!(epd.get_board().get_ply(), 4);
+    assert_eq!(epd.get_comment(), Some("avoid; this"));
+    assert_eq!(epd.get_avoid_moves().len(), 1);
+    assert_eq!(epd.get_depth(), Some(12));
+    assert_eq!(epd.get_eval(), Some(-35));
+    assert_eq!(epd.get_pv().iter().map(|mv| mv.to_string()).collect::<Vec<_>>(), ["f1b5", "a7a6", "b5a4"]);
+
+    assert!(Epd::new("8/8/8/8/8/8/8/K1k5 w -").is_err());
+    assert!(Epd::new("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qh7;").is_err());
+    assert!(Epd::new("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - acd x;").is_err());
+}
+
+#[test]
+fn epd_perft() {
+    let epd = Epd::new("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1 ;D1 26 ;D2 568 ;D3 13744").unwrap();
+    let perft = Perft::new(1, 0, false);
+
+    assert_eq!(epd.get_perft().len(), 3);
+    for &(depth, nodes) in epd.get_perft() {
+        assert_eq!(perft.run(epd.get_board(), depth).nodes, nodes);
+    }
+}
@@ -0,0 +1,40 @@
+use chess::game::Game;
+use chess::prelude::*;
+
+#[test]
+fn game_record() {
+    let start = Board::new(Variant::Standard.start_fen()).unwrap();
+    let mut game = Game::new(start.clone());
+    for s in ["e2e4", "e7e5", "g1f3"] {
+        let mv = game.get_board().parse_move(s).unwrap();
+        game.play(mv).unwrap();
+    }
+    assert_eq!(game.get_sans(), ["e4", "e5", "Nf3"]);
+    assert!(game.play(game.get_moves()[0]).is_err());
+
+    // Undoing and redoing moves the cursor.
+    let nf3 = game.undo().unwrap();
+    let e5 = game.undo().unwrap();
+    assert_eq!(game.get_cursor(), 1);
+    assert_eq!(game.get_board().to_string(), game.board_at(1).unwrap().to_string());
+    assert_eq!(game.redo().unwrap(), e5);
+    game.play(nf3).unwrap();
+    assert_eq!(game.get_moves().len(), 3);
+
+    // Playing another move throws out the undone ones.
+    game.goto(1).unwrap();
+    let c5 = game.get_board().parse_move("c7c5").unwrap();
+    game.play(c5).unwrap();
+    assert_eq!(game.get_sans(), ["e4", "c5"]);
+    assert!(game.redo().is_err());
+    assert!(game.goto(3).is_err());
+
+    game.goto(0).unwrap();
+    assert_eq!(game.get_board().to_string(), start.to_string());
+    assert_eq!(game.get_result(), Status::Playing);
+
+    game.set_header("White", "Alice");
+    game.set_header("White", "Bob");
+    assert_eq!(game.get_header("White"), Some("Bob"));
+    assert_eq!(game.get_headers().len(), 1);
+}
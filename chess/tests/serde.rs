@@ -0,0 +1,79 @@
+use serde::de::DeserializeSeed;
+
+use chess::bitboard::BitBoard;
+use chess::moves::MoveSeed;
+use chess::piece::Piece;
+use chess::prelude::*;
+use chess::square::Square;
+
+// Positions used for testing, with a move and it's SAN representation.
+const POSITIONS: [(&str, &str, &str); 7] = [
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "g1f3", "Nf3"),
+    ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", "e1c1", "O-O-O"),
+    ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", "e5f7", "Nxf7"),
+    ("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1", "g2h1q", "gxh1=Q"),
+    ("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "a1a8", "Ra8+"),
+    ("6k1/5ppp/8/8/8/8/8/R3R1K1 w - - 0 1", "e1e8", "Re8#"),
+    ("6k1/5ppp/8/8/8/8/8/R4RK1 w - - 0 1", "a1e1", "Rae1"),
+];
+
+#[test]
+fn primitives_round_trip() {
+    for sq in Square::SQUARES {
+        let json = serde_json::to_string(&sq).unwrap();
+        assert_eq!(json, format!("\"{}\"", sq));
+        assert_eq!(serde_json::from_str::<Square>(&json).unwrap(), sq);
+    }
+
+    for color in Color::COLORS {
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+    }
+
+    for piece in Piece::PIECES {
+        let json = serde_json::to_string(&piece).unwrap();
+        assert_eq!(serde_json::from_str::<Piece>(&json).unwrap(), piece);
+    }
+
+    let bb = BitBoard::RANK_2 | BitBoard::FILE_H;
+    let json = serde_json::to_string(&bb).unwrap();
+    assert_eq!(serde_json::from_str::<BitBoard>(&json).unwrap(), bb);
+
+    assert!(serde_json::from_str::<Square>("\"i9\"").is_err());
+}
+
+#[test]
+fn board_round_trip() {
+    for (fen, _, _) in POSITIONS {
+        let board = Board::new(fen).unwrap();
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, format!("\"{}\"", fen));
+
+        let other: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.to_string(), board.to_string());
+        assert_eq!(other.get_zobrist(), board.get_zobrist());
+    }
+
+    assert!(serde_json::from_str::<Board>("\"8/8/8 w - - 0 1\"").is_err());
+}
+
+#[test]
+fn move_round_trip() {
+    for (fen, uci, san) in POSITIONS {
+        let board = Board::new(fen).unwrap();
+        let mv = board.parse_move(uci).unwrap();
+
+        assert_eq!(serde_json::to_string(&mv).unwrap(), format!("\"{}\"", uci));
+        assert_eq!(serde_json::to_string(&mv.san(&board)).unwrap(), format!("\"{}\"", san));
+
+        for json in [format!("\"{}\"", uci), format!("\"{}\"", san)] {
+            let mut de = serde_json::Deserializer::from_str(&json);
+            assert_eq!(MoveSeed(&board).deserialize(&mut de).unwrap(), mv);
+        }
+    }
+
+    let board = Board::new(POSITIONS[0].0).unwrap();
+    let mut de = serde_json::Deserializer::from_str("\"e2e5\"");
+    assert!(MoveSeed(&board).deserialize(&mut de).is_err());
+}
@@ -0,0 +1,37 @@
+use chess::prelude::*;
+use chess::square::Square;
+use chess::svg::{Svg, Theme};
+
+#[test]
+fn render_svg() {
+    let mut board = Board::new("rnbqkbnr/ppppp2p/5p2/6p1/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3").unwrap();
+    let svg = Svg::new(&board).render();
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    assert_eq!(svg.matches("♟\u{fe0e}").count(), 16);
+    assert_eq!(svg.matches("<rect").count(), 64);
+    assert!(!svg.contains("url(#check)"));
+
+    // Last move, check, arrows and marks.
+    let mv = board.parse_move("d1h5").unwrap();
+    board.do_move(mv);
+    let svg = Svg::new(&board)
+        .last_move(mv)
+        .arrow(Square::H5, Square::E8)
+        .mark(Square::G6)
+        .flipped(true)
+        .size(480)
+        .render();
+    assert_eq!(svg.matches("<rect").count(), 66);
+    assert!(svg.contains("url(#check)"));
+    assert_eq!(svg.matches("<line").count(), 1);
+    assert!(svg.contains(r#"width="480""#));
+
+    // Flipped, the h file is on the left.
+    assert!(svg.find(">h</text>").unwrap() < svg.find(">a</text>").unwrap());
+
+    let theme = Theme {
+        light: "#eeeeee".to_string(),
+        ..Theme::default()
+    };
+    assert!(Svg::new(&board).theme(theme).coordinates(false).render().contains("#eeeeee"));
+}
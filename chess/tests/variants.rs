@@ -0,0 +1,113 @@
+use chess::movegen;
+use chess::perft::Perft;
+use chess::prelude::*;
+use chess::square::Square;
+
+/// Plays the moves, given in pure algebraic coordinates notation, on the board.
+fn play(board: &mut Board, moves: &str) -> Vec<Move> {
+    moves.split(' ').map(|s| {
+        let mv = board.parse_move(s).unwrap();
+        board.do_move(mv);
+        mv
+    }).collect()
+}
+
+#[test]
+fn variants_perft() {
+    // Reference numbers from the starting positions, at depth 4.
+    let results = [
+        (Variant::Standard, 197281),
+        (Variant::KingOfTheHill, 197281),
+        (Variant::ThreeCheck, 197281),
+        (Variant::Antichess, 153299),
+        (Variant::Atomic, 197326),
+        (Variant::Crazyhouse, 197281),
+    ];
+
+    let perft = Perft::new(4, 16, false);
+
+    for (variant, res) in results {
+        let board = Board::new_variant(variant, variant.start_fen()).unwrap();
+        assert_eq!(board.to_string(), variant.start_fen());
+        assert_eq!(board.get_phase(), Board::MAX_PHASE);
+        assert_eq!(perft.run(&board, 4).nodes, res, "Error at {}.", variant);
+    }
+}
+
+#[test]
+fn variants_termination() {
+    // King of the hill: reaching the center wins.
+    let mut board = Board::new_variant(Variant::KingOfTheHill, "4k3/8/8/8/8/4K3/8/8 w - - 0 1").unwrap();
+    play(&mut board, "e3e4");
+    assert!(matches!(board.status(), Status::Win(Color::White)));
+
+    // Three-check: the third check wins, and the counters are restored on undo.
+    let fen = "4k3/8/8/8/8/8/8/R3K3 w - - 1+3 0 1";
+    let mut board = Board::new_variant(Variant::ThreeCheck, fen).unwrap();
+    let moves = play(&mut board, "a1a8");
+    assert!(matches!(board.status(), Status::Win(Color::White)));
+    assert_eq!(board.to_string(), "R3k3/8/8/8/8/8/8/4K3 b - - 0+3 1 1");
+    board.undo_move(moves[0]);
+    assert_eq!(board.get_checks(Color::White), 2);
+
+    // Antichess: captures are compulsory, and being stalemated wins.
+    let board = Board::new_variant(Variant::Antichess, "8/8/8/8/8/1p6/8/1R2K3 w - - 0 1").unwrap();
+    assert!(board.parse_move("b1a1").is_err());
+    assert!(board.parse_move("e1d2").is_err());
+    assert!(board.parse_move("b1b3").is_ok());
+    let board = Board::new_variant(Variant::Antichess, "8/8/8/8/8/p7/P7/8 w - - 0 1").unwrap();
+    assert!(matches!(board.status(), Status::Win(Color::White)));
+
+    // Atomic: captures explode the surroundings, exploding the king wins.
+    let fen = "4k3/3pr3/8/8/8/8/8/4R1K1 w - - 0 1";
+    let mut board = Board::new_variant(Variant::Atomic, fen).unwrap();
+    let zobrist = board.get_zobrist();
+    let moves = play(&mut board, "e1e7");
+    assert_eq!(board.to_string(), "8/3p4/8/8/8/8/8/6K1 b - - 0 1");
+    assert_eq!(board.get_phase(), 0);
+    assert!(matches!(board.status(), Status::Win(Color::White)));
+    board.undo_move(moves[0]);
+    assert_eq!(board.to_string(), fen);
+    assert_eq!(board.get_phase(), 4);
+    assert_eq!(board.get_zobrist(), zobrist);
+
+    // Atomic: the king can't castle out of check.
+    let board = Board::new_variant(Variant::Atomic, "4k3/8/8/8/4r3/8/8/4K2R w K - 0 1").unwrap();
+    let mut legals = Vec::new();
+    movegen::legals(&board, &mut legals);
+    assert!(!legals.contains(&Move::castle(Square::E1, Square::G1)));
+    assert!(legals.contains(&Move::quiet(Square::E1, Square::F1)));
+}
+
+#[test]
+fn crazyhouse() {
+    // Reference numbers for drops and promoted pieces.
+    let perft = Perft::new(4, 16, false);
+    let board = Board::new_variant(Variant::Crazyhouse, "2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1").unwrap();
+    assert_eq!(perft.run(&board, 2).nodes, 75353);
+    let fen = "4k3/1Q~6/8/8/4b3/8/Kpp5/8[] b - - 0 1";
+    let mut board = Board::new_variant(Variant::Crazyhouse, fen).unwrap();
+    assert_eq!(perft.run(&board, 4).nodes, 132758);
+
+    // Captured pieces go to the pocket, promoted ones as pawns, and may be dropped.
+    let zobrist = board.get_zobrist();
+    let moves = play(&mut board, "e4b7 a2b2 P@e2");
+    assert_eq!(board.to_string(), "4k3/1b6/8/8/8/8/1Kp1p3/8[P] w - - 0 3");
+    assert_eq!(board.get_phase(), 1);
+    let pawn_zobrist = Board::new_variant(Variant::Crazyhouse, &board.to_string()).unwrap().get_pawn_zobrist();
+    assert_eq!(board.get_pawn_zobrist(), pawn_zobrist);
+    assert_eq!(moves[2].to_string(), "P@e2");
+    assert_eq!(board.parse_san("P@e4").unwrap(), board.parse_move("P@e4").unwrap());
+    assert!(board.parse_move("P@e8").is_err());
+    assert!(board.parse_move("N@e4").is_err());
+
+    for &mv in moves.iter().rev() {
+        board.undo_move(mv);
+    }
+    assert_eq!(board.to_string(), fen);
+    assert_eq!(board.get_zobrist(), zobrist);
+    assert_eq!(board.get_phase(), 5);
+
+    // Pockets are not allowed in other variants.
+    assert!(Board::new("8/8/8/8/8/8/8/K1k5[Q] w - - 0 1").is_err());
+}
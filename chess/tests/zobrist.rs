@@ -0,0 +1,48 @@
+use chess::prelude::*;
+use chess::square::Square;
+use chess::zobrist::Zobrist;
+
+/// Computes the zobrist key of the board from scratch.
+fn zobrist(board: &Board) -> Zobrist {
+    let mut zobrist = Zobrist::ZERO;
+
+    for sq in Square::SQUARES {
+        if let Some((color, piece)) = board.get_piece(sq) {
+            zobrist ^= Zobrist::from((color, piece, sq));
+        }
+    }
+
+    if board.get_side_to_move() == Color::Black {
+        !zobrist
+    } else {
+        zobrist
+    }
+}
+
+#[test]
+fn castling_zobrist() {
+    const WHITE: &str = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1";
+    const BLACK: &str = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R b KQkq - 0 1";
+
+    for (fen, s) in [(WHITE, "e1g1"), (WHITE, "e1c1"), (BLACK, "e8g8"), (BLACK, "e8c8")] {
+        let mut board = Board::new(fen).unwrap();
+        let mv = board.parse_move(s).unwrap();
+
+        board.do_move(mv);
+        assert_eq!(board.get_zobrist(), zobrist(&board), "Error after {} at {:?}.", s, fen);
+
+        board.undo_move(mv);
+        assert_eq!(board.get_zobrist(), zobrist(&board), "Error after undoing {} at {:?}.", s, fen);
+    }
+}
+
+#[test]
+fn black_to_move_zobrist() {
+    let board = Board::new("rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1").unwrap();
+    assert_eq!(board.get_zobrist(), zobrist(&board));
+
+    // The same position, reached by playing the move.
+    let mut other = Board::new("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    other.do_move(other.parse_move("g1f3").unwrap());
+    assert_eq!(board.get_zobrist(), other.get_zobrist());
+}
@@ -0,0 +1,128 @@
+use chess::attacks;
+use chess::bitboard::BitBoard;
+use chess::board::Board;
+use chess::color::Color;
+use chess::piece::Piece;
+use chess::square::Square;
+
+use crate::params::EvalTerms;
+use crate::trace::{Term, Trace};
+use crate::utils::{adjacent_files, file_mask, front_ranks, relative_rank};
+use crate::weights::EvalParams;
+
+/// The average number of safe squares attacked by a piece, by piece order.
+const MOBILITY_AVERAGE: [f32; 6] = [0.0, 6.0, 4.0, 6.0, 12.0, 0.0];
+
+/// Evaluates the mobility and placement of the pieces of that color, and the safety of its
+/// king, adding the midgame and endgame values of the switched on terms to the trace.
+pub(crate) fn eval_activity(board: &Board, color: Color, weights: &EvalParams, terms: EvalTerms, trace: &mut impl Trace) {
+    let them = color.invert();
+    let occ = board.get_occupancy();
+    let (our_pawns, their_pawns) = (board.get_bitboard(color, Piece::Pawn), board.get_bitboard(them, Piece::Pawn));
+    let (our_king, their_king) = (king_square(board, color), king_square(board, them));
+
+    let pawn_attacks = |color, pawns: BitBoard| {
+        pawns.iter_squares().fold(BitBoard::EMPTY, |acc, sq| acc | attacks::pawn(color, sq))
+    };
+    let defended = pawn_attacks(color, our_pawns);
+    let safe = !occ.colored(color) & !pawn_attacks(them, their_pawns);
+    let king_zone = their_king.map_or(BitBoard::EMPTY, |sq| attacks::king(sq) | BitBoard::from(sq));
+
+    let (mut mobility, mut pieces, mut safety) = ([0.0; 2], [0.0; 2], [0.0; 2]);
+    let (mut attackers, mut units) = (0, 0.0);
+
+    for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        for sq in board.get_bitboard(color, piece).iter_squares() {
+            let targets = match piece {
+                Piece::Knight => attacks::knight(sq),
+                Piece::Bishop => attacks::bishop(sq, occ.all()),
+                Piece::Rook => attacks::rook(sq, occ.all()),
+                _ => attacks::queen(sq, occ.all()),
+            };
+
+            if terms.mobility {
+                let [bonus_mg, bonus_eg] = weights.mobility[usize::from(piece)];
+                let count = f32::from((targets & safe).count()) - MOBILITY_AVERAGE[usize::from(piece)];
+                mobility[0] += bonus_mg * count;
+                mobility[1] += bonus_eg * count;
+            }
+
+            let hits = (targets & king_zone).count();
+            if hits > 0 {
+                attackers += 1;
+                units += weights.attack_units[usize::from(piece)] * f32::from(hits);
+            }
+
+            if terms.rook_files && piece == Piece::Rook {
+                let file = file_mask(sq);
+                if ((our_pawns | their_pawns) & file).empty() {
+                    pieces[0] += weights.open_file[0];
+                    pieces[1] += weights.open_file[1];
+                } else if (our_pawns & file).empty() {
+                    pieces[0] += weights.semi_open_file[0];
+                    pieces[1] += weights.semi_open_file[1];
+                }
+
+                // The seventh rank matters when there are pawns to attack or a king to confine.
+                let targets = their_pawns.iter_squares().any(|sq| relative_rank(color, sq) == 6);
+                let confined = their_king.is_some_and(|sq| relative_rank(color, sq) == 7);
+                if relative_rank(color, sq) == 6 && (targets || confined) {
+                    pieces[0] += weights.seventh_rank[0];
+                    pieces[1] += weights.seventh_rank[1];
+                }
+            }
+
+            if terms.outposts && piece == Piece::Knight {
+                let rank = relative_rank(color, sq);
+                let chased = (their_pawns & adjacent_files(sq) & front_ranks(color, sq)).not_empty();
+                if (3..=5).contains(&rank) && defended.contains(sq) && !chased {
+                    pieces[0] += weights.outpost[0];
+                    pieces[1] += weights.outpost[1];
+                }
+            }
+        }
+    }
+
+    // A single attacker is rarely dangerous, the danger grows quickly with the attack units.
+    if terms.king_attacks && attackers >= 2 {
+        safety[0] += (weights.king_danger[0] * units * units).min(weights.max_king_danger);
+        safety[1] += weights.king_danger[1] * units * units;
+    }
+
+    if terms.pawn_shelter {
+        if let Some(king) = our_king {
+            safety[0] += shelter(color, king, our_pawns, their_pawns, weights);
+        }
+    }
+
+    if terms.bishop_pair && board.get_bitboard(color, Piece::Bishop).more_than_one() {
+        pieces[0] += weights.bishop_pair[0];
+        pieces[1] += weights.bishop_pair[1];
+    }
+
+    trace.add(Term::Mobility, color, mobility[0], mobility[1]);
+    trace.add(Term::KingSafety, color, safety[0], safety[1]);
+    trace.add(Term::Pieces, color, pieces[0], pieces[1]);
+}
+
+/// Returns the square of the king of that color, if it has not exploded.
+#[inline]
+fn king_square(board: &Board, color: Color) -> Option<Square> {
+    board.get_bitboard(color, Piece::King).iter_squares().next()
+}
+
+/// Returns the midgame bonus for the pawn shield in front of the king, and the penalty
+/// for the enemy pawns storming it.
+fn shelter(color: Color, king: Square, ours: BitBoard, theirs: BitBoard, weights: &EvalParams) -> f32 {
+    let front = front_ranks(color, king);
+    let closest = |pawns: BitBoard| pawns.iter_squares().map(|sq| (sq.y() - king.y()).unsigned_abs() as usize).min();
+    let mut score = 0.0;
+
+    for x in (king.x() - 1).max(0)..=(king.x() + 1).min(7) {
+        let file = BitBoard(BitBoard::FILE_A.0 << x) & front;
+        score += weights.shelter[closest(ours & file).map_or(0, |d| d.min(3))];
+        score += closest(theirs & file).and_then(|d| weights.storm.get(d)).unwrap_or(&0.0);
+    }
+
+    score
+}
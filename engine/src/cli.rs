@@ -1,20 +1,34 @@
-use std::time::Duration;
-use std::{env, io, thread};
+use std::time::{Duration, Instant};
+use std::{env, fs, io, thread};
 use std::io::Write;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::{Error, Result};
 use clap::{App, Arg};
 
+use chess::epd::Epd;
+use chess::game::Game;
 use chess::prelude::*;
-use engine::Engine;
-
-/// The default fen used, the starting position.
-const DEFAULT_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+use engine::{Engine, EvalParams, Network};
 
 /// The maximum number of moves displayed in move history.
 const MAX_HISTORY: usize = 24;
 
+/// The interval at which the engine is polled when running an epd test suite.
+const EPD_POLL: Duration = Duration::from_millis(10);
+
+/// The positions searched by the bench: openings, middlegames with tactics, and endgames.
+const BENCH: &[&str] = &[
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP2BPPP/R2QKB1R w KQ - 0 8",
+    "r2q1rk1/1b2bppp/p2p1n2/1p2p3/3PP3/2P2N1P/PPB2PP1/RNBQR1K1 w - - 0 13",
+    "2r3k1/pp3ppp/4p3/3n4/3P4/P4N2/1P3PPP/2R3K1 w - - 0 25",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "8/8/4k3/8/2p5/8/B2K4/8 w - - 0 1",
+];
+
 /// The text displayed when the user types "help".
 const HELP: &str = r#"Available commands:
   help            : prints this message.
@@ -23,13 +37,22 @@ const HELP: &str = r#"Available commands:
   think <seconds> : starts the engine for <seconds> seconds.
   do              : plays the engine's preferred move.
   auto <seconds>  : plays the engine against itself, with <seconds> seconds to think for each move.
+  epd <file> <ms> : runs the engine on the positions of an epd test suite, thinking <ms> milliseconds
+                    for each, and reports the solved positions.
+  eval            : prints the breakdown of the handcrafted evaluation of the position.
+  nnue <file|off> : evaluates standard chess positions with the network of <file>, or with the
+                    handcrafted evaluation again if "off".
+  contempt <p>    : sets the value in pawns <p> the engine gives up by drawing, negative to seek draws.
+  multipv <n>     : makes the engine search its <n> best lines, and prints them.
+  searchmoves ... : restricts the search to the given moves, or lifts the restriction if none.
+  bench <depth>   : searches the bench positions to <depth>, and reports the nodes searched.
   exit            : exits the cli."#;
 
 /// The global state of the cli.
 struct State {
     engine: Engine,
     buffer: String,
-    history: Vec<Move>,
+    game: Game,
 }
 
 // ================================ Utils
@@ -46,17 +69,18 @@ impl State {
         println!("Fen string: \"{}\"", board);
 
         // Print history.
-        if self.history.is_empty() {
+        let history = &self.game.get_sans()[..self.game.get_cursor()];
+        if history.is_empty() {
             println!("No move history yet.");
         } else {
             print!("Move history: ");
 
-            let history_string = if self.history.len() > MAX_HISTORY {
+            let history_string = if history.len() > MAX_HISTORY {
                 print!("..., ");
-                &self.history[self.history.len()-MAX_HISTORY..]
+                &history[history.len()-MAX_HISTORY..]
             } else {
-                &self.history[..]
-            }.iter().map(|mv| format!("{}", mv)).collect::<Vec<_>>().join(", ");
+                history
+            }.join(", ");
                 
             println!("{}", history_string);
         }
@@ -82,6 +106,15 @@ impl State {
         if self.engine.read_board().status().is_playing() {
             if let Some(mv) = self.engine.get_best_move() {
                 println!("Engine's preferred move: {}.\nFurthest depth searched: {}.", mv, self.engine.get_current_depth());
+
+                // The alternatives, in multi-pv mode.
+                let lines = self.engine.get_lines();
+                if lines.len() > 1 {
+                    for (i, line) in lines.iter().enumerate() {
+                        let pv = line.get_pv().iter().map(|mv| mv.to_string()).collect::<Vec<_>>().join(" ");
+                        println!("  {}. {:+.2}: {}", i + 1, line.get_score(), pv);
+                    }
+                }
             } else {
                 println!("Engine hasn't had time to think yet.")
             }
@@ -116,13 +149,13 @@ impl State {
 
     /// Plays the given move.
     fn play_move(&mut self, mv: Move) {
-        let mut board = self.engine.write_board();
-
-        // Sanity check.
-        assert!(board.is_pseudo_legal(mv) && board.is_legal(mv), "Tried to play illegal move");
+        self.game.play(mv).expect("Tried to play illegal move");
+        self.sync_engine();
+    }
 
-        self.history.push(mv);
-        board.do_move(mv);
+    /// Sets the engine's board to the current position of the game.
+    fn sync_engine(&self) {
+        *self.engine.write_board() = self.game.get_board().clone();
     }
 }
 
@@ -142,13 +175,8 @@ impl State {
 
     /// Reverts the last move.
     fn back(&mut self) -> Result<()> {
-        if self.history.is_empty() {
-            return Err(Error::msg("No move to undo."));
-        }
-
-        let mv = self.history.pop().unwrap();
-        let mut board = self.engine.write_board();
-        board.undo_move(mv);
+        self.game.undo()?;
+        self.sync_engine();
 
         Ok(())
     }
@@ -195,14 +223,164 @@ impl State {
         Ok(())
     }
 
-    /// Resets the board to it's initial state.
-    fn reset(&mut self, fen: &str) -> Result<()> {
-        // Reset the board.
-        let mut board = self.engine.write_board();
-        *board = Board::new(fen).unwrap();
+    /// Runs the engine on each position of an epd file, thinking for the parsed given time, in milliseconds.
+    /// A position is solved when the engine settled on a best move (bm) and none to avoid (am),
+    /// the time it did so is reported.
+    fn epd(&mut self, variant: Variant, args: &mut impl Iterator<Item = String>) -> Result<()> {
+        let path = args.next().ok_or(Error::msg("Cannot find <file> argument."))?;
+        let duration = Duration::from_millis(
+            u64::from_str(&args.next().ok_or(Error::msg("Can't find <ms> argument."))?)?
+        );
+
+        let records = fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(Epd::new_variant(variant, line)?))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut solved = 0;
+        let start = Instant::now();
+
+        for (i, epd) in records.iter().enumerate() {
+            let id = epd.get_id().map_or_else(|| format!("#{}", i + 1), str::to_string);
+            let board = epd.get_board();
+
+            if !board.status().is_playing() {
+                println!("{:<20} skipped, the game has ended.", id);
+                continue;
+            }
+
+            self.game = Game::new(board.clone());
+            self.sync_engine();
+
+            // Poll the engine, remembering since when its best move is a solution.
+            let position_start = Instant::now();
+            let mut solved_at = None;
+            self.engine.start();
+
+            while position_start.elapsed() < duration {
+                thread::sleep(EPD_POLL.min(duration - position_start.elapsed()));
+
+                match self.engine.get_best_move() {
+                    Some(mv) if epd.is_solution(mv) => {
+                        solved_at.get_or_insert(position_start.elapsed());
+                    },
+                    _ => solved_at = None,
+                }
+            }
+
+            self.engine.stop();
+            let mv = self.engine.get_best_move().expect("Engine found nothing");
+            if !epd.is_solution(mv) {
+                solved_at = None;
+            }
+
+            let expected = epd.get_best_moves().iter().map(|mv| mv.san(board).to_string())
+                .chain(epd.get_avoid_moves().iter().map(|mv| format!("not {}", mv.san(board))))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            if let Some(elapsed) = solved_at {
+                solved += 1;
+                println!("{:<20} {:<8} solved in {} ms", id, mv.san(board).to_string(), elapsed.as_millis());
+            } else {
+                println!("{:<20} {:<8} failed, expected {}", id, mv.san(board).to_string(), expected);
+            }
+        }
+
+        println!("Solved {}/{} positions in {:.1}s.", solved, records.len(), start.elapsed().as_secs_f64());
+        self.ask_ok();
+
+        Ok(())
+    }
+
+    /// Searches the bench positions to the given depth, and reports the number of nodes
+    /// searched, to measure the effect of search changes. The searches are single-threaded
+    /// and start from an empty table, so the node counts are reproducible.
+    fn bench(&mut self, args: &mut impl Iterator<Item = String>) -> Result<()> {
+        let depth = u8::from_str(&args.next().ok_or(Error::msg("Cannot find <depth> argument."))?)?;
+        if !(1..=32).contains(&depth) {
+            return Err(Error::msg("The depth must be between 1 and 32."));
+        }
+
+        let (mut total, start) = (0, Instant::now());
+
+        for fen in BENCH {
+            self.game = Game::new(Board::new(fen)?);
+            self.sync_engine();
+
+            // A position without moves has nothing to measure.
+            let mut moves = Vec::new();
+            movegen::legals(self.game.get_board(), &mut moves);
+            if moves.is_empty() {
+                return Err(Error::msg(format!("Nothing to search in {}.", fen)));
+            }
+
+            let position_start = Instant::now();
+            self.engine.search_fixed_depth(depth);
+
+            let nodes = self.engine.get_nodes();
+            total += nodes;
+            println!("{:<72} {:>10} nodes in {:>6} ms", fen, nodes, position_start.elapsed().as_millis());
+        }
 
-        // Reset the history.
-        self.history.clear();
+        let elapsed = start.elapsed();
+        println!("Total: {} nodes in {} ms, {:.0} nodes per second.", total, elapsed.as_millis(), total as f64 / elapsed.as_secs_f64());
+
+        self.ask_ok();
+
+        Ok(())
+    }
+
+    /// Prints the contribution of each term of the handcrafted evaluation of the position.
+    fn eval(&mut self) -> Result<()> {
+        let trace = self.engine.eval_trace(&self.engine.read_board());
+        println!("{}", trace);
+        self.ask_ok();
+
+        Ok(())
+    }
+
+    /// Loads the network of the given file to evaluate positions, or goes back to the handcrafted evaluation.
+    fn nnue(&mut self, args: &mut impl Iterator<Item = String>) -> Result<()> {
+        match args.next().ok_or(Error::msg("Cannot find <file> argument."))?.as_str() {
+            "off" => self.engine.set_network(None),
+            path => self.engine.set_network(Some(Arc::new(Network::load(path)?))),
+        }
+
+        Ok(())
+    }
+
+    /// Sets the contempt of the engine, in pawns.
+    fn contempt(&mut self, args: &mut impl Iterator<Item = String>) -> Result<()> {
+        let contempt = args.next().ok_or(Error::msg("Cannot find <p> argument."))?.parse()?;
+        self.engine.set_contempt(contempt);
+
+        Ok(())
+    }
+
+    /// Sets the number of lines searched by the engine.
+    fn multi_pv(&mut self, args: &mut impl Iterator<Item = String>) -> Result<()> {
+        let lines = args.next().ok_or(Error::msg("Cannot find <n> argument."))?.parse()?;
+        self.engine.set_multi_pv(lines);
+
+        Ok(())
+    }
+
+    /// Restricts the search of the engine to the given moves.
+    fn search_moves(&mut self, args: &mut impl Iterator<Item = String>) -> Result<()> {
+        let board = self.engine.read_board().clone();
+        let moves = args.map(|mv| board.parse_move(&mv)).collect::<Result<Vec<_>, _>>()?;
+        self.engine.set_search_moves(&moves);
+
+        Ok(())
+    }
+
+    /// Resets the board to it's initial state.
+    fn reset(&mut self, variant: Variant, fen: &str) -> Result<()> {
+        // Reset the game and the engine's board.
+        self.game = Game::new(Board::new_variant(variant, fen)?);
+        self.sync_engine();
 
         Ok(())
     }
@@ -211,9 +389,6 @@ impl State {
 /// The main function parses the programs arguments, initializes the chess library
 /// and the engine and then enter a REPL.
 fn main() -> Result<()> {
-    // Initializes the chess library.
-    chess::init();
-
     // Get the args to the program.
     let args = App::new("Rush Chess Engine CLI")
         .version(env!("CARGO_PKG_VERSION"))
@@ -225,19 +400,66 @@ fn main() -> Result<()> {
             .value_name("FEN")
             .help("Sets the fen string to use as the starting position, the default position is used if no fen string is provided. Use double-quotes.")
             .takes_value(true))
+        .arg(Arg::with_name("variant")
+            .short("v")
+            .long("variant")
+            .value_name("VARIANT")
+            .help("Sets the variant played: standard, kingOfTheHill, threeCheck, antichess, atomic or crazyhouse. Defaults to standard.")
+            .takes_value(true))
+        .arg(Arg::with_name("weights")
+            .short("w")
+            .long("weights")
+            .value_name("FILE")
+            .help("Loads the weights of the handcrafted evaluation from that json file, the missing ones keep their built-in value.")
+            .takes_value(true))
+        .arg(Arg::with_name("nnue")
+            .short("n")
+            .long("nnue")
+            .value_name("FILE")
+            .help("Evaluates standard chess positions with the network of that file instead of the handcrafted evaluation.")
+            .takes_value(true))
+        .arg(Arg::with_name("contempt")
+            .short("c")
+            .long("contempt")
+            .value_name("PAWNS")
+            .help("Sets the value in pawns the engine gives up by drawing, negative to seek draws. Defaults to 0.")
+            .takes_value(true))
+        .arg(Arg::with_name("multipv")
+            .short("m")
+            .long("multipv")
+            .value_name("LINES")
+            .help("Sets the number of lines searched by the engine, the best one and its alternatives. Defaults to 1.")
+            .takes_value(true))
         .get_matches();
 
-    // The fen string used for the position.
-    let default_fen = args.value_of("fen").unwrap_or(DEFAULT_FEN);
+    // The variant played, and the fen string used for the position.
+    let variant = args.value_of("variant").map_or(Ok(Variant::Standard), Variant::from_str)?;
+    let default_fen = args.value_of("fen").unwrap_or_else(|| variant.start_fen());
+
+    // The weights of the evaluation.
+    let weights = args.value_of("weights").map_or(Ok(EvalParams::default()), EvalParams::load)?;
 
     // Construct the state.
+    let board = Board::new_variant(variant, default_fen)?;
     let mut state = State {
         // Parse fen and create board, then engine.
-        engine: Engine::new(Board::from_str(default_fen)?),
+        engine: Engine::with_weights(board.clone(), weights),
         buffer: String::new(),
-        history: Vec::new(),
+        game: Game::new(board),
     };
 
+    if let Some(path) = args.value_of("nnue") {
+        state.engine.set_network(Some(Arc::new(Network::load(path)?)));
+    }
+
+    if let Some(contempt) = args.value_of("contempt") {
+        state.engine.set_contempt(contempt.parse()?);
+    }
+
+    if let Some(lines) = args.value_of("multipv") {
+        state.engine.set_multi_pv(lines.parse()?);
+    }
+
     // The REPL.
     loop {
         // Print the state of the board and of the engine.
@@ -258,7 +480,14 @@ fn main() -> Result<()> {
                 "think" => state.think(&mut args),
                 "do" => state.do_engine(),
                 "auto" => state.auto(&mut args),
-                "reset" => state.reset(&default_fen),
+                "epd" => state.epd(variant, &mut args),
+                "eval" => state.eval(),
+                "nnue" => state.nnue(&mut args),
+                "contempt" => state.contempt(&mut args),
+                "multipv" => state.multi_pv(&mut args),
+                "searchmoves" => state.search_moves(&mut args),
+                "bench" => state.bench(&mut args),
+                "reset" => state.reset(variant, default_fen),
                 "exit" => {
                     println!("Goodbye.");
                     break;
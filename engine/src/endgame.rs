@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use chess::attacks;
+use chess::board::Board;
+use chess::color::Color;
+use chess::piece::Piece;
+use chess::square::Square;
+
+use crate::params;
+use crate::utils::{distance, relative_rank};
+
+/// The score of an endgame the strong side knows how to win, in pawns, on top of
+/// which the specialized evaluations guide it to the mate or to the promotion.
+const KNOWN_WIN: f32 = 10.0;
+
+/// The bonus for driving the weak king to a corner of the color of a dark-squared bishop
+/// in KBNvK, the lowest in the center and in the other corners.
+const PUSH_TO_CORNERS: [f32; 64] = [
+    11.25, 10.0, 8.75, 7.5, 6.25, 5.0, 3.75, 2.5,
+    10.0, 8.75, 7.5, 6.25, 5.0, 3.75, 2.5, 3.75,
+    8.75, 7.5, 5.625, 3.75, 3.75, 1.875, 3.75, 5.0,
+    7.5, 6.25, 3.75, 1.25, 0.0, 3.75, 5.0, 6.25,
+    6.25, 5.0, 3.75, 0.0, 1.25, 3.75, 6.25, 7.5,
+    5.0, 3.75, 1.875, 3.75, 3.75, 5.625, 7.5, 8.75,
+    3.75, 2.5, 3.75, 5.0, 6.25, 7.5, 8.75, 10.0,
+    2.5, 3.75, 5.0, 6.25, 7.5, 8.75, 10.0, 11.25,
+];
+
+/// The pieces of a signature, in the order they are written.
+const PIECES: [(Piece, char); 5] = [
+    (Piece::Queen, 'Q'),
+    (Piece::Rook, 'R'),
+    (Piece::Bishop, 'B'),
+    (Piece::Knight, 'N'),
+    (Piece::Pawn, 'P'),
+];
+
+/// A specialized evaluation, from the point of view of the strong side.
+type Evaluator = fn(&Board, Color) -> f32;
+
+/// Returns the factor by which the endgame values of the strong side are scaled.
+type Scaler = fn(&Board, Color) -> f32;
+
+/// The endgames with a specialized evaluation, with the strong side first. Any other
+/// material with a queen or a rook against a bare king is evaluated like KQvK.
+const EVALUATORS: [(&str, Evaluator); 5] = [
+    ("KQvK", kxk),
+    ("KRvK", kxk),
+    ("KBNvK", kbnk),
+    ("KPvK", kpk),
+    ("KRvKP", krkp),
+];
+
+/// The endgames whose values are scaled down, with the strong side first.
+const SCALERS: [(&str, Scaler); 2] = [
+    ("KNNvK", |_, _| 0.0),
+    ("KBPvK", kbpk),
+];
+
+//#################################################################################################
+//
+//                                       struct Signature
+//
+//#################################################################################################
+
+/// The material of a position, as the number of pieces of each kind and color but the
+/// kings, on 4 bits each. It is written like "KBNvK", white first.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Signature(u64);
+
+// ================================ impl
+
+impl Signature {
+    /// Computes the signature of the material on the board.
+    #[inline]
+    fn new(board: &Board) -> Signature {
+        let mut signature = 0;
+        for color in Color::COLORS {
+            for (piece, _) in PIECES {
+                let count = u64::from(board.get_bitboard(color, piece).count().min(15));
+                signature |= count << Signature::shift(color, piece);
+            }
+        }
+
+        Signature(signature)
+    }
+
+    /// Returns the number of pieces of that kind and color.
+    #[inline]
+    fn count(self, color: Color, piece: Piece) -> u8 {
+        (self.0 >> Signature::shift(color, piece) & 0xF) as u8
+    }
+
+    /// Parses a signature written like "KBNvK", white first.
+    fn parse(s: &str) -> Signature {
+        let mut signature = 0;
+        for (color, side) in Color::COLORS.iter().copied().zip(s.split('v')) {
+            for c in side.chars().filter(|&c| c != 'K') {
+                let (piece, _) = PIECES.iter().find(|&&(_, letter)| letter == c).expect("Invalid endgame signature.");
+                signature += 1 << Signature::shift(color, *piece);
+            }
+        }
+
+        Signature(signature)
+    }
+
+    /// Returns the signature of the same material with the colors swapped.
+    fn mirror(self) -> Signature {
+        Signature(self.0 >> 20 | (self.0 & 0xFFFFF) << 20)
+    }
+
+    /// Returns the position of the count of that kind of piece of that color.
+    #[inline]
+    fn shift(color: Color, piece: Piece) -> usize {
+        20 * usize::from(color) + 4 * usize::from(piece)
+    }
+}
+
+// ================================ traits impl
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for color in Color::COLORS {
+            if color == Color::Black {
+                write!(f, "v")?;
+            }
+
+            write!(f, "K")?;
+            for (piece, letter) in PIECES {
+                for _ in 0..self.count(color, piece) {
+                    write!(f, "{}", letter)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//#################################################################################################
+//
+//                                       struct Endgames
+//
+//#################################################################################################
+
+/// The endgame knowledge, indexed by signature with the strong side of each endgame.
+struct Endgames {
+    evaluators: HashMap<Signature, (Evaluator, Color)>,
+    scalers: HashMap<Signature, (Scaler, Color)>,
+    kpk: Vec<u64>,
+}
+
+/// The endgames, built once as the bitbase takes a little while to generate.
+static ENDGAMES: OnceLock<Endgames> = OnceLock::new();
+
+/// Returns the endgames, building them on first use.
+fn endgames() -> &'static Endgames {
+    ENDGAMES.get_or_init(|| {
+        let mut evaluators = HashMap::new();
+        for (s, evaluator) in EVALUATORS {
+            evaluators.insert(Signature::parse(s), (evaluator, Color::White));
+            evaluators.insert(Signature::parse(s).mirror(), (evaluator, Color::Black));
+        }
+
+        let mut scalers = HashMap::new();
+        for (s, scaler) in SCALERS {
+            scalers.insert(Signature::parse(s), (scaler, Color::White));
+            scalers.insert(Signature::parse(s).mirror(), (scaler, Color::Black));
+        }
+
+        Endgames {
+            evaluators,
+            scalers,
+            kpk: generate_kpk(),
+        }
+    })
+}
+
+/// Builds the endgame tables, so that the first search does not wait for them.
+pub(crate) fn init() {
+    endgames();
+}
+
+/// Returns the specialized evaluation of the position from the point of view of the
+/// strong side, and that side, if the endgame is known.
+pub(crate) fn evaluate(board: &Board) -> Option<(f32, Color)> {
+    let signature = Signature::new(board);
+    if let Some(&(evaluator, strong)) = endgames().evaluators.get(&signature) {
+        return Some((evaluator(board, strong), strong));
+    }
+
+    // A bare king against a queen or a rook, with any other pieces.
+    Color::COLORS.iter().copied().find(|&strong| {
+        let weak = strong.invert();
+        PIECES.iter().all(|&(piece, _)| signature.count(weak, piece) == 0)
+            && (signature.count(strong, Piece::Queen) > 0 || signature.count(strong, Piece::Rook) > 0)
+    }).map(|strong| (kxk(board, strong), strong))
+}
+
+/// Returns the factor scaling the endgame values of the strong side, between 0 for a
+/// draw and 1 when the material is not drawish.
+pub(crate) fn scale(board: &Board, strong: Color) -> f32 {
+    let signature = Signature::new(board);
+    if let Some(&(scaler, color)) = endgames().scalers.get(&signature) {
+        if color == strong {
+            return scaler(board, strong);
+        }
+    }
+
+    let weak = strong.invert();
+    let material = |color| {
+        [Piece::Rook, Piece::Knight, Piece::Bishop, Piece::Queen].iter().copied()
+            .map(|piece| f32::from(signature.count(color, piece)) * params::value_of(piece))
+            .sum::<f32>()
+    };
+
+    // Without pawns, a minor piece is not enough to mate, and more is hard with little advantage.
+    if signature.count(strong, Piece::Pawn) == 0 && material(strong) - material(weak) <= params::value_of(Piece::Bishop) {
+        return if material(strong) <= params::value_of(Piece::Bishop) {0.0} else {0.25};
+    }
+
+    // Bishops of opposite colors, and no other pieces.
+    let bishops = |color| board.get_bitboard(color, Piece::Bishop);
+    let others = |color| [Piece::Rook, Piece::Knight, Piece::Queen].iter().copied().all(|piece| signature.count(color, piece) == 0);
+    if bishops(strong).count() == 1 && bishops(weak).count() == 1 && others(strong) && others(weak) {
+        let parity = |color| bishops(color).iter_squares().next().unwrap().parity();
+        if parity(strong) != parity(weak) {
+            return 0.5;
+        }
+    }
+
+    1.0
+}
+
+//#################################################################################################
+//
+//                                          evaluators
+//
+//#################################################################################################
+
+/// Returns the square of the king of that color.
+#[inline]
+fn king(board: &Board, color: Color) -> Square {
+    board.get_bitboard(color, Piece::King).iter_squares().next().unwrap()
+}
+
+/// Returns the distance in king moves from the square to the closest edge, from 0 to 3.
+#[inline]
+fn edge_distance(sq: Square) -> f32 {
+    sq.x().min(7 - sq.x()).min(sq.y()).min(7 - sq.y()) as f32
+}
+
+/// Returns the value of the pieces of that color but the king.
+fn material(board: &Board, color: Color) -> f32 {
+    PIECES.iter()
+        .map(|&(piece, _)| f32::from(board.get_bitboard(color, piece).count()) * params::value_of(piece))
+        .sum()
+}
+
+/// A bare king against mating material: the weak king is driven to the edge, and the
+/// strong king comes closer to it.
+fn kxk(board: &Board, strong: Color) -> f32 {
+    let (ours, theirs) = (king(board, strong), king(board, strong.invert()));
+
+    KNOWN_WIN + material(board, strong)
+        + 0.3 * (3.0 - edge_distance(theirs))
+        + 0.1 * (7 - distance(ours, theirs)) as f32
+}
+
+/// A bare king against a bishop and a knight: the weak king is driven to a corner of
+/// the color of the bishop, the only ones where it can be mated.
+fn kbnk(board: &Board, strong: Color) -> f32 {
+    let (ours, theirs) = (king(board, strong), king(board, strong.invert()));
+    let bishop = board.get_bitboard(strong, Piece::Bishop).iter_squares().next().unwrap();
+    let knight = board.get_bitboard(strong, Piece::Knight).iter_squares().next().unwrap();
+
+    // The table is built for a dark-squared bishop, with the a1 and h8 corners.
+    let sq = if bishop.parity() == Color::Black {theirs as usize} else {theirs as usize ^ 7};
+
+    // The strong king must stay close to drive the weak one along the edge.
+    KNOWN_WIN + material(board, strong)
+        + PUSH_TO_CORNERS[sq]
+        + 0.7 * (7 - distance(ours, theirs)) as f32
+        + 0.1 * (7 - distance(knight, theirs)) as f32
+}
+
+/// A king and a pawn against a bare king, looked up in the bitbase: won positions are
+/// scored by the advance of the pawn, the others are draws.
+fn kpk(board: &Board, strong: Color) -> f32 {
+    let pawn = board.get_bitboard(strong, Piece::Pawn).iter_squares().next().unwrap();
+    let (ours, theirs) = (king(board, strong), king(board, strong.invert()));
+
+    // The bitbase is built for white pawns on the files a to d.
+    let normalize = |sq: Square| {
+        let sq = if strong == Color::White {sq as usize} else {sq as usize ^ 56};
+        if pawn.x() >= 4 {sq ^ 7} else {sq}
+    };
+    let us = if board.get_side_to_move() == strong {Color::White} else {Color::Black};
+
+    if probe_kpk(&endgames().kpk, us, normalize(ours), normalize(theirs), normalize(pawn)) {
+        KNOWN_WIN + params::value_of(Piece::Pawn) + 0.2 * relative_rank(strong, pawn) as f32
+    } else {
+        0.0
+    }
+}
+
+/// A rook against a pawn: won when the strong king stands in front of the pawn or the
+/// weak king is too far from it, else close to a draw the further the pawn is advanced.
+fn krkp(board: &Board, strong: Color) -> f32 {
+    let weak = strong.invert();
+    let (ours, theirs) = (king(board, strong), king(board, weak));
+    let rook = board.get_bitboard(strong, Piece::Rook).iter_squares().next().unwrap();
+    let pawn = board.get_bitboard(weak, Piece::Pawn).iter_squares().next().unwrap();
+    let queening = Square::from((pawn.x(), if weak == Color::White {7} else {0}));
+    let tempo = |color| if board.get_side_to_move() == color {1} else {0};
+
+    if ours.x() == pawn.x() && relative_rank(weak, ours) > relative_rank(weak, pawn) {
+        return params::value_of(Piece::Rook) - 0.1 * distance(ours, pawn) as f32;
+    }
+
+    if distance(theirs, pawn) >= 3 + tempo(weak) && distance(theirs, rook) >= 3 {
+        return params::value_of(Piece::Rook) - 0.1 * distance(ours, pawn) as f32;
+    }
+
+    let push = attacks::pawn_push(weak, pawn).unwrap_or(pawn);
+    if relative_rank(strong, theirs) <= 2 && distance(theirs, pawn) == 1 && relative_rank(strong, ours) >= 3 && distance(ours, pawn) > 2 + tempo(strong) {
+        return 0.4 - 0.04 * distance(ours, pawn) as f32;
+    }
+
+    1.0 - 0.04 * (distance(ours, push) - distance(theirs, push) - distance(pawn, queening)) as f32
+}
+
+/// A bishop and a rook pawn against a bare king: a draw when the bishop does not control
+/// the queening square and the weak king stands next to it.
+fn kbpk(board: &Board, strong: Color) -> f32 {
+    let pawn = board.get_bitboard(strong, Piece::Pawn).iter_squares().next().unwrap();
+    let bishop = board.get_bitboard(strong, Piece::Bishop).iter_squares().next().unwrap();
+    let queening = Square::from((pawn.x(), if strong == Color::White {7} else {0}));
+
+    let rook_pawn = pawn.x() == 0 || pawn.x() == 7;
+    if rook_pawn && bishop.parity() != queening.parity() && distance(king(board, strong.invert()), queening) <= 1 {
+        0.0
+    } else {
+        1.0
+    }
+}
+
+//#################################################################################################
+//
+//                                          kpk bitbase
+//
+//#################################################################################################
+
+/// The number of positions of the bitbase: the side to move, the two kings and the white
+/// pawn on the files a to d and the ranks 2 to 7.
+const KPK_SIZE: usize = 2 * 64 * 64 * 24;
+
+/// The results of the positions during the generation of the bitbase, as flags.
+const INVALID: u8 = 0;
+const UNKNOWN: u8 = 1;
+const DRAW: u8 = 2;
+const WIN: u8 = 4;
+
+/// Returns the index of a position in the bitbase.
+#[inline]
+fn kpk_index(us: Color, white: usize, black: usize, pawn: usize) -> usize {
+    ((usize::from(us) * 64 + white) * 64 + black) * 24 + (pawn / 8 - 1) * 4 + pawn % 8
+}
+
+/// Returns true if white wins the position with its king and pawn, with the pawn on
+/// the files a to d.
+#[inline]
+fn probe_kpk(kpk: &[u64], us: Color, white: usize, black: usize, pawn: usize) -> bool {
+    let i = kpk_index(us, white, black, pawn);
+    kpk[i / 64] >> (i % 64) & 1 != 0
+}
+
+/// Generates the bitbase of the king and pawn against king endgame, by classifying the
+/// positions from their successors until none changes.
+fn generate_kpk() -> Vec<u64> {
+    let squares = |bb: chess::bitboard::BitBoard| bb.iter_squares().map(|sq| sq as usize);
+    let king_attacks = |sq: usize| attacks::king(Square::SQUARES[sq]);
+    let pawn_attacks = |sq: usize| attacks::pawn(Color::White, Square::SQUARES[sq]);
+    let dist = |a: usize, b: usize| distance(Square::SQUARES[a], Square::SQUARES[b]);
+
+    let mut positions = vec![INVALID; KPK_SIZE];
+    let mut all = Vec::with_capacity(KPK_SIZE);
+
+    for us in Color::COLORS {
+        for white in 0..64 {
+            for black in 0..64 {
+                for pawn in (8..56).filter(|sq| sq % 8 < 4) {
+                    all.push((us, white, black, pawn));
+                    let stop = pawn + 8;
+
+                    positions[kpk_index(us, white, black, pawn)] = if dist(white, black) <= 1 || white == pawn || black == pawn
+                        || (us == Color::White && pawn_attacks(pawn).contains(Square::SQUARES[black])) {
+                        INVALID
+                    } else if us == Color::White && pawn / 8 == 6 && white != stop && black != stop
+                        && (dist(black, stop) > 1 || dist(white, stop) == 1) {
+                        // The pawn promotes and the queen cannot be taken.
+                        WIN
+                    } else if us == Color::Black && ((king_attacks(black) & !(king_attacks(white) | pawn_attacks(pawn))).empty()
+                        || (king_attacks(black).contains(Square::SQUARES[pawn]) && !king_attacks(white).contains(Square::SQUARES[pawn]))) {
+                        // Stalemate, or the pawn is taken.
+                        DRAW
+                    } else {
+                        UNKNOWN
+                    };
+                }
+            }
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for &(us, white, black, pawn) in &all {
+            let i = kpk_index(us, white, black, pawn);
+            if positions[i] != UNKNOWN {
+                continue;
+            }
+
+            let mut results = 0;
+            if us == Color::White {
+                for to in squares(king_attacks(white)) {
+                    results |= positions[kpk_index(Color::Black, to, black, pawn)];
+                }
+
+                if pawn / 8 < 6 {
+                    results |= positions[kpk_index(Color::Black, white, black, pawn + 8)];
+                }
+                if pawn / 8 == 1 && pawn + 8 != white && pawn + 8 != black {
+                    results |= positions[kpk_index(Color::Black, white, black, pawn + 16)];
+                }
+            } else {
+                for to in squares(king_attacks(black)) {
+                    results |= positions[kpk_index(Color::White, white, to, pawn)];
+                }
+            }
+
+            // Each side goes for its best result, a win for white and a draw for black.
+            let (good, bad) = if us == Color::White {(WIN, DRAW)} else {(DRAW, WIN)};
+            positions[i] = if results & good != 0 {
+                good
+            } else if results & UNKNOWN != 0 {
+                UNKNOWN
+            } else {
+                bad
+            };
+
+            changed |= positions[i] != UNKNOWN;
+        }
+    }
+
+    let mut kpk = vec![0; KPK_SIZE.div_ceil(64)];
+    for (i, &result) in positions.iter().enumerate() {
+        if result == WIN {
+            kpk[i / 64] |= 1 << (i % 64);
+        }
+    }
+
+    kpk
+}
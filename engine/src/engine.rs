@@ -1,14 +1,192 @@
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
 use std::sync::{Arc, Barrier, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
+use serde::Serialize;
+
 use chess::board::Board;
 use chess::moves::{AtomicMove, Move};
 
-use crate::params;
+use crate::{endgame, eval};
+use crate::nnue::Network;
+use crate::params::{self, EvalTerms, Extensions, Pruning};
 use crate::search::Search;
 use crate::table::TranspositionTable;
+use crate::trace::EvalTrace;
+use crate::weights::EvalParams;
+
+//#################################################################################################
+//
+//                                          struct Line
+//
+//#################################################################################################
+
+/// A line found by the engine: a root move, its score and the principal variation
+/// starting with it, as searched at some depth.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Line {
+    #[serde(rename = "move")]
+    mv: Move,
+    score: f32,
+    depth: u8,
+    seldepth: u8,
+    pv: Vec<Move>,
+}
+
+// ================================ pub impl
+
+impl Line {
+    /// Returns the root move of the line.
+    #[inline]
+    pub fn get_move(&self) -> Move {
+        self.mv
+    }
+
+    /// Returns the score of the line from the point of view of the side to move, in pawns.
+    #[inline]
+    pub fn get_score(&self) -> f32 {
+        self.score
+    }
+
+    /// Returns the depth the line was searched to.
+    #[inline]
+    pub fn get_depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Returns the highest ply the search of the line reached before its quiescent
+    /// search, above the depth when moves were extended.
+    #[inline]
+    pub fn get_seldepth(&self) -> u8 {
+        self.seldepth
+    }
+
+    /// Returns the principal variation, starting with the root move.
+    #[inline]
+    pub fn get_pv(&self) -> &[Move] {
+        &self.pv
+    }
+}
+
+// ================================ pub(crate) impl
+
+impl Line {
+    /// Creates a new line.
+    #[inline]
+    pub(crate) fn new(score: f32, depth: u8, seldepth: u8, pv: Vec<Move>) -> Line {
+        Line {
+            mv: pv[0],
+            score,
+            depth,
+            seldepth,
+            pv,
+        }
+    }
+}
+
+//#################################################################################################
+//
+//                                        struct RootMove
+//
+//#################################################################################################
+
+/// The kind of score of a root move, searched within a window.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bound {
+    /// The score is exact, it fell within the window.
+    Exact,
+    /// The score is at most that, the move failed low.
+    Upper,
+    /// The score is at least that, the move failed high.
+    Lower,
+}
+
+/// A legal move of the root position, with its score at the last depth searched and
+/// the number of nodes searched after it.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct RootMove {
+    #[serde(rename = "move")]
+    mv: Move,
+    score: f32,
+    bound: Bound,
+    stale: bool,
+    nodes: u64,
+}
+
+// ================================ pub impl
+
+impl RootMove {
+    /// Returns the move.
+    #[inline]
+    pub fn get_move(&self) -> Move {
+        self.mv
+    }
+
+    /// Returns the score of the move from the point of view of the side to move, in pawns.
+    /// It is exact for the best moves, and usually an upper bound for the others.
+    #[inline]
+    pub fn get_score(&self) -> f32 {
+        self.score
+    }
+
+    /// Returns the kind of the score: exact, or a bound of the window it was searched with.
+    #[inline]
+    pub fn get_bound(&self) -> Bound {
+        self.bound
+    }
+
+    /// Returns true if the score is the one of a previous search, the move being left
+    /// out by a cutoff at the root.
+    #[inline]
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Returns the number of nodes searched after the move.
+    #[inline]
+    pub fn get_nodes(&self) -> u64 {
+        self.nodes
+    }
+}
+
+// ================================ pub(crate) impl
+
+impl RootMove {
+    /// Creates a new root move, not searched yet.
+    #[inline]
+    pub(crate) fn new(mv: Move) -> RootMove {
+        RootMove {
+            mv,
+            score: f32::NEG_INFINITY,
+            bound: Bound::Upper,
+            stale: true,
+            nodes: 0,
+        }
+    }
+
+    /// Records the score of a search of the move within the window, and the nodes it took.
+    #[inline]
+    pub(crate) fn update(&mut self, score: f32, alpha: f32, beta: f32, nodes: u64) {
+        self.score = score;
+        self.bound = if score <= alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.stale = false;
+        self.nodes += nodes;
+    }
+
+    /// Marks the score as the one of a previous search.
+    #[inline]
+    pub(crate) fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+}
 
 //#################################################################################################
 //
@@ -26,9 +204,20 @@ pub(crate) struct GlobalInfo {
     table: TranspositionTable,
     search_depth: AtomicU8,
     search_id: AtomicU8,
+    nodes: AtomicU64,
     best_move: AtomicMove,
+    lines: RwLock<Vec<Line>>,
+    root_moves: RwLock<Vec<RootMove>>,
+    multi_pv: AtomicUsize,
+    search_moves: RwLock<Vec<Move>>,
 
     board: RwLock<Board>,
+    network: RwLock<Option<Arc<Network>>>,
+    weights: EvalParams,
+    eval_terms: RwLock<EvalTerms>,
+    contempt: RwLock<f32>,
+    pruning: RwLock<Pruning>,
+    extensions: RwLock<Extensions>,
 }
 
 // ================================ pub(crate) impl
@@ -46,6 +235,54 @@ impl GlobalInfo {
         self.board.read().unwrap().clone()
     }
 
+    /// Returns the weights of the handcrafted evaluation.
+    #[inline]
+    pub(crate) fn get_weights(&self) -> &EvalParams {
+        &self.weights
+    }
+
+    /// Returns the optional terms of the handcrafted evaluation.
+    #[inline]
+    pub(crate) fn eval_terms(&self) -> EvalTerms {
+        *self.eval_terms.read().unwrap()
+    }
+
+    /// Returns the network evaluating standard chess positions, if any.
+    #[inline]
+    pub(crate) fn network(&self) -> Option<Arc<Network>> {
+        self.network.read().unwrap().clone()
+    }
+
+    /// Returns the value in pawns the engine gives up by drawing.
+    #[inline]
+    pub(crate) fn contempt(&self) -> f32 {
+        *self.contempt.read().unwrap()
+    }
+
+    /// Returns the forward pruning heuristics of the search.
+    #[inline]
+    pub(crate) fn pruning(&self) -> Pruning {
+        *self.pruning.read().unwrap()
+    }
+
+    /// Returns the extensions of the search.
+    #[inline]
+    pub(crate) fn extensions(&self) -> Extensions {
+        *self.extensions.read().unwrap()
+    }
+
+    /// Returns the number of lines to search.
+    #[inline]
+    pub(crate) fn multi_pv(&self) -> usize {
+        self.multi_pv.load(Ordering::Relaxed)
+    }
+
+    /// Returns the root moves the search is restricted to, all of them if empty.
+    #[inline]
+    pub(crate) fn search_moves(&self) -> Vec<Move> {
+        self.search_moves.read().unwrap().clone()
+    }
+
     /// Returns true if the engine is currently searching.
     #[inline]
     pub(crate) fn is_searching(&self) -> bool {
@@ -91,22 +328,33 @@ impl GlobalInfo {
         1 + depth + (id + 1).trailing_zeros() as u8 
     }
 
-    /// Report back a move, stores if it was searched at a deeper depth
-    /// than the current one, and subsequently increase the base search depth.
+    /// Adds the nodes searched by a thread to the total.
     #[inline]
-    pub(crate) fn report_move(&self, mv: Move, depth: u8) {
-        self.search_depth.fetch_update(
-            Ordering::SeqCst,
-            Ordering::SeqCst,
-            |cur_depth| {
-                if depth <= cur_depth {
-                    None
-                } else {
-                    self.best_move.store(mv);
-                    Some(depth)
-                }
-            }
-        ).ok();
+    pub(crate) fn add_nodes(&self, nodes: u64) {
+        self.nodes.fetch_add(nodes, Ordering::Relaxed);
+    }
+
+    /// Report back the lines and the root moves of a thread, the best first, stores them
+    /// if they were searched at a deeper depth than the current one, and subsequently
+    /// increase the base search depth.
+    pub(crate) fn report_lines(&self, lines: Vec<Line>, root_moves: &[RootMove], depth: u8) {
+        // The lock orders the reports of the threads.
+        let mut current = self.lines.write().unwrap();
+        if depth > self.search_depth() {
+            self.best_move.store(lines[0].mv);
+            *current = lines;
+            *self.root_moves.write().unwrap() = root_moves.to_vec();
+            self.search_depth.store(depth, Ordering::Release);
+        }
+    }
+
+    /// Forgets the results of the search, for the next one to start from scratch.
+    pub(crate) fn reset(&self) {
+        self.search_depth.store(0, Ordering::Release);
+        self.search_id.store(0, Ordering::Release);
+        self.best_move.reset();
+        self.lines.write().unwrap().clear();
+        self.root_moves.write().unwrap().clear();
     }
 }
 
@@ -128,6 +376,12 @@ pub struct Engine {
 impl Engine {
     /// Initializes a new chess engine, working on a board.
     pub fn new(board: Board) -> Engine {
+        Engine::with_weights(board, EvalParams::default())
+    }
+
+    /// Initializes a new chess engine, working on a board and evaluating
+    /// positions with the given weights.
+    pub fn with_weights(board: Board, weights: EvalParams) -> Engine {
         // Construct the initial info object.
         let info = Arc::new(GlobalInfo {
             barrier: Barrier::new(params::NUM_SEARCH_THREAD + 1),
@@ -137,11 +391,25 @@ impl Engine {
             table: TranspositionTable::new(),
             search_depth: AtomicU8::new(0),
             search_id: AtomicU8::new(0),
+            nodes: AtomicU64::new(0),
             best_move: AtomicMove::default(),
+            lines: RwLock::new(Vec::new()),
+            root_moves: RwLock::new(Vec::new()),
+            multi_pv: AtomicUsize::new(params::MULTI_PV),
+            search_moves: RwLock::new(Vec::new()),
 
             board: RwLock::new(board),
+            network: RwLock::new(None),
+            weights,
+            eval_terms: RwLock::new(params::EVAL_TERMS),
+            contempt: RwLock::new(params::CONTEMPT),
+            pruning: RwLock::new(params::PRUNING),
+            extensions: RwLock::new(params::EXTENSIONS),
         });
 
+        // Builds the endgame tables before the first search.
+        endgame::init();
+
         // Initializes the thread pool.
         let handles = (0..params::NUM_SEARCH_THREAD).map(|_| {
             let info = info.clone();
@@ -176,6 +444,19 @@ impl Engine {
         self.info.wait();
     }
 
+    /// Searches the position to that depth on the calling thread, and returns once done.
+    /// The search starts from an empty table with a single thread: it is reproducible.
+    /// Stops the search if it is on and resets the search informations.
+    pub fn search_fixed_depth(&self, depth: u8) {
+        self.stop();
+        self.info.reset();
+        self.info.get_table().clear();
+
+        self.info.searching.store(true, Ordering::Release);
+        Search::new(self.info.clone()).search_fixed_depth(depth.min(params::MAX_DEPTH));
+        self.info.searching.store(false, Ordering::Release);
+    }
+
     /// Stops the engine if it is searching.
     /// Search may be resumed by calling start() again.
     pub fn stop(&self) {
@@ -199,11 +480,120 @@ impl Engine {
         self.info.best_move.load()
     }
 
+    /// Returns the number of nodes searched by all the threads since the board was written.
+    pub fn get_nodes(&self) -> u64 {
+        self.info.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Returns the lines found at the current best depth searched, the best first.
+    pub fn get_lines(&self) -> Vec<Line> {
+        self.info.lines.read().unwrap().clone()
+    }
+
+    /// Returns the legal root moves searched at the current best depth, the best first,
+    /// with their scores and the number of nodes the reporting thread spent on them.
+    pub fn get_root_moves(&self) -> Vec<RootMove> {
+        self.info.root_moves.read().unwrap().clone()
+    }
+
+    /// Restricts the search to those root moves, or searches all of them if there are
+    /// none or none is legal. The restriction is lifted when the board is written.
+    /// Stops the search if it is on and resets the search informations.
+    pub fn set_search_moves(&self, moves: &[Move]) {
+        self.stop();
+        self.info.reset();
+        *self.info.search_moves.write().unwrap() = moves.to_vec();
+    }
+
+    /// Returns the root moves the search is restricted to, all of them if empty.
+    pub fn get_search_moves(&self) -> Vec<Move> {
+        self.info.search_moves()
+    }
+
+    /// Sets the number of lines searched, the best one and its alternatives, at least one.
+    /// Stops the search if it is on.
+    pub fn set_multi_pv(&self, lines: usize) {
+        self.stop();
+        self.info.multi_pv.store(lines.max(1), Ordering::Relaxed);
+    }
+
+    /// Returns the number of lines searched.
+    pub fn get_multi_pv(&self) -> usize {
+        self.info.multi_pv()
+    }
+
     /// Returns the current best depth searched.
     pub fn get_current_depth(&self) -> u8 {
         self.info.search_depth()
     }
 
+    /// Sets the network evaluating standard chess positions, or goes back to the
+    /// handcrafted evaluation if it is None. Stops the search if it is on.
+    pub fn set_network(&self, network: Option<Arc<Network>>) {
+        self.stop();
+        *self.info.network.write().unwrap() = network;
+    }
+
+    /// Returns true if positions of standard chess are evaluated by a network.
+    pub fn has_network(&self) -> bool {
+        self.info.network.read().unwrap().is_some()
+    }
+
+    /// Sets the contempt, the value in pawns the engine gives up by drawing: positive to
+    /// avoid draws against weaker opponents, negative to seek them against stronger ones.
+    /// Stops the search if it is on.
+    pub fn set_contempt(&self, contempt: f32) {
+        self.stop();
+        *self.info.contempt.write().unwrap() = contempt;
+    }
+
+    /// Returns the contempt of the engine, in pawns.
+    pub fn get_contempt(&self) -> f32 {
+        self.info.contempt()
+    }
+
+    /// Sets the optional terms of the handcrafted evaluation, to measure what they are worth.
+    /// Stops the search if it is on.
+    pub fn set_eval_terms(&self, terms: EvalTerms) {
+        self.stop();
+        *self.info.eval_terms.write().unwrap() = terms;
+    }
+
+    /// Returns the optional terms of the handcrafted evaluation.
+    pub fn get_eval_terms(&self) -> EvalTerms {
+        self.info.eval_terms()
+    }
+
+    /// Sets the forward pruning heuristics of the search, to measure what they are worth.
+    /// Stops the search if it is on.
+    pub fn set_pruning(&self, pruning: Pruning) {
+        self.stop();
+        *self.info.pruning.write().unwrap() = pruning;
+    }
+
+    /// Returns the forward pruning heuristics of the search.
+    pub fn get_pruning(&self) -> Pruning {
+        self.info.pruning()
+    }
+
+    /// Sets the extensions of the search, to measure what they are worth.
+    /// Stops the search if it is on.
+    pub fn set_extensions(&self, extensions: Extensions) {
+        self.stop();
+        *self.info.extensions.write().unwrap() = extensions;
+    }
+
+    /// Returns the extensions of the search.
+    pub fn get_extensions(&self) -> Extensions {
+        self.info.extensions()
+    }
+
+    /// Returns the breakdown of the handcrafted evaluation of the board with the weights
+    /// and terms of the engine, even when positions are evaluated by a network.
+    pub fn eval_trace(&self, board: &Board) -> EvalTrace {
+        eval::trace(board, self.info.get_weights(), self.info.eval_terms())
+    }
+
     /// Returns a read lock to the board.
     pub fn read_board(&self) -> RwLockReadGuard<'_, Board> {
         self.info.board.read().unwrap()
@@ -214,9 +604,9 @@ impl Engine {
     pub fn write_board(&self) -> RwLockWriteGuard<'_, Board> {
         self.stop();
 
-        self.info.search_depth.store(0, Ordering::Release);
-        self.info.search_id.store(0, Ordering::Release);
-        self.info.best_move.reset();
+        self.info.reset();
+        self.info.nodes.store(0, Ordering::Release);
+        self.info.search_moves.write().unwrap().clear();
 
         self.info.board.write().unwrap()
     }
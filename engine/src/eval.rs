@@ -1,120 +1,167 @@
 use chess::board::Board;
 use chess::color::Color;
 use chess::piece::Piece;
-
-use crate::{params, utils};
-
-const PAWNS: [f32; 64] = [
-	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-	0.05, 0.1, 0.1, -0.2, -0.2, 0.1, 0.1, 0.05,
-	0.05, -0.05, -0.1, 0.0, 0.0, -0.1, -0.05, 0.05,
-	0.0, 0.0, 0.0, 0.2, 0.2, 0.0, 0.0, 0.0,
-	0.05, 0.05, 0.1, 0.25, 0.25, 0.1, 0.05, 0.05,
-	0.1, 0.1, 0.2, 0.3, 0.3, 0.2, 0.1, 0.1,
-	0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5,
-	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-];
-
-const ROOKS: [f32; 64] = [
-	0.0, 0.0, 0.0, 0.05, 0.05, 0.0, 0.0, 0.0,
-	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
-	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
-	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
-	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
-	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
-	0.05, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.05,
-	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-];
-
-const KNIGHTS: [f32; 64] = [
-	-0.5, -0.4, -0.3, -0.3, -0.3, -0.3, -0.4, -0.5,
-	-0.4, -0.2, 0.0, 0.05, 0.05, 0.0, -0.2, -0.4,
-	-0.3, 0.05, 0.1, 0.15, 0.15, 0.1, 0.05, -0.3,
-	-0.3, 0.0, 0.15, 0.2, 0.2, 0.15, 0.0, -0.3,
-	-0.3, 0.05, 0.15, 0.2, 0.2, 0.15, 0.05, -0.3,
-	-0.3, 0.0, 0.1, 0.15, 0.15, 0.1, 0.0, -0.3,
-	-0.4, -0.2, 0.0, 0.0, 0.0, 0.0, -0.2, -0.4,
-	-0.5, -0.4, -0.3, -0.3, -0.3, -0.3, -0.4, -0.5,
-];
-
-const BISHOPS: [f32; 64] = [
-	-0.2, -0.1, -0.1, -0.1, -0.1, -0.1, -0.1, -0.2,
-	-0.1, 0.05, 0.0, 0.0, 0.0, 0.0, 0.05, -0.1,
-	-0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1, -0.1,
-	-0.1, 0.0, 0.1, 0.1, 0.1, 0.1, 0.0, -0.1,
-	-0.1, 0.05, 0.05, 0.1, 0.1, 0.05, 0.05, -0.1,
-	-0.1, 0.0, 0.05, 0.1, 0.1, 0.05, 0.0, -0.1,
-	-0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.1,
-	-0.2, -0.1, -0.1, -0.1, -0.1, -0.1, -0.1, -0.2,
-];
-
-const QUEENS: [f32; 64] = [
-	-0.2, -0.1, -0.1, -0.05, -0.05, -0.1, -0.1, -0.2,
-	-0.1, 0.0, 0.05, 0.0, 0.0, 0.0, 0.0, -0.1,
-	-0.1, 0.05, 0.05, 0.05, 0.05, 0.05, 0.0, -0.1,
-	0.0, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.05,
-	-0.05, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.05,
-	-0.1, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.1,
-	-0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.1,
-	-0.2, -0.1, -0.1, -0.05, -0.05, -0.1, -0.1, -0.2,
-];
-
-const KINGS_EARLY: [f32; 64] = [
-	0.2, 0.3, 0.1, 0.0, 0.0, 0.1, 0.3, 0.2,
-	0.2, 0.2, 0.0, 0.0, 0.0, 0.0, 0.2, 0.2,
-	-0.1, -0.2, -0.2, -0.2, -0.2, -0.2, -0.2, -0.1,
-	-0.2, -0.3, -0.3, -0.4, -0.4, -0.3, -0.3, -0.2,
-	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
-	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
-	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
-	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
-];
-
-
-const KINGS_ENDGAME: [f32; 64] = [
-	-0.5, -0.3, -0.3, -0.3, -0.3, -0.3, -0.3, -0.5,
-	-0.3, -0.3, 0.0, 0.0, 0.0, 0.0, -0.3, -0.3,
-	-0.3, -0.1, 0.2, 0.3, 0.3, 0.2, -0.1, -0.3,
-	-0.3, -0.1, 0.3, 0.4, 0.4, 0.3, -0.1, -0.3,
-	-0.3, -0.1, 0.3, 0.4, 0.4, 0.3, -0.1, -0.3,
-	-0.3, -0.1, 0.2, 0.3, 0.3, 0.2, -0.1, -0.3,
-	-0.3, -0.2, -0.1, 0.0, 0.0, -0.1, -0.2, -0.3,
-	-0.5, -0.4, -0.3, -0.2, -0.2, -0.3, -0.4, -0.5,
-];
-
-const TABLES: [[f32; 64]; 5] = [
-    PAWNS, ROOKS, KNIGHTS,
-    BISHOPS, QUEENS
-];
-
-/// The evaluation function.
-pub(crate) fn eval(board: &Board) -> f32 {
-    let mut score = 0.0;
-
-    for &piece in &Piece::PIECES[..5] {
-        for sq in board.get_bitboard(Color::White, piece).iter_squares() {
-            score += params::value_of(piece) + TABLES[usize::from(piece)][usize::from(sq)];
-        }
-        
-        for sq in board.get_bitboard(Color::Black, piece).iter_squares() {
-            score -= params::value_of(piece) + TABLES[usize::from(piece)][63 - usize::from(sq)];
-        }  
+use chess::square::Square;
+use chess::variant::Variant;
+
+use crate::activity::eval_activity;
+use crate::endgame;
+use crate::params::EvalTerms;
+use crate::pawns::{PawnEntry, PawnTable};
+use crate::trace::{EvalTrace, Term, Trace};
+use crate::weights::EvalParams;
+
+/// The evaluation function, caching the pawn structures in the pawn table.
+pub(crate) fn eval(board: &Board, pawns: &mut PawnTable, weights: &EvalParams, terms: EvalTerms) -> f32 {
+    let score = eval_white(board, || pawns.probe(board, weights), weights, terms, &mut ());
+
+    if board.get_side_to_move() == Color::White {score} else {-score}
+}
+
+/// Evaluates the position from the point of view of the side to move, in pawns,
+/// like the search does.
+pub fn evaluate(board: &Board) -> f32 {
+    evaluate_with(board, &EvalParams::DEFAULT)
+}
+
+/// Evaluates the position like evaluate(), with the given weights.
+pub fn evaluate_with(board: &Board, weights: &EvalParams) -> f32 {
+    let score = eval_white(board, || PawnEntry::new(board, weights), weights, EvalTerms::default(), &mut ());
+
+    if board.get_side_to_move() == Color::White {score} else {-score}
+}
+
+/// Evaluates the position with the given weights and terms, recording the contribution of each term.
+pub(crate) fn trace(board: &Board, weights: &EvalParams, terms: EvalTerms) -> EvalTrace {
+    let mut trace = EvalTrace::new(phase(board));
+    let score = eval_white(board, || PawnEntry::new(board, weights), weights, terms, &mut trace);
+
+    trace.set_score(score);
+    trace
+}
+
+/// Evaluates the position from white's point of view, with the specialized evaluations
+/// of the known endgames in standard chess. The pawn structure is only computed if needed.
+fn eval_white(board: &Board, pawns: impl FnOnce() -> PawnEntry, weights: &EvalParams, terms: EvalTerms, trace: &mut impl Trace) -> f32 {
+    match board.get_variant() {
+        Variant::Antichess => eval_antichess(board, trace),
+        Variant::Standard => match endgame::evaluate(board) {
+            Some((score, strong)) => {
+                trace.add(Term::Endgame, strong, score, score);
+                if strong == Color::White {score} else {-score}
+            },
+            None => eval_standard(board, &pawns(), weights, terms, trace),
+        },
+        _ => eval_standard(board, &pawns(), weights, terms, trace),
     }
+}
+
+/// The evaluation of orthodox-like variants, from white's point of view. Every material
+/// and positional term has a midgame and an endgame value, interpolated by the phase.
+fn eval_standard(board: &Board, pawns: &PawnEntry, weights: &EvalParams, terms: EvalTerms, trace: &mut impl Trace) -> f32 {
+    let mut score = Tapered {mg: 0.0, eg: 0.0, trace};
+
+    for color in Color::COLORS {
+        // Kings are iterated over, as they may have exploded in atomic.
+        for &piece in &Piece::PIECES {
+            let [table_mg, table_eg] = &weights.tables[usize::from(piece)];
+            let [value_mg, value_eg] = weights.material.get(usize::from(piece)).copied().unwrap_or([0.0; 2]);
+            let pieces = board.get_bitboard(color, piece);
+
+            // The pieces in hand count as material, in crazyhouse.
+            let count = f32::from(pieces.count()) + f32::from(board.get_pocket(color, piece));
+            score.add(Term::Material, color, value_mg * count, value_eg * count);
+
+            let (mut mg, mut eg) = (0.0, 0.0);
+            for sq in pieces.iter_squares() {
+                let i = if color == Color::White {usize::from(sq)} else {63 - usize::from(sq)};
+                mg += table_mg[i];
+                eg += table_eg[i];
+            }
+            score.add(Term::PieceSquares, color, mg, eg);
+        }
 
-	let white_king_sq = utils::king_sq_color(board, Color::White);
-	let black_king_sq = utils::king_sq_color(board, Color::Black);
-
-    if utils::is_endgame(board) {
-        score += KINGS_ENDGAME[usize::from(white_king_sq)];
-        score -= KINGS_ENDGAME[63 - usize::from(black_king_sq)];
-    } else {
-        score += KINGS_EARLY[usize::from(white_king_sq)];
-        score -= KINGS_EARLY[63 - usize::from(black_king_sq)];
+        // The pawn structure, and the kings escorting or stopping the passed pawns.
+        let [pawns_mg, pawns_eg] = pawns.get_score(color);
+        score.add(Term::Pawns, color, pawns_mg, pawns_eg + pawns.king_distance(board, color, weights));
+
+        // The mobility and placement of the pieces, and the safety of the kings.
+        eval_activity(board, color, weights, terms, &mut score);
+
+        // The variant bonuses are not interpolated.
+        let bonus = match board.get_variant() {
+            Variant::KingOfTheHill => board.get_bitboard(color, Piece::King).iter_squares()
+                .map(|sq| weights.hill.get(hill_distance(sq)).unwrap_or(&0.0))
+                .sum(),
+            Variant::ThreeCheck => weights.checks[usize::from(board.get_checks(color).min(2))],
+            _ => 0.0,
+        };
+        score.add(Term::Variant, color, bonus, bonus);
     }
 
-	if board.get_side_to_move() == Color::Black {
-		score = -score;
-	}
+    // The endgame values of the side ahead are scaled down with drawish material.
+    let scale = match board.get_variant() {
+        Variant::Standard => endgame::scale(board, if score.eg >= 0.0 {Color::White} else {Color::Black}),
+        _ => 1.0,
+    };
+    score.scale(scale);
+
+    let phase = phase(board);
+    score.mg * phase + score.eg * scale * (1.0 - phase)
+}
+
+/// The evaluation of antichess, from white's point of view: the fewer pieces, the better.
+fn eval_antichess(board: &Board, trace: &mut impl Trace) -> f32 {
+    let occ = board.get_occupancy();
+    let (white, black) = (occ.colored(Color::White).count() as f32, occ.colored(Color::Black).count() as f32);
+    trace.add(Term::Material, Color::White, -white, -white);
+    trace.add(Term::Material, Color::Black, -black, -black);
+
+    black - white
+}
+
+/// Returns the weight of the midgame values, from 1 with all the pieces to 0 with none.
+#[inline]
+fn phase(board: &Board) -> f32 {
+    f32::from(board.get_phase().min(Board::MAX_PHASE)) / f32::from(Board::MAX_PHASE)
+}
+
+/// Returns the distance in king moves from the square to the closest central square.
+#[inline]
+fn hill_distance(sq: Square) -> usize {
+    let (x, y) = (sq.x(), sq.y());
+    let dx = (3 - x).max(x - 4).max(0);
+    let dy = (3 - y).max(y - 4).max(0);
+    dx.max(dy) as usize
+}
+
+//#################################################################################################
+//
+//                                        struct Tapered
+//
+//#################################################################################################
+
+/// Sums the contributions of the terms from white's point of view, forwarding them
+/// to the inner trace.
+struct Tapered<'a, T: Trace> {
+    mg: f32,
+    eg: f32,
+    trace: &'a mut T,
+}
+
+// ================================ traits impl
+
+impl<T: Trace> Trace for Tapered<'_, T> {
+    #[inline]
+    fn add(&mut self, term: Term, color: Color, mg: f32, eg: f32) {
+        let sign = if color == Color::White {1.0} else {-1.0};
+        self.mg += sign * mg;
+        self.eg += sign * eg;
+        self.trace.add(term, color, mg, eg);
+    }
 
-    score
-}
\ No newline at end of file
+    #[inline]
+    fn scale(&mut self, scale: f32) {
+        self.trace.scale(scale);
+    }
+}
@@ -0,0 +1,56 @@
+use chess::color::Color;
+use chess::moves::Move;
+
+//#################################################################################################
+//
+//                                      struct HistoryTable
+//
+//#################################################################################################
+
+/// The maximum absolute value of a history score.
+const MAX_HISTORY: i32 = 16384;
+
+/// The history of the quiet moves of a search thread, by color, from and to squares:
+/// positive for moves that caused cutoffs, negative for moves that failed to.
+#[derive(Debug)]
+pub(crate) struct HistoryTable(Vec<i32>);
+
+// ================================ pub(crate) impl
+
+impl HistoryTable {
+    /// Creates a new empty history table.
+    pub(crate) fn new() -> HistoryTable {
+        HistoryTable(vec![0; 2 * 64 * 64])
+    }
+
+    /// Returns the history score of the quiet move, played by that color.
+    #[inline]
+    pub(crate) fn get(&self, color: Color, mv: Move) -> i32 {
+        self.0[HistoryTable::index(color, mv)]
+    }
+
+    /// Adds the bonus, or the malus if negative, to the score of the move played by that
+    /// color. The closer the score is to its bounds, the less it moves.
+    #[inline]
+    pub(crate) fn update(&mut self, color: Color, mv: Move, bonus: i32) {
+        let score = &mut self.0[HistoryTable::index(color, mv)];
+        *score += bonus - *score * bonus.abs() / MAX_HISTORY;
+    }
+
+    /// Halves the scores, for the history of previous searches to fade away.
+    pub(crate) fn age(&mut self) {
+        for score in &mut self.0 {
+            *score /= 2;
+        }
+    }
+}
+
+// ================================ impl
+
+impl HistoryTable {
+    /// Returns the index of the move of that color in the table.
+    #[inline]
+    fn index(color: Color, mv: Move) -> usize {
+        (usize::from(color) * 64 + usize::from(mv.from())) * 64 + usize::from(mv.to())
+    }
+}
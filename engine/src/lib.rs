@@ -1,15 +1,27 @@
 extern crate chess;
 
 mod params;
+mod activity;
+mod endgame;
 mod engine;
 mod eval;
+mod history;
 mod movepick;
+mod nnue;
+mod pawns;
 mod search;
 mod table;
+mod trace;
 mod utils;
+mod weights;
 
 /// The version of the engine.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // Export the Engine struct.
-pub use self::engine::Engine;
\ No newline at end of file
+pub use self::engine::{Bound, Engine, Line, RootMove};
+pub use self::eval::{evaluate, evaluate_with};
+pub use self::nnue::{Accumulator, Network};
+pub use self::params::{EvalTerms, Extensions, Pruning};
+pub use self::trace::{EvalTrace, Term, TermTrace};
+pub use self::weights::EvalParams;
\ No newline at end of file
@@ -52,7 +52,9 @@ impl MovePicker {
         
         let checkers = board.get_checkers();
 
-        if checkers.empty() {
+        // Check evasions only apply to variants with orthodox checks,
+        // other variants generate everything and rely on the legality test.
+        if checkers.empty() || !board.get_variant().has_standard_checks() {
             MovePicker {
                 state: GenState::QueenPromotes, 
                 zero_index, 
@@ -82,6 +84,7 @@ impl MovePicker {
     pub(crate) fn next(&mut self, board: &Board, buffer: &mut Vec<Move>) -> Option<Range<usize>> {
         // Remove the last batch from the list.
         buffer.truncate(self.zero_index);
+        let under_promotes = &board.get_variant().promotes()[1..];
 
         loop {
             // Compute that stage and go to the next one.
@@ -123,12 +126,12 @@ impl MovePicker {
                 GenState::UnderPromotes => {
                     // --- All under promotions ---
                     movegen::gen_promote_captures(board, |from, to, capture| {
-                        for promote in MovePicker::UNDER_PROMOTES {
+                        for &promote in under_promotes {
                             buffer.push(Move::promote_capture(from, to, capture, promote));
                         }
                     });
                     movegen::gen_promotes(board, |from, to| {
-                        for promote in MovePicker::UNDER_PROMOTES {
+                        for &promote in under_promotes {
                             buffer.push(Move::promote(from, to, promote));
                         }
                     });
@@ -149,6 +152,10 @@ impl MovePicker {
                     movegen::gen_king_quiets(board, |from, to| {
                         buffer.push(Move::quiet(from, to));
                     });
+                    // All drops, in crazyhouse.
+                    movegen::gen_drops(board, |piece, to| {
+                        buffer.push(Move::drop(piece, to));
+                    });
                     GenState::End
                 },
     
@@ -192,14 +199,14 @@ impl MovePicker {
                 GenState::CheckOthers => {
                     // Under promotions.
                     movegen::gen_promote_captures(board, |from, to, capture| {
-                        for promote in MovePicker::UNDER_PROMOTES {
+                        for &promote in under_promotes {
                             if self.check_mask.contains(to) {
                                 buffer.push(Move::promote_capture(from, to, capture, promote))
                             };
                         }
                     });
                     movegen::gen_promotes(board, |from, to| {
-                        for promote in MovePicker::UNDER_PROMOTES {
+                        for &promote in under_promotes {
                             if self.check_mask.contains(to) {
                                 buffer.push(Move::promote(from, to, promote));
                             };
@@ -223,6 +230,12 @@ impl MovePicker {
                     movegen::gen_king_quiets(board, |from, to| {
                         buffer.push(Move::quiet(from, to));
                     });
+                    // Blocking drops, in crazyhouse.
+                    movegen::gen_drops(board, |piece, to| {
+                        if self.check_mask.contains(to) {
+                            buffer.push(Move::drop(piece, to));
+                        }
+                    });
                     GenState::End
                 },
     
@@ -250,10 +263,3 @@ impl MovePicker {
         }        
     }
 }
-
-// ================================ impl
-
-impl MovePicker {
-    /// The list of under promotions, from best to worst.
-    const UNDER_PROMOTES: [Piece; 3] = [Piece::Rook, Piece::Bishop, Piece::Knight];
-}
\ No newline at end of file
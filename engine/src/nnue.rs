@@ -0,0 +1,309 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::sync::Arc;
+
+use chess::board::{Board, MoveHook};
+use chess::color::Color;
+use chess::piece::Piece;
+use chess::square::Square;
+
+/// The number of inputs of the network, one per colored piece and square.
+const INPUTS: usize = 768;
+
+/// The quantization of the hidden layer, whose activations are clipped to [0, QA],
+/// and of the output weights.
+const QA: i32 = 255;
+const QB: i32 = 64;
+
+/// The scale of the output of the network, in pawns.
+const SCALE: f32 = 4.0;
+
+/// The magic bytes starting a network file.
+const MAGIC: &[u8; 4] = b"RNN1";
+
+//#################################################################################################
+//
+//                                        struct Network
+//
+//#################################################################################################
+
+/// A quantized 768->N->1 neural network, evaluating positions of standard chess from
+/// white's point of view. The hidden layer is computed incrementally by accumulators.
+#[derive(Clone, Debug)]
+pub struct Network {
+    kernels: simd::Kernels,
+    hidden: usize,
+    feature_weights: Vec<i16>,
+    feature_bias: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i32,
+}
+
+// ================================ pub impl
+
+impl Network {
+    /// Loads a network from a file, see Network::from_bytes() for the format.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Network> {
+        Network::from_bytes(&fs::read(path)?)
+    }
+
+    /// Reads a network from its bytes: the magic "RNN1", the size N of the hidden layer as
+    /// an u32, then the 768*N feature weights, grouped by input, the N feature biases and
+    /// the N output weights as i16, and the output bias as an i32, all little-endian.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Network> {
+        let invalid = |msg| io::Error::new(ErrorKind::InvalidData, msg);
+
+        if bytes.len() < 8 || &bytes[..4] != MAGIC {
+            return Err(invalid("Not a network file."));
+        }
+
+        let hidden = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+        if hidden == 0 || bytes.len() != 8 + 2 * (INPUTS * hidden + 2 * hidden) + 4 {
+            return Err(invalid("Invalid size of network file."));
+        }
+
+        let mut i16s = bytes[8..bytes.len() - 4].chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]));
+        let mut take = |n| i16s.by_ref().take(n).collect::<Vec<_>>();
+        let feature_weights = take(INPUTS * hidden);
+        let feature_bias = take(hidden);
+        let output_weights = take(hidden);
+
+        let last = &bytes[bytes.len() - 4..];
+        let output_bias = i32::from_le_bytes([last[0], last[1], last[2], last[3]]);
+
+        Ok(Network {
+            kernels: simd::Kernels::detect(true),
+            hidden,
+            feature_weights,
+            feature_bias,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    /// Returns the size of the hidden layer.
+    #[inline]
+    pub fn get_hidden(&self) -> usize {
+        self.hidden
+    }
+
+    /// Uses the vectorized operations if simd is true and the cpu supports them, the
+    /// scalar ones otherwise. Networks use the vectorized ones when they are loaded.
+    pub fn set_simd(&mut self, simd: bool) {
+        self.kernels = simd::Kernels::detect(simd);
+    }
+
+    /// Returns true if the network uses the vectorized operations.
+    #[inline]
+    pub fn has_simd(&self) -> bool {
+        self.kernels.simd
+    }
+}
+
+// ================================ impl
+
+impl Network {
+    /// Returns the weights of the input of that piece on that square.
+    #[inline]
+    fn weights(&self, color: Color, piece: Piece, sq: Square) -> &[i16] {
+        let input = (usize::from(color) * 6 + usize::from(piece)) * 64 + usize::from(sq);
+        &self.feature_weights[input * self.hidden..(input + 1) * self.hidden]
+    }
+}
+
+//#################################################################################################
+//
+//                                      struct Accumulator
+//
+//#################################################################################################
+
+/// The hidden layer of a network for a board, kept up to date as a move hook
+/// of Board::do_move_with() and Board::undo_move_with().
+#[derive(Clone, Debug)]
+pub struct Accumulator {
+    network: Arc<Network>,
+    values: Vec<i16>,
+}
+
+// ================================ pub impl
+
+impl Accumulator {
+    /// Creates the accumulator of the network for that board.
+    pub fn new(network: Arc<Network>, board: &Board) -> Accumulator {
+        let mut acc = Accumulator {
+            values: Vec::with_capacity(network.hidden),
+            network,
+        };
+
+        acc.refresh(board);
+        acc
+    }
+
+    /// Recomputes the hidden layer from scratch, from the pieces on the board.
+    pub fn refresh(&mut self, board: &Board) {
+        self.values.clear();
+        self.values.extend_from_slice(&self.network.feature_bias);
+
+        for sq in board.get_occupancy().all().iter_squares() {
+            let (color, piece) = board.get_piece(sq).unwrap();
+            (self.network.kernels.add)(&mut self.values, self.network.weights(color, piece, sq));
+        }
+    }
+
+    /// Evaluates the board from the point of view of the side to move, in pawns.
+    pub fn evaluate(&self, board: &Board) -> f32 {
+        let output = (self.network.kernels.dot_clipped)(&self.values, &self.network.output_weights) + self.network.output_bias;
+        let score = output as f32 * SCALE / (QA * QB) as f32;
+
+        if board.get_side_to_move() == Color::White {score} else {-score}
+    }
+
+    /// Returns the values of the hidden layer, before the activation.
+    #[inline]
+    pub fn get_values(&self) -> &[i16] {
+        &self.values
+    }
+}
+
+// ================================ traits impl
+
+impl MoveHook for Accumulator {
+    /// Adds the weights of the piece to the hidden layer.
+    #[inline]
+    fn place(&mut self, color: Color, piece: Piece, sq: Square) {
+        (self.network.kernels.add)(&mut self.values, self.network.weights(color, piece, sq));
+    }
+
+    /// Subtracts the weights of the piece from the hidden layer.
+    #[inline]
+    fn remove(&mut self, color: Color, piece: Piece, sq: Square) {
+        (self.network.kernels.sub)(&mut self.values, self.network.weights(color, piece, sq));
+    }
+}
+
+//#################################################################################################
+//
+//                                          mod simd
+//
+//#################################################################################################
+
+/// The operations of the network, with avx2 when the cpu supports it and a scalar
+/// fallback. Both give the exact same results, additions wrap around.
+mod simd {
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    use super::QA;
+
+    /// The operations on the hidden layer, chosen once for the cpu.
+    #[derive(Clone, Copy, Debug)]
+    pub(super) struct Kernels {
+        pub(super) simd: bool,
+        pub(super) add: fn(&mut [i16], &[i16]),
+        pub(super) sub: fn(&mut [i16], &[i16]),
+        pub(super) dot_clipped: fn(&[i16], &[i16]) -> i32,
+    }
+
+    impl Kernels {
+        /// The scalar operations, available everywhere.
+        const SCALAR: Kernels = Kernels {
+            simd: false,
+            add: add_scalar,
+            sub: sub_scalar,
+            dot_clipped: dot_clipped_scalar,
+        };
+
+        /// The avx2 operations.
+        // SAFE: only selected by Kernels::detect() when the cpu supports avx2.
+        #[cfg(target_arch = "x86_64")]
+        const AVX2: Kernels = Kernels {
+            simd: true,
+            add: |acc, weights| unsafe {add_avx2(acc, weights)},
+            sub: |acc, weights| unsafe {sub_avx2(acc, weights)},
+            dot_clipped: |acc, weights| unsafe {dot_clipped_avx2(acc, weights)},
+        };
+
+        /// Returns the vectorized operations if simd is true and the cpu supports
+        /// them, the scalar ones otherwise.
+        pub(super) fn detect(simd: bool) -> Kernels {
+            #[cfg(target_arch = "x86_64")]
+            if simd && is_x86_feature_detected!("avx2") {
+                return Kernels::AVX2;
+            }
+
+            let _ = simd;
+            Kernels::SCALAR
+        }
+    }
+
+    /// Adds the weights to the accumulator.
+    fn add_scalar(acc: &mut [i16], weights: &[i16]) {
+        for (a, &w) in acc.iter_mut().zip(weights) {
+            *a = a.wrapping_add(w);
+        }
+    }
+
+    /// Subtracts the weights from the accumulator.
+    fn sub_scalar(acc: &mut [i16], weights: &[i16]) {
+        for (a, &w) in acc.iter_mut().zip(weights) {
+            *a = a.wrapping_sub(w);
+        }
+    }
+
+    /// Returns the dot product of the accumulator clipped to [0, QA] and the weights,
+    /// also used for the lanes left by the vectorized one.
+    #[inline]
+    fn dot_clipped_scalar(acc: &[i16], weights: &[i16]) -> i32 {
+        acc.iter().zip(weights).map(|(&a, &w)| i32::from(a).clamp(0, QA) * i32::from(w)).sum()
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add_avx2(acc: &mut [i16], weights: &[i16]) {
+        let n = acc.len().min(weights.len()) / 16 * 16;
+        for i in (0..n).step_by(16) {
+            let a = _mm256_loadu_si256(acc.as_ptr().add(i) as *const __m256i);
+            let w = _mm256_loadu_si256(weights.as_ptr().add(i) as *const __m256i);
+            _mm256_storeu_si256(acc.as_mut_ptr().add(i) as *mut __m256i, _mm256_add_epi16(a, w));
+        }
+
+        for (a, &w) in acc[n..].iter_mut().zip(&weights[n..]) {
+            *a = a.wrapping_add(w);
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub_avx2(acc: &mut [i16], weights: &[i16]) {
+        let n = acc.len().min(weights.len()) / 16 * 16;
+        for i in (0..n).step_by(16) {
+            let a = _mm256_loadu_si256(acc.as_ptr().add(i) as *const __m256i);
+            let w = _mm256_loadu_si256(weights.as_ptr().add(i) as *const __m256i);
+            _mm256_storeu_si256(acc.as_mut_ptr().add(i) as *mut __m256i, _mm256_sub_epi16(a, w));
+        }
+
+        for (a, &w) in acc[n..].iter_mut().zip(&weights[n..]) {
+            *a = a.wrapping_sub(w);
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn dot_clipped_avx2(acc: &[i16], weights: &[i16]) -> i32 {
+        let n = acc.len().min(weights.len()) / 16 * 16;
+        let (zero, qa) = (_mm256_setzero_si256(), _mm256_set1_epi16(QA as i16));
+        let mut sum = _mm256_setzero_si256();
+
+        for i in (0..n).step_by(16) {
+            let a = _mm256_loadu_si256(acc.as_ptr().add(i) as *const __m256i);
+            let w = _mm256_loadu_si256(weights.as_ptr().add(i) as *const __m256i);
+            let clipped = _mm256_min_epi16(_mm256_max_epi16(a, zero), qa);
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(clipped, w));
+        }
+
+        let mut lanes = [0i32; 8];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sum);
+        lanes.iter().sum::<i32>() + dot_clipped_scalar(&acc[n..], &weights[n..])
+    }
+}
@@ -3,15 +3,60 @@ use chess::piece::Piece;
 /// The size of the transposition table in bytes. Not exact.
 pub(crate) const TABLE_SIZE: usize = 16777216;
 
+/// The size of the pawn table of each search thread in bytes. Not exact.
+pub(crate) const PAWN_TABLE_SIZE: usize = 1048576;
+
 /// The number of search threads used.
 pub(crate) const NUM_SEARCH_THREAD: usize = 3;
 
+/// The default number of lines searched, the best one and the alternatives.
+pub(crate) const MULTI_PV: usize = 1;
+
 /// The aspiration window used by the engine.
 pub(crate) const ASPIRATION_WINDOW: &[f32] = &[10.0, 50.0, 250.0, f32::INFINITY];
 
 /// The maximum search depth.
 pub(crate) const MAX_DEPTH: u8 = 32;
 
+/// The default contempt, the value in pawns the engine gives up by drawing.
+pub(crate) const CONTEMPT: f32 = 0.0;
+
+/// The amplitude of the deterministic jitter of draw scores, in pawns.
+pub(crate) const DRAW_JITTER: f32 = 0.01;
+
+/// The score beyond which a mate was found, where no forward pruning is done.
+pub(crate) const MATE_BOUND: f32 = 100.0;
+
+/// The maximum depth of reverse futility pruning, and its margin per ply, in pawns.
+pub(crate) const REVERSE_FUTILITY_DEPTH: u8 = 6;
+pub(crate) const REVERSE_FUTILITY_MARGIN: f32 = 0.9;
+
+/// The margins of futility pruning by depth at frontier nodes, in pawns.
+pub(crate) const FUTILITY_MARGINS: [f32; 4] = [0.0, 1.2, 2.2, 3.2];
+
+/// The margins of razoring by depth, in pawns.
+pub(crate) const RAZORING_MARGINS: [f32; 3] = [0.0, 2.5, 3.5];
+
+/// The number of moves searched by depth after which the quiet moves are pruned.
+pub(crate) const LATE_MOVE_COUNTS: [usize; 5] = [0, 6, 9, 14, 21];
+
+/// The maximum depth of history pruning, and the history below which, times the
+/// square of the depth, quiet moves are pruned.
+pub(crate) const HISTORY_PRUNING_DEPTH: u8 = 3;
+pub(crate) const HISTORY_PRUNING_MARGIN: i32 = -512;
+
+/// The minimum depth of singular extensions, the depth by which the table entry may be
+/// shallower, and the margin per ply below its score the other moves must stay, in pawns.
+pub(crate) const SINGULAR_DEPTH: u8 = 6;
+pub(crate) const SINGULAR_TABLE_DEPTH: u8 = 3;
+pub(crate) const SINGULAR_MARGIN: f32 = 0.05;
+
+/// The width of the null window of the singular search, in pawns.
+pub(crate) const SINGULAR_WINDOW: f32 = 0.01;
+
+/// The relative rank from which the pushes of passed pawns are extended, from 0.
+pub(crate) const PASSED_PAWN_RANK: usize = 6;
+
 /// Used during quiescient search for move generation.
 pub(crate) const DELTA: f32 = 2.0;
 
@@ -26,4 +71,130 @@ pub const fn value_of(piece: Piece) -> f32 {
         Piece::Queen => 9.0,
         Piece::King => 200.0,
     }
-}
\ No newline at end of file
+}
+
+/// The optional terms of the evaluation, each of which may be switched off for A/B testing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalTerms {
+    /// Rewards the safe squares attacked by the knights, bishops, rooks and queens.
+    pub mobility: bool,
+    /// Punishes the pieces attacking the squares around the king.
+    pub king_attacks: bool,
+    /// Rewards the pawns sheltering the king, and punishes the enemy pawns storming it.
+    pub pawn_shelter: bool,
+    /// Rewards having both bishops.
+    pub bishop_pair: bool,
+    /// Rewards the rooks on open and half-open files, and on the seventh rank.
+    pub rook_files: bool,
+    /// Rewards the knights on squares defended by a pawn that no enemy pawn can attack.
+    pub outposts: bool,
+}
+
+impl EvalTerms {
+    /// None of the optional terms.
+    pub const NONE: EvalTerms = EvalTerms {
+        mobility: false,
+        king_attacks: false,
+        pawn_shelter: false,
+        bishop_pair: false,
+        rook_files: false,
+        outposts: false,
+    };
+}
+
+impl Default for EvalTerms {
+    /// Returns the terms used by default.
+    fn default() -> EvalTerms {
+        EVAL_TERMS
+    }
+}
+
+/// The terms used by default by the evaluation.
+pub(crate) const EVAL_TERMS: EvalTerms = EvalTerms {
+    mobility: true,
+    king_attacks: true,
+    pawn_shelter: true,
+    bishop_pair: true,
+    rook_files: true,
+    outposts: true,
+};
+
+/// The forward pruning heuristics of the search, each of which may be switched off for A/B testing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pruning {
+    /// Cuts nodes whose evaluation is well above beta.
+    pub reverse_futility: bool,
+    /// Skips quiet moves at frontier nodes whose evaluation is well below alpha.
+    pub futility: bool,
+    /// Only searches captures at nodes whose evaluation is far below alpha.
+    pub razoring: bool,
+    /// Skips quiet moves coming late in the move ordering at low depths.
+    pub late_moves: bool,
+    /// Skips quiet moves with a bad history at low depths.
+    pub history: bool,
+}
+
+impl Pruning {
+    /// No forward pruning at all.
+    pub const NONE: Pruning = Pruning {
+        reverse_futility: false,
+        futility: false,
+        razoring: false,
+        late_moves: false,
+        history: false,
+    };
+}
+
+impl Default for Pruning {
+    /// Returns the pruning heuristics used by default.
+    fn default() -> Pruning {
+        PRUNING
+    }
+}
+
+/// The pruning heuristics used by default by the search.
+pub(crate) const PRUNING: Pruning = Pruning {
+    reverse_futility: true,
+    futility: true,
+    razoring: true,
+    late_moves: true,
+    history: true,
+};
+
+/// The extensions of the search, each of which may be switched off for A/B testing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extensions {
+    /// Extends moves giving check.
+    pub check: bool,
+    /// Extends the move of the table when the other moves fail low by a margin.
+    pub singular: bool,
+    /// Extends recaptures of a piece of about the same value.
+    pub recapture: bool,
+    /// Extends pushes of passed pawns close to promotion.
+    pub passed_pawn: bool,
+}
+
+impl Extensions {
+    /// No extension at all.
+    pub const NONE: Extensions = Extensions {
+        check: false,
+        singular: false,
+        recapture: false,
+        passed_pawn: false,
+    };
+}
+
+impl Default for Extensions {
+    /// Returns the extensions used by default.
+    fn default() -> Extensions {
+        EXTENSIONS
+    }
+}
+
+/// The extensions used by default by the search.
+pub(crate) const EXTENSIONS: Extensions = Extensions {
+    check: true,
+    singular: true,
+    recapture: true,
+    passed_pawn: true,
+};
\ No newline at end of file
@@ -0,0 +1,160 @@
+use chess::attacks;
+use chess::bitboard::BitBoard;
+use chess::board::Board;
+use chess::color::Color;
+use chess::piece::Piece;
+use chess::zobrist::Zobrist;
+
+use crate::params;
+use crate::utils::{adjacent_files, distance, file_mask, front_ranks, relative_rank};
+use crate::weights::EvalParams;
+
+//#################################################################################################
+//
+//                                       struct PawnEntry
+//
+//#################################################################################################
+
+/// The pawn structure evaluation of a position, which only depends on the pawns.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct PawnEntry {
+    zobrist: Zobrist,
+    score: [[f32; 2]; 2],
+    passed: [BitBoard; 2],
+}
+
+// ================================ pub(crate) impl
+
+impl PawnEntry {
+    /// Evaluates the pawn structure of the board, for both colors.
+    pub(crate) fn new(board: &Board, weights: &EvalParams) -> PawnEntry {
+        let mut entry = PawnEntry {
+            zobrist: board.get_pawn_zobrist(),
+            score: [[0.0; 2]; 2],
+            passed: [BitBoard::EMPTY; 2],
+        };
+
+        for color in Color::COLORS {
+            let ours = board.get_bitboard(color, Piece::Pawn);
+            let theirs = board.get_bitboard(color.invert(), Piece::Pawn);
+
+            for sq in ours.iter_squares() {
+                let rank = relative_rank(color, sq);
+                let file = file_mask(sq);
+                let adjacent = adjacent_files(sq);
+                let front = front_ranks(color, sq);
+                let (mut mg, mut eg) = (0.0, 0.0);
+
+                let passed = (theirs & (file | adjacent) & front).empty();
+                let isolated = (ours & adjacent).empty();
+                let supporters = ours & adjacent & !front;
+                let connected = (ours & adjacent & sq.rank()).not_empty() || (ours & attacks::pawn(color.invert(), sq)).not_empty();
+
+                if passed {
+                    entry.passed[usize::from(color)] |= BitBoard::from(sq);
+                    mg += weights.passed[rank][0];
+                    eg += weights.passed[rank][1];
+                } else if (theirs & file & front).empty() && supporters.count() >= (theirs & adjacent & front).count() {
+                    // Nothing blocks the file, and the pawns guarding it can be traded off.
+                    mg += weights.candidate[rank][0];
+                    eg += weights.candidate[rank][1];
+                }
+
+                if isolated {
+                    mg += weights.isolated[0];
+                    eg += weights.isolated[1];
+                } else if !passed && supporters.empty() {
+                    // No pawn can defend it, and its stop square is controlled by the enemy pawns.
+                    let stop = attacks::pawn_push(color, sq);
+                    if stop.is_some_and(|stop| (theirs & attacks::pawn(color, stop)).not_empty()) {
+                        mg += weights.backward[0];
+                        eg += weights.backward[1];
+                    }
+                }
+
+                if (ours & file & front).not_empty() {
+                    mg += weights.doubled[0];
+                    eg += weights.doubled[1];
+                }
+
+                if connected {
+                    mg += weights.connected[rank][0];
+                    eg += weights.connected[rank][1];
+                }
+
+                entry.score[usize::from(color)][0] += mg;
+                entry.score[usize::from(color)][1] += eg;
+            }
+        }
+
+        entry
+    }
+
+    /// Returns the midgame and endgame evaluation of the pawn structure of that color.
+    #[inline]
+    pub(crate) fn get_score(&self, color: Color) -> [f32; 2] {
+        self.score[usize::from(color)]
+    }
+
+    /// Returns the endgame bonus for the distance of the kings to the passed pawns of that
+    /// color. It is not cached, as it depends on the kings.
+    pub(crate) fn king_distance(&self, board: &Board, color: Color, weights: &EvalParams) -> f32 {
+        let king = |color| board.get_bitboard(color, Piece::King).iter_squares().next();
+        let (ours, theirs) = match (king(color), king(color.invert())) {
+            (Some(ours), Some(theirs)) => (ours, theirs),
+            _ => return 0.0,
+        };
+
+        let mut score = 0.0;
+        for sq in self.passed[usize::from(color)].iter_squares() {
+            if let Some(stop) = attacks::pawn_push(color, sq) {
+                let rank = relative_rank(color, sq) as f32;
+                score += rank * (
+                    weights.king_distance[0] * distance(theirs, stop) as f32 +
+                    weights.king_distance[1] * distance(ours, stop) as f32
+                );
+            }
+        }
+
+        score
+    }
+}
+
+//#################################################################################################
+//
+//                                       struct PawnTable
+//
+//#################################################################################################
+
+/// The size in entries of the table. It is a power of two for faster indexing.
+const NUM_ENTRIES: usize = (params::PAWN_TABLE_SIZE / std::mem::size_of::<Option<PawnEntry>>()).next_power_of_two();
+
+/// A cache of pawn structure evaluations, indexed by the pawn zobrist key of the boards.
+/// Each search thread owns one, as pawn structures change rarely along a search.
+#[derive(Clone, Debug)]
+pub(crate) struct PawnTable(Vec<Option<PawnEntry>>);
+
+// ================================ pub(crate) impl
+
+impl PawnTable {
+    /// Creates an empty pawn table.
+    pub(crate) fn new() -> PawnTable {
+        PawnTable(vec![None; NUM_ENTRIES])
+    }
+
+    /// Returns the pawn structure evaluation of the board, computing it if it is not cached.
+    #[inline]
+    pub(crate) fn probe(&mut self, board: &Board, weights: &EvalParams) -> PawnEntry {
+        let zobrist = board.get_pawn_zobrist();
+        let i = zobrist.idx::<NUM_ENTRIES>() as usize;
+
+        match self.0[i] {
+            Some(entry) if entry.zobrist == zobrist => entry,
+            _ => {
+                let entry = PawnEntry::new(board, weights);
+                self.0[i] = Some(entry);
+                entry
+            },
+        }
+    }
+}
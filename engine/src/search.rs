@@ -1,13 +1,18 @@
 use std::sync::Arc;
 
 use chess::board::Board;
+use chess::color::Color;
 use chess::moves::Move;
 use chess::piece::Piece;
+use chess::variant::Variant;
 
-use crate::engine::GlobalInfo;
+use crate::engine::{GlobalInfo, Line, RootMove};
 use crate::{eval, utils};
+use crate::history::HistoryTable;
 use crate::movepick::MovePicker;
-use crate::params;
+use crate::nnue::Accumulator;
+use crate::params::{self, EvalTerms, Extensions, Pruning};
+use crate::pawns::PawnTable;
 use crate::table::{TableEntry, TableEntryFlag};
 
 /// A struct holding all the necessary information for a search thread.
@@ -15,10 +20,25 @@ use crate::table::{TableEntry, TableEntryFlag};
 pub(crate) struct Search {
     info: Arc<GlobalInfo>,
     best_move: Option<Move>,
+    root_moves: Vec<RootMove>,
+    excluded: Vec<Move>,
+    skipped: Option<Move>,
+    path: Vec<Move>,
+    extensions: u8,
     depth: u8,
+    seldepth: u8,
+    nodes: u64,
     board: Board,
     buffer: Vec<Move>,
-    seed: u32,
+    quiets: Vec<Move>,
+    history: HistoryTable,
+    pawns: PawnTable,
+    nnue: Option<Accumulator>,
+    us: Color,
+    eval_terms: EvalTerms,
+    contempt: f32,
+    pruning: Pruning,
+    extension_rules: Extensions,
 }
 
 // ================================ pub(crate) impl
@@ -29,10 +49,25 @@ impl Search {
         Search {
             info,
             best_move: None,
+            root_moves: Vec::new(),
+            excluded: Vec::new(),
+            skipped: None,
+            path: Vec::new(),
+            extensions: 0,
             depth: 0,
+            seldepth: 0,
+            nodes: 0,
             board: Board::default(),
             buffer: Vec::new(),
-            seed: 0,
+            quiets: Vec::new(),
+            history: HistoryTable::new(),
+            pawns: PawnTable::new(),
+            nnue: None,
+            us: Color::White,
+            eval_terms: params::EVAL_TERMS,
+            contempt: params::CONTEMPT,
+            pruning: params::PRUNING,
+            extension_rules: params::EXTENSIONS,
         }
     }
 
@@ -55,6 +90,18 @@ impl Search {
         }
     }
 
+    /// Searches the position to that depth, iteratively deepening from depth 1.
+    pub(crate) fn search_fixed_depth(&mut self, depth: u8) {
+        let mut scores = self.prepare_search();
+
+        for search_depth in 1..=depth {
+            if !self.iterate(search_depth, &mut scores) {
+                break;
+            }
+        }
+
+        self.info.add_nodes(self.nodes);
+    }
 }
 
 // ================================ impl
@@ -62,40 +109,80 @@ impl Search {
 impl Search {
     /// Search the position until told to stop.
     fn search_position(&mut self) {
-        // Clone global board and get search depth.
-        const MAX_IDX: usize = params::ASPIRATION_WINDOW.len() - 1;
+        let mut scores = self.prepare_search();
+        let mut counted = 0;
         
-        { // Update the board.
-            let ply = self.board.get_ply();
-            self.board = self.info.board();
-            if self.board.get_ply() != ply {
-                // New board, reset some fields.
-                self.best_move = None;
+        loop {
+            // Counts the nodes of the previous iteration.
+            self.info.add_nodes(self.nodes - counted);
+            counted = self.nodes;
+
+            // Get the depth this thread needs to search to, bounded as mates are found quickly.
+            let search_depth = self.info.thread_search_depth().min(params::MAX_DEPTH);
+            if !self.iterate(search_depth, &mut scores) {
+                break;
             }
         }
-        
+
+        self.info.add_nodes(self.nodes - counted);
+    }
+
+    /// Sets the search up with the global board and options, and returns the
+    /// first reference score of each line.
+    fn prepare_search(&mut self) -> Vec<f32> {
+        self.board = self.info.board();
+
+        // Draws are scored from the point of view of the side the engine plays.
+        self.us = self.board.get_side_to_move();
+        self.eval_terms = self.info.eval_terms();
+        self.contempt = self.info.contempt();
+        self.pruning = self.info.pruning();
+        self.extension_rules = self.info.extensions();
+
+        // The network only knows standard chess.
+        self.nnue = self.info.network()
+            .filter(|_| self.board.get_variant() == Variant::Standard)
+            .map(|network| Accumulator::new(network, &self.board));
+
+        // The root moves are scored again from scratch, the history fades away.
+        self.root_moves = self.legal_root_moves();
+        self.history.age();
+        self.nodes = 0;
+
         // Compute first reference score.
         let best_score = self.quiescence(f32::NEG_INFINITY, f32::INFINITY);
+        vec![best_score; self.info.multi_pv()]
+    }
+
+    /// Searches the lines at that depth, starting from the scores of the previous
+    /// iteration, and reports them. Returns false if the search was stopped.
+    fn iterate(&mut self, search_depth: u8, scores: &mut [f32]) -> bool {
+        const MAX_IDX: usize = params::ASPIRATION_WINDOW.len() - 1;
+
+        let mut lines = Vec::with_capacity(scores.len());
+        self.excluded.clear();
         
-        'main: loop {
-            // Get the depth this thread needs to search to.
-            let search_depth = self.info.thread_search_depth();
+        // Each line is searched without the root moves of the previous ones.
+        while lines.len() < scores.len().min(self.root_moves.len()) {
+            let best_score = scores[lines.len()];
             
             // Get the first values of alpha and beta in the aspiration window.
             let mut alpha = best_score - params::ASPIRATION_WINDOW[0];
             let mut beta = best_score + params::ASPIRATION_WINDOW[0];
             
             let (mut alpha_idx, mut beta_idx) = (0, 0);
+            self.best_move = None;
+            self.seldepth = 0;
             
-            loop {
-                let best_score = self.alpha_beta(alpha, beta, true, search_depth, search_depth);
+            let score = loop {
+                let best_score = self.search_root(alpha, beta, search_depth);
                 
                 if !self.info.is_searching() {
-                    break 'main;
+                    return false;
                 }
                 
                 if self.info.search_depth() >= search_depth {
-                    break;
+                    return true;
                 }
                 
                 if best_score <= alpha {
@@ -105,70 +192,361 @@ impl Search {
                     beta_idx = MAX_IDX.min(beta_idx + 1);
                     beta = best_score + params::ASPIRATION_WINDOW[beta_idx];
                 } else {
-                    break;
+                    break best_score;
                 }
-            }
+            };
             
-            if let Some(mv) = self.best_move {
-                self.info.report_move(mv, search_depth);
+            // No root move raised alpha.
+            let mv = match self.best_move {
+                Some(mv) => mv,
+                None => break,
+            };
+            
+            scores[lines.len()] = score;
+            lines.push(Line::new(score, search_depth, self.seldepth, self.principal_variation(mv, search_depth)));
+            self.excluded.push(mv);
+        }
+        
+        if !lines.is_empty() {
+            // The next iteration searches the best moves first.
+            self.root_moves.sort_by(|a, b| b.get_score().partial_cmp(&a.get_score()).unwrap());
+            self.info.report_lines(lines, &self.root_moves, search_depth);
+        }
+
+        true
+    }
+    
+    /// Returns the legal moves of the root, restricted to the search moves if any of them
+    /// is legal, in the order of the move picker.
+    fn legal_root_moves(&mut self) -> Vec<RootMove> {
+        let mut root_moves = Vec::new();
+        let mut picker = MovePicker::new(&self.board, &self.buffer);
+
+        while let Some(range) = picker.next(&self.board, &mut self.buffer) {
+            for i in range {
+                if self.board.is_legal(self.buffer[i]) {
+                    root_moves.push(RootMove::new(self.buffer[i]));
+                }
             }
         }
+        self.buffer.clear();
+
+        let search_moves = self.info.search_moves();
+        if root_moves.iter().any(|root| search_moves.contains(&root.get_move())) {
+            root_moves.retain(|root| search_moves.contains(&root.get_move()));
+        }
+
+        root_moves
+    }
+
+    /// Returns the principal variation starting with that root move, following the
+    /// best moves stored in the table, at most as long as the search depth.
+    fn principal_variation(&self, mv: Move, depth: u8) -> Vec<Move> {
+        let mut board = self.board.clone();
+        let mut pv = vec![mv];
+        board.do_move(mv);
+        
+        while pv.len() < depth as usize {
+            match self.info.get_table().get(board.get_zobrist()).map(|entry| entry.mv) {
+                Some(mv) if board.is_pseudo_legal(mv) && board.is_legal(mv) => {
+                    board.do_move(mv);
+                    pv.push(mv);
+                },
+                _ => break,
+            }
+        }
+        
+        pv
+    }
+    
+    /// Stores the result of the node in the table, but at the root when some moves are
+    /// excluded, as the result is not the one of the position.
+    #[inline]
+    fn insert(&self, mv: Move, score: f32, depth: u8, flag: TableEntryFlag) {
+        if self.depth > 0 || self.excluded.is_empty() {
+            self.info.get_table().insert(TableEntry::new(&self.board, mv, score, depth, flag));
+        }
     }
     
-    /// The alpha-beta negamax algorithm, with a few more heuristics in it.
-    pub(crate) fn alpha_beta(&mut self, mut alpha: f32, beta: f32, do_null: bool, mut depth: u8, search_depth: u8) -> f32 {        
+    /// Returns true if the quiet move comes late enough in the move ordering, or has a bad
+    /// enough history, to be pruned at that depth.
+    #[inline]
+    fn is_late_quiet(&self, mv: Move, depth: u8, move_count: usize) -> bool {
+        let pruning = self.pruning;
+        let depth_squared = i32::from(depth) * i32::from(depth);
+
+        (pruning.late_moves && usize::from(depth) < params::LATE_MOVE_COUNTS.len()
+            && move_count > params::LATE_MOVE_COUNTS[usize::from(depth)])
+        || (pruning.history && depth <= params::HISTORY_PRUNING_DEPTH
+            && self.history.get(self.board.get_side_to_move(), mv) < params::HISTORY_PRUNING_MARGIN * depth_squared)
+    }
+
+    /// Rewards the quiet move for a cutoff, and punishes the quiets searched before it
+    /// from that index, by the square of the depth.
+    #[inline]
+    fn update_history(&mut self, mv: Move, quiets: usize, depth: u8) {
+        let us = self.board.get_side_to_move();
+        let bonus = i32::from(depth) * i32::from(depth);
+
+        self.history.update(us, mv, bonus);
+        for &quiet in &self.quiets[quiets..] {
+            self.history.update(us, quiet, -bonus);
+        }
+    }
+
+    /// Returns true if the move is extended for what it is, its checks aside: a recapture
+    /// on the square of the previous capture, a push of a passed pawn close to promotion,
+    /// or the singular move of the position.
+    #[inline]
+    fn is_extended(&self, mv: Move, singular: Option<Move>) -> bool {
+        let extensions = self.extension_rules;
+        let us = self.board.get_side_to_move();
+
+        (extensions.singular && singular == Some(mv))
+        || (extensions.recapture && mv.is_capture()
+            && self.path.last().is_some_and(|last| last.is_capture() && last.to() == mv.to()
+                && (params::value_of(last.get_capture()) - params::value_of(mv.get_capture())).abs() < params::value_of(Piece::Pawn)))
+        || (extensions.passed_pawn && !mv.is_drop() && self.board.get_piece_unchecked(mv.from()) == Piece::Pawn
+            && utils::relative_rank(us, mv.to()) >= params::PASSED_PAWN_RANK
+            && utils::is_passed(&self.board, us, mv.to()))
+    }
+
+    /// Returns the move of the table if it is singular: if searching the position without it,
+    /// at a reduced depth, fails low by a margin below its score.
+    fn singular_move(&mut self, depth: u8, search_depth: u8) -> Option<Move> {
+        if !self.extension_rules.singular || depth < params::SINGULAR_DEPTH {
+            return None;
+        }
+
+        // Only a lower bound or an exact score, close to the depth, tells the move is good.
+        let entry = self.info.get_table().get(self.board.get_zobrist())?;
+        if entry.depth + params::SINGULAR_TABLE_DEPTH < depth 
+            || matches!(entry.flag, TableEntryFlag::Alpha)
+            || entry.score.abs() >= params::MATE_BOUND
+            || !self.board.is_pseudo_legal(entry.mv) || !self.board.is_legal(entry.mv) {
+            return None;
+        }
+
+        let singular_beta = entry.score - params::SINGULAR_MARGIN * depth as f32;
+        self.skipped = Some(entry.mv);
+        let score = self.alpha_beta(singular_beta - params::SINGULAR_WINDOW, singular_beta, false, (depth - 1) / 2, search_depth);
+
+        if score < singular_beta {
+            Some(entry.mv)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of a draw in the current position, relative to the side to move.
+    #[inline]
+    fn draw_value(&self) -> f32 {
+        utils::draw_value(&self.board, self.us, self.contempt, self.nodes)
+    }
+
+    /// Evaluates the position with the network if there is one, else with the handcrafted evaluation.
+    #[inline]
+    fn evaluate(&mut self) -> f32 {
+        match &self.nnue {
+            Some(acc) => acc.evaluate(&self.board),
+            None => eval::eval(&self.board, &mut self.pawns, self.info.get_weights(), self.eval_terms),
+        }
+    }
+
+    /// Searches the root moves in order but the excluded ones, recording their scores and
+    /// whether they are exact or bounds of the window. The moves left out by a cutoff keep
+    /// their previous scores, marked stale, until the window is widened and they are searched again.
+    /// The root is always searched, as the table ignores the history, hence the repetitions.
+    fn search_root(&mut self, mut alpha: f32, beta: f32, search_depth: u8) -> f32 {
+        let old_alpha = alpha;
+        let depth = if self.board.get_checkers().not_empty() {search_depth + 1} else {search_depth};
+
+        let mut best_score = f32::NEG_INFINITY;
+        let mut best_move = None;
+
+        for i in 0..self.root_moves.len() {
+            let mv = self.root_moves[i].get_move();
+
+            if self.excluded.contains(&mv) {
+                continue;
+            }
+
+            let nodes = self.nodes;
+            self.depth += 1;
+            self.path.push(mv);
+            self.board.do_move_with(mv, &mut self.nnue);
+            let score = -self.alpha_beta(-beta, -alpha, true, depth-1, search_depth);
+            self.board.undo_move_with(mv, &mut self.nnue);
+            self.path.pop();
+            self.depth -= 1;
+
+            if self.info.search_depth() >= search_depth || !self.info.is_searching() {
+                return 0.0;
+            }
+
+            self.root_moves[i].update(score, alpha, beta, self.nodes - nodes);
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(mv);
+
+                if score > alpha {
+                    if score >= beta {
+                        for root in &mut self.root_moves[i+1..] {
+                            if !self.excluded.contains(&root.get_move()) {
+                                root.mark_stale();
+                            }
+                        }
+
+                        self.insert(mv, beta, depth, TableEntryFlag::Beta);
+                        return beta;
+                    }
+
+                    alpha = score;
+                }
+            }
+        }
+
+        if let Some(mv) = best_move {
+            if alpha != old_alpha {
+                self.insert(mv, best_score, depth, TableEntryFlag::Exact);
+                self.best_move = best_move;
+            } else {
+                self.insert(mv, best_score, depth, TableEntryFlag::Alpha);
+            }
+        }
+
+        alpha
+    }
+
+    /// The alpha-beta negamax algorithm, with a few more heuristics in it. A skipped move,
+    /// set before the call, is left out of the search of the position.
+    pub(crate) fn alpha_beta(&mut self, mut alpha: f32, beta: f32, do_null: bool, depth: u8, search_depth: u8) -> f32 {        
+        let skipped = self.skipped.take();
+
         if depth == 0 {
+            self.seldepth = self.seldepth.max(self.depth);
             return self.quiescence(alpha, beta);
         }
+
+        self.nodes += 1;
+
+        if let Some(score) = utils::variant_score(&self.board, self.depth) {
+            return score;
+        }
         
-        if utils::is_pseudo_draw(&self.board, alpha, self.depth == 0) {
+        let draw = self.draw_value();
+        if utils::is_pseudo_draw(&self.board, alpha, draw, self.depth == 0) {
+            // The 50 move rule ends the game, the root aside as a move must be found.
+            if self.depth > 0 && self.board.get_halfmove() >= 100 {
+                return draw;
+            }
+
+            // The side to move can at least draw, which may be enough for a cutoff.
+            alpha = draw;
             if alpha >= beta {
-                return utils::prng_draw_value(&mut self.seed);
+                return alpha;
             }
         }
         
         if self.depth >= params::MAX_DEPTH {
-            return eval::eval(&self.board);
+            return self.evaluate();
         }
         
-        if let Some((mv, score)) = self.info.get_table().probe(self.board.get_zobrist(), alpha, beta, depth) {
-            if self.board.is_pseudo_legal(mv) && self.board.is_legal(mv) {
-                if score >= alpha && self.depth == 0 {
-                    self.best_move = Some(mv);
+        // The table holds the results of the whole position, not of the one without the skipped move.
+        if skipped.is_none() {
+            if let Some((mv, score)) = self.info.get_table().probe(self.board.get_zobrist(), alpha, beta, depth) {
+                if self.board.is_pseudo_legal(mv) && self.board.is_legal(mv) {
+                    return score;
                 }
-                return score;
             }
         }
         
         let old_alpha = alpha;
         let in_check = self.board.get_checkers().not_empty();
         
-        if in_check {
-            depth += 1;
-        } else if do_null && self.depth > 0 && depth >= 4 {
+        if !in_check && do_null && self.depth > 0 && depth >= 4 {
             // TODO: Null move heuristic
         }
+
+        // Forward pruning, out of check and away from mates. Antichess forces captures
+        // and its evaluation hardly knows more than the material.
+        let prunable = !in_check 
+            && alpha.abs() < params::MATE_BOUND && beta.abs() < params::MATE_BOUND
+            && self.board.get_variant() != Variant::Antichess;
+        let pruning = self.pruning;
+
+        // The static evaluation is only needed by the heuristics comparing it to the bounds.
+        let eval = if prunable && (pruning.reverse_futility || pruning.futility || pruning.razoring) {
+            self.evaluate()
+        } else {
+            0.0
+        };
+
+        if prunable {
+            // Reverse futility: the position is good enough to stay above beta.
+            if pruning.reverse_futility && depth <= params::REVERSE_FUTILITY_DEPTH 
+                && eval - params::REVERSE_FUTILITY_MARGIN * depth as f32 >= beta {
+                return beta;
+            }
+
+            // Razoring: the position is bad enough that only captures may save it.
+            if pruning.razoring && usize::from(depth) < params::RAZORING_MARGINS.len()
+                && eval + params::RAZORING_MARGINS[usize::from(depth)] <= alpha
+                && self.quiescence(alpha, beta) <= alpha {
+                return alpha;
+            }
+        }
+
+        // Futility: at frontier nodes, quiet moves are unlikely to raise the score up to alpha.
+        let futile = prunable && pruning.futility && usize::from(depth) < params::FUTILITY_MARGINS.len()
+            && eval + params::FUTILITY_MARGINS[usize::from(depth)] <= alpha;
+
+        // The search of a skipped move is not itself looking for a singular move.
+        let singular = if skipped.is_none() {self.singular_move(depth, search_depth)} else {None};
     
         let mut best_score = f32::NEG_INFINITY;
         let mut best_move = None;
         let mut picker = MovePicker::new(&self.board, &self.buffer);
         let mut move_count = 0;
+        let quiets = self.quiets.len();
     
         while let Some(range) = picker.next(&self.board, &mut self.buffer) {
             for i in range {
                 let mv = self.buffer[i];
 
-                if !self.board.is_legal(mv) {
+                if skipped == Some(mv) || !self.board.is_legal(mv) {
+                    continue;
+                }
+
+                move_count += 1;
+                let quiet = !mv.is_capture() && !mv.is_en_passant() && !mv.is_promote();
+                let pruned = quiet && prunable && move_count > 1 && (futile || self.is_late_quiet(mv, depth, move_count));
+
+                // Quiet moves giving check are always searched.
+                if pruned && !self.board.gives_check(mv) {
                     continue;
                 }
 
+                let extended = self.is_extended(mv, singular);
+
                 self.depth += 1;
-                self.board.do_move(mv);
-                let score = -self.alpha_beta(-beta, -alpha, do_null, depth-1, search_depth);
-                self.board.undo_move(mv);
+                self.board.do_move_with(mv, &mut self.nnue);
+                let gives_check = self.board.get_checkers().not_empty();
+
+                // A path is extended by at most half the search depth, lest the tree explodes.
+                let extension = u8::from((extended || (self.extension_rules.check && gives_check)) && 2 * self.extensions < search_depth);
+
+                self.path.push(mv);
+                self.extensions += extension;
+                let score = -self.alpha_beta(-beta, -alpha, do_null, depth - 1 + extension, search_depth);
+                self.extensions -= extension;
+                self.path.pop();
+                self.board.undo_move_with(mv, &mut self.nnue);
                 self.depth -= 1;
 
                 if self.info.search_depth() >= search_depth || !self.info.is_searching() {
+                    self.quiets.truncate(quiets);
                     return 0.0;
                 }
         
@@ -178,17 +556,15 @@ impl Search {
                     
                     if score > alpha {
                         if score >= beta {
-                            if !mv.is_capture() {
+                            if quiet {
                                 // TODO: killer heuristic
+                                self.update_history(mv, quiets, depth);
                             }
 
-                            self.info.get_table().insert(TableEntry::new(
-                                &self.board,
-                                mv, 
-                                beta,
-                                depth, 
-                                TableEntryFlag::Beta
-                            ));
+                            self.quiets.truncate(quiets);
+                            if skipped.is_none() {
+                                self.insert(mv, beta, depth, TableEntryFlag::Beta);
+                            }
                             
                             return beta;
                         }
@@ -196,39 +572,31 @@ impl Search {
                         alpha = score;
                     }
                 }
-                
-                move_count += 1;
+
+                if quiet {
+                    self.quiets.push(mv);
+                }
             }
         }
+
+        self.quiets.truncate(quiets);
         
         if move_count == 0 {
-            return if in_check {
+            // Without the skipped move, nothing was found: it is singular.
+            return if skipped.is_some() {
+                alpha
+            } else if self.board.get_variant() == Variant::Antichess {
+                params::value_of(Piece::King) - self.depth as f32
+            } else if in_check {
                 -params::value_of(Piece::King) + self.depth as f32
             } else {
-                0.0
+                self.draw_value()
             };
         }
         
-        if alpha != old_alpha {
-            self.info.get_table().insert(TableEntry::new(
-                &self.board,
-                best_move.unwrap(), 
-                best_score, 
-                depth, 
-                TableEntryFlag::Exact
-            ));
-            
-            if self.depth == 0 {
-                self.best_move = best_move;
-            }
-        } else {
-            self.info.get_table().insert(TableEntry::new(
-                &self.board,
-                best_move.unwrap(), 
-                best_score, 
-                depth, 
-                TableEntryFlag::Alpha
-            ));
+        if skipped.is_none() {
+            let flag = if alpha != old_alpha {TableEntryFlag::Exact} else {TableEntryFlag::Alpha};
+            self.insert(best_move.unwrap(), best_score, depth, flag);
         }
         
         alpha
@@ -236,33 +604,52 @@ impl Search {
 
     /// Return the value of the position, computed with a quiescent search (only considering captures).
     fn quiescence(&mut self, mut alpha: f32, beta: f32) -> f32 {
-        if utils::is_pseudo_draw(&self.board, alpha, self.depth == 0) {
+        self.nodes += 1;
+
+        let draw = self.draw_value();
+        if utils::is_pseudo_draw(&self.board, alpha, draw, self.depth == 0) {
+            // The 50 move rule ends the game, the root aside as a move must be found.
+            if self.depth > 0 && self.board.get_halfmove() >= 100 {
+                return draw;
+            }
+
+            // The side to move can at least draw, which may be enough for a cutoff.
+            alpha = draw;
             if alpha >= beta {
-                return utils::prng_draw_value(&mut self.seed);
+                return alpha;
             }
         }
+
+        if let Some(score) = utils::variant_score(&self.board, self.depth) {
+            return score;
+        }
         
-        let stand_pat = eval::eval(&self.board);
+        let stand_pat = self.evaluate();
     
         if self.depth >= params::MAX_DEPTH {
             return stand_pat;
         }
-    
-        if stand_pat >= beta {
-            return beta;
-        }
-    
-        let mut big_delta = params::value_of(Piece::Queen);
-        if utils::may_promote(&self.board) {
-            big_delta += params::value_of(Piece::Queen) - params::value_of(Piece::Pawn);
-        }
-    
-        if stand_pat < alpha - big_delta {
-            return alpha;
+
+        // In antichess, a capture must be played when there is one, so the side to move may not stand pat.
+        let forced = self.board.get_variant() == Variant::Antichess && self.board.can_capture();
+
+        if !forced {
+            if stand_pat >= beta {
+                return beta;
+            }
+        
+            let mut big_delta = params::value_of(Piece::Queen);
+            if utils::may_promote(&self.board) {
+                big_delta += params::value_of(Piece::Queen) - params::value_of(Piece::Pawn);
+            }
+        
+            if stand_pat < alpha - big_delta {
+                return alpha;
+            }
+        
+            alpha = alpha.max(stand_pat);
         }
     
-        alpha = alpha.max(stand_pat);
-    
         let mut picker = MovePicker::new(&self.board, &self.buffer);
     
         'search: while let Some(range) = picker.next(&self.board, &mut self.buffer) {
@@ -273,14 +660,14 @@ impl Search {
                     break 'search; // TODO: Better movepicker
                 }
 
-                if !mv.is_capture() || params::value_of(mv.get_capture()) + params::DELTA < alpha || !self.board.is_legal(mv) {
+                if !mv.is_capture() || (!forced && params::value_of(mv.get_capture()) + params::DELTA < alpha) || !self.board.is_legal(mv) {
                     continue;
                 }
         
                 self.depth += 1;
-                self.board.do_move(mv);
+                self.board.do_move_with(mv, &mut self.nnue);
                 let score = -self.quiescence(-beta, -alpha);
-                self.board.undo_move(mv);
+                self.board.undo_move_with(mv, &mut self.nnue);
                 self.depth -= 1;
         
                 if !self.info.is_searching() {
@@ -0,0 +1,148 @@
+use std::fs;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Error, Result};
+use clap::{App, Arg};
+
+use chess::prelude::*;
+use engine::{Engine, EvalTerms, Extensions, Pruning};
+
+/// The time to think for each move, in milliseconds.
+const DEFAULT_TIME: &str = "100";
+
+/// The number of games played from each opening, with each color.
+const DEFAULT_ROUNDS: &str = "1";
+
+/// The number of halfmoves after which a game is adjudicated as a draw.
+const MAX_PLIES: usize = 400;
+
+/// The features of the search that may be switched off.
+const FEATURES: &[&str] = &["pruning", "extensions", "eval-terms"];
+
+/// The openings the games start from when none are provided: balanced positions
+/// out of the main lines.
+const OPENINGS: &[&str] = &[
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+    "rnbqkb1r/pp2pppp/3p1n2/8/3NP3/8/PPP2PPP/RNBQKB1R w KQkq - 1 5",
+    "rnbqkb1r/ppp1pppp/5n2/3p4/2PP4/8/PP2PPPP/RNBQKBNR w KQkq - 1 3",
+    "rnbqkbnr/pp2pppp/2p5/3p4/3PP3/8/PPP2PPP/RNBQKBNR w KQkq - 0 3",
+    "rnbqk2r/ppppppbp/5np1/8/2PP4/2N5/PP2PPPP/R1BQKBNR w KQkq - 2 4",
+    "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+    "rnbqkbnr/pppp1ppp/4p3/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2",
+];
+
+/// Creates an engine for that board, with or without the feature.
+fn engine(board: &Board, feature: &str, on: bool) -> Engine {
+    let engine = Engine::new(board.clone());
+
+    match feature {
+        "pruning" => engine.set_pruning(if on {Pruning::default()} else {Pruning::NONE}),
+        "extensions" => engine.set_extensions(if on {Extensions::default()} else {Extensions::NONE}),
+        "eval-terms" => engine.set_eval_terms(if on {EvalTerms::default()} else {EvalTerms::NONE}),
+        _ => unreachable!(),
+    }
+
+    engine
+}
+
+/// Plays a game from the opening between the engine with the feature, playing that color,
+/// and the engine without it. Returns whether the former won, or None for a draw.
+fn play(fen: &str, feature: &str, color: Color, time: Duration) -> Result<Option<bool>> {
+    let mut board = Board::new(fen)?;
+    let (with, without) = (engine(&board, feature, true), engine(&board, feature, false));
+
+    for _ in 0..MAX_PLIES {
+        match board.status() {
+            Status::Playing => {},
+            Status::Draw => return Ok(None),
+            Status::Win(winner) => return Ok(Some(winner == color)),
+        }
+
+        let engine = if board.get_side_to_move() == color {&with} else {&without};
+        *engine.write_board() = board.clone();
+        engine.start();
+        thread::sleep(time);
+        engine.stop();
+
+        board.do_move(engine.get_best_move().ok_or(Error::msg("The engine found no move."))?);
+    }
+
+    Ok(None)
+}
+
+/// The main function parses the arguments, then plays the engine with a feature of the search
+/// against itself without it, from each opening with both colors, and reports the results.
+fn main() -> Result<()> {
+    let args = App::new("Rush Self-Play")
+        .version(env!("CARGO_PKG_VERSION"))
+        .author("Benjamin Lefebvre")
+        .about("Plays the Rush chess engine against itself with a feature of the search switched off, to measure what it is worth.")
+        .arg(Arg::with_name("feature")
+            .value_name("FEATURE")
+            .help("The feature of the search switched off for the opponent.")
+            .possible_values(FEATURES)
+            .required(true))
+        .arg(Arg::with_name("time")
+            .short("t")
+            .long("time")
+            .value_name("MS")
+            .help("The time to think for each move, in milliseconds. Defaults to 100.")
+            .takes_value(true))
+        .arg(Arg::with_name("rounds")
+            .short("r")
+            .long("rounds")
+            .value_name("N")
+            .help("The number of games played from each opening with each color. Defaults to 1.")
+            .takes_value(true))
+        .arg(Arg::with_name("openings")
+            .short("o")
+            .long("openings")
+            .value_name("FILE")
+            .help("The positions the games start from, one fen per line, instead of the built-in ones.")
+            .takes_value(true))
+        .get_matches();
+
+    let feature = args.value_of("feature").unwrap();
+    let time = Duration::from_millis(u64::from_str(args.value_of("time").unwrap_or(DEFAULT_TIME))?);
+    let rounds = usize::from_str(args.value_of("rounds").unwrap_or(DEFAULT_ROUNDS))?;
+
+    let openings = match args.value_of("openings") {
+        Some(path) => fs::read_to_string(path)?.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from).collect(),
+        None => OPENINGS.iter().map(|&fen| String::from(fen)).collect::<Vec<_>>(),
+    };
+    if openings.is_empty() || rounds == 0 {
+        return Err(Error::msg("No games to play."));
+    }
+
+    let (mut wins, mut draws, mut losses) = (0, 0, 0);
+
+    for _ in 0..rounds {
+        for fen in &openings {
+            for color in Color::COLORS {
+                match play(fen, feature, color, time)? {
+                    Some(true) => wins += 1,
+                    Some(false) => losses += 1,
+                    None => draws += 1,
+                }
+
+                println!("{:<72} {:?}: +{} ={} -{}", fen, color, wins, draws, losses);
+            }
+        }
+    }
+
+    // The difference of elo matching the score, which has no finite value if a side won every game.
+    let games = f64::from(wins + draws + losses);
+    let score = (f64::from(wins) + 0.5 * f64::from(draws)) / games;
+    let elo = if score > 0.0 && score < 1.0 {
+        format!("{:+.0} elo", 400.0 * (score / (1.0 - score)).log10())
+    } else {
+        String::from("n/a elo")
+    };
+
+    println!("With {} against without: +{} ={} -{}, scoring {:.1}%, {}.", feature, wins, draws, losses, 100.0 * score, elo);
+
+    Ok(())
+}
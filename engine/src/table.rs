@@ -25,8 +25,8 @@ pub(crate) struct TableEntry {
     age: u16,
     pub(crate) mv: Move,
     pub(crate) score: f32,
-    depth: u8,
-    flag: TableEntryFlag,
+    pub(crate) depth: u8,
+    pub(crate) flag: TableEntryFlag,
 }
 
 // ================================ pub(crate) impl
@@ -121,6 +121,23 @@ impl TranspositionTable {
 
         None
     }
+
+    /// Empties the table.
+    pub(crate) fn clear(&self) {
+        for i in 0..NUM_BUCKETS {
+            // SAFE: i < NUM_BUCKETS, the table is only cleared between searches.
+            unsafe {*self.0.add(i) = None};
+        }
+    }
+
+    /// Returns the entry stored for that position, whatever its depth and bound.
+    #[inline]
+    pub(crate) fn get(&self, zobrist: Zobrist) -> Option<TableEntry> {
+        let i = zobrist.idx::<NUM_BUCKETS>();
+
+        // SAFE: not inherently unsafe, at worst we risk getting a currupted entry.
+        unsafe {*self.0.offset(i)}.filter(|entry| entry.zobrist == zobrist)
+    }
 }
 
 // ================================ traits impl
@@ -128,7 +145,7 @@ impl TranspositionTable {
 impl Drop for TranspositionTable {
     /// TranspositionTable needs to be manually dropped.
     fn drop(&mut self) {
-        unsafe {Box::from_raw(self.0)};
+        drop(unsafe {Box::from_raw(self.0)});
     }
 }
 
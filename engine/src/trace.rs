@@ -0,0 +1,186 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use chess::color::Color;
+
+//#################################################################################################
+//
+//                                          enum Term
+//
+//#################################################################################################
+
+/// A term of the handcrafted evaluation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Term {
+    Material,
+    PieceSquares,
+    Pawns,
+    Mobility,
+    KingSafety,
+    Pieces,
+    Variant,
+    Endgame,
+}
+
+// ================================ pub impl
+
+impl Term {
+    /// The terms, in the order of a trace.
+    pub const TERMS: [Term; 8] = [
+        Term::Material,
+        Term::PieceSquares,
+        Term::Pawns,
+        Term::Mobility,
+        Term::KingSafety,
+        Term::Pieces,
+        Term::Variant,
+        Term::Endgame,
+    ];
+
+    /// Returns the name of the term, for display.
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Term::Material => "Material",
+            Term::PieceSquares => "Piece-square",
+            Term::Pawns => "Pawns",
+            Term::Mobility => "Mobility",
+            Term::KingSafety => "King safety",
+            Term::Pieces => "Pieces",
+            Term::Variant => "Variant",
+            Term::Endgame => "Endgame",
+        }
+    }
+}
+
+//#################################################################################################
+//
+//                                         trait Trace
+//
+//#################################################################################################
+
+/// A sink for the contributions of the terms of the evaluation. The unit type ignores
+/// them, so that the evaluation pays nothing for the tracing when searching.
+pub(crate) trait Trace {
+    /// Adds the midgame and endgame contribution of a term for that color.
+    fn add(&mut self, term: Term, color: Color, mg: f32, eg: f32);
+
+    /// Sets the factor scaling the endgame values, for drawish material.
+    fn scale(&mut self, _scale: f32) {}
+}
+
+// ================================ traits impl
+
+impl Trace for () {
+    #[inline]
+    fn add(&mut self, _: Term, _: Color, _: f32, _: f32) {}
+}
+
+//#################################################################################################
+//
+//                                       struct EvalTrace
+//
+//#################################################################################################
+
+/// The contribution of a term for both colors, as [midgame, endgame] values in pawns.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct TermTrace {
+    pub term: Term,
+    pub white: [f32; 2],
+    pub black: [f32; 2],
+}
+
+/// The breakdown of the handcrafted evaluation of a position, by term and color.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EvalTrace {
+    terms: Vec<TermTrace>,
+    phase: f32,
+    scale: f32,
+    score: f32,
+}
+
+// ================================ pub impl
+
+impl EvalTrace {
+    /// Returns the contributions of the terms.
+    #[inline]
+    pub fn get_terms(&self) -> &[TermTrace] {
+        &self.terms
+    }
+
+    /// Returns the weight of the midgame values, from 1 with all the pieces to 0 with none.
+    #[inline]
+    pub fn get_phase(&self) -> f32 {
+        self.phase
+    }
+
+    /// Returns the factor by which the endgame values are scaled, below 1 with drawish material.
+    #[inline]
+    pub fn get_scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Returns the evaluation of the position from white's point of view, in pawns.
+    #[inline]
+    pub fn get_score(&self) -> f32 {
+        self.score
+    }
+}
+
+// ================================ pub(crate) impl
+
+impl EvalTrace {
+    /// Creates an empty trace, for a position at that phase.
+    pub(crate) fn new(phase: f32) -> EvalTrace {
+        EvalTrace {
+            terms: Term::TERMS.iter().map(|&term| TermTrace {term, white: [0.0; 2], black: [0.0; 2]}).collect(),
+            phase,
+            scale: 1.0,
+            score: 0.0,
+        }
+    }
+
+    /// Sets the final evaluation, from white's point of view.
+    pub(crate) fn set_score(&mut self, score: f32) {
+        self.score = score;
+    }
+}
+
+// ================================ traits impl
+
+impl Trace for EvalTrace {
+    #[inline]
+    fn add(&mut self, term: Term, color: Color, mg: f32, eg: f32) {
+        let entry = &mut self.terms[term as usize];
+        let values = if color == Color::White {&mut entry.white} else {&mut entry.black};
+        values[0] += mg;
+        values[1] += eg;
+    }
+
+    #[inline]
+    fn scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+}
+
+impl fmt::Display for EvalTrace {
+    /// Formats the trace as a table, with the difference between the colors for each term.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<14}|{:^17}|{:^17}|{:^17}", "Term", "White", "Black", "Total")?;
+        writeln!(f, "{:<14}|{:>8} {:>8}|{:>8} {:>8}|{:>8} {:>8}", "", "MG", "EG", "MG", "EG", "MG", "EG")?;
+        writeln!(f, "{:-<14}+{:-<17}+{:-<17}+{:-<17}", "", "", "", "")?;
+
+        for entry in &self.terms {
+            let [white_mg, white_eg] = entry.white;
+            let [black_mg, black_eg] = entry.black;
+            writeln!(
+                f, "{:<14}|{:>8.2} {:>8.2}|{:>8.2} {:>8.2}|{:>8.2} {:>8.2}",
+                entry.term.get_name(), white_mg, white_eg, black_mg, black_eg, white_mg - black_mg, white_eg - black_eg,
+            )?;
+        }
+
+        writeln!(f, "{:-<14}+{:-<17}+{:-<17}+{:-<17}", "", "", "", "")?;
+        write!(f, "Phase: {:.2}, endgame scale: {:.2}, evaluation: {:.2} from white's point of view.", self.phase, self.scale, self.score)
+    }
+}
@@ -0,0 +1,195 @@
+use std::fs;
+use std::str::FromStr;
+use std::thread;
+use std::time::Instant;
+
+use anyhow::{Error, Result};
+use clap::{App, Arg};
+
+use chess::epd::Epd;
+use chess::prelude::*;
+use engine::EvalParams;
+
+/// The step by which weights are moved by the local search, in pawns.
+const DEFAULT_STEP: &str = "0.01";
+
+/// The maximum number of passes over the weights.
+const DEFAULT_PASSES: &str = "100";
+
+/// A position labeled with the result of its game, from white's point of view.
+struct Sample {
+    board: Board,
+    result: f64,
+}
+
+/// Parses the result of a game, as "1-0", "0-1" or "1/2-1/2", or as white's score
+/// "1.0", "0.5" or "0.0", possibly quoted or in brackets.
+fn parse_result(s: &str) -> Result<f64> {
+    match s.trim_matches(|c| matches!(c, '"' | '[' | ']' | ';')) {
+        "1-0" => Ok(1.0),
+        "0-1" => Ok(0.0),
+        "1/2-1/2" => Ok(0.5),
+        score => match f64::from_str(score) {
+            Ok(score) if (0.0..=1.0).contains(&score) => Ok(score),
+            _ => Err(Error::msg(format!("Invalid result: \"{}\".", s))),
+        },
+    }
+}
+
+/// Parses a labeled position: either an epd record with the result in the "c9" opcode,
+/// or a fen string followed by the result, like "<fen> [1.0]".
+fn parse_sample(line: &str) -> Result<Sample> {
+    if line.contains(" c9 ") {
+        let epd = Epd::new(line)?;
+        let result = epd.get_operation("c9").and_then(|operands| operands.first())
+            .ok_or(Error::msg("Cannot find the result in the c9 opcode."))?;
+
+        return Ok(Sample {
+            board: epd.get_board().clone(),
+            result: parse_result(result)?,
+        });
+    }
+
+    let (fen, result) = line.trim().rsplit_once(' ').ok_or(Error::msg("Cannot find the result of the position."))?;
+    Ok(Sample {
+        board: Board::new(fen.trim())?,
+        result: parse_result(result)?,
+    })
+}
+
+/// Returns the mean squared error between the results of the samples and the evaluation
+/// of their positions, mapped to an expected score by a sigmoid of scale k.
+fn error(samples: &[Sample], weights: &EvalParams, k: f64, threads: usize) -> f64 {
+    let chunk = samples.len().div_ceil(threads);
+
+    let sum: f64 = thread::scope(|s| {
+        let handles: Vec<_> = samples.chunks(chunk.max(1)).map(|samples| s.spawn(move || {
+            samples.iter().map(|sample| {
+                let score = f64::from(engine::evaluate_with(&sample.board, weights));
+                let score = if sample.board.get_side_to_move() == Color::White {score} else {-score};
+                let expected = 1.0 / (1.0 + 10f64.powf(-k * score / 4.0));
+                (sample.result - expected).powi(2)
+            }).sum::<f64>()
+        })).collect();
+
+        handles.into_iter().map(|handle| handle.join().unwrap()).sum()
+    });
+
+    sum / samples.len() as f64
+}
+
+/// Finds the scale of the sigmoid minimizing the error with the given weights, by
+/// scanning values with finer and finer steps.
+fn find_k(samples: &[Sample], weights: &EvalParams, threads: usize) -> f64 {
+    let (mut best, mut step) = (1.0, 1.0);
+
+    for _ in 0..5 {
+        let candidates = (-10..=10).map(|i| best + step * f64::from(i)).filter(|&k| k > 0.0);
+        best = candidates
+            .map(|k| (k, error(samples, weights, k, threads)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+            .0;
+        step /= 10.0;
+    }
+
+    best
+}
+
+/// Tunes the weights with a local search: each weight is moved by the step in both
+/// directions, keeping the changes that lower the error, until none does.
+fn local_search(samples: &[Sample], weights: &mut EvalParams, k: f64, step: f32, passes: usize, threads: usize) {
+    let mut best = error(samples, weights, k, threads);
+    println!("Initial error: {:.6}.", best);
+
+    for pass in 1..=passes {
+        let start = Instant::now();
+        let mut improved = 0;
+
+        for i in 0..weights.as_slice().len() {
+            for delta in [step, -2.0 * step] {
+                weights.as_mut_slice()[i] += delta;
+                let e = error(samples, weights, k, threads);
+                if e < best {
+                    best = e;
+                    improved += 1;
+                    break;
+                }
+                if delta < 0.0 {
+                    weights.as_mut_slice()[i] += step;
+                }
+            }
+        }
+
+        println!("Pass {}: error {:.6}, {} weights improved in {:.1}s.", pass, best, improved, start.elapsed().as_secs_f64());
+        if improved == 0 {
+            break;
+        }
+    }
+}
+
+/// The main function parses the arguments, loads the labeled positions and tunes the weights
+/// of the evaluation on them, then writes them as json or rust source.
+fn main() -> Result<()> {
+    let args = App::new("Rush Texel Tuner")
+        .version(env!("CARGO_PKG_VERSION"))
+        .author("Benjamin Lefebvre")
+        .about("Tunes the weights of the evaluation of the Rush chess engine on positions labeled with the results of their games.")
+        .arg(Arg::with_name("positions")
+            .value_name("FILE")
+            .help("The labeled positions, one per line: a fen followed by the result (1-0, 0-1, 1/2-1/2 or 1.0, 0.5, 0.0, possibly in brackets), or an epd record with the result in the c9 opcode.")
+            .required(true))
+        .arg(Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .value_name("FILE")
+            .help("The file the tuned weights are written to: as json if it ends with .json, to be loaded with --weights, else as the rust source of an EvalParams. They are printed as rust source if no file is provided.")
+            .takes_value(true))
+        .arg(Arg::with_name("weights")
+            .short("w")
+            .long("weights")
+            .value_name("FILE")
+            .help("Starts from the weights of that json file instead of the built-in ones.")
+            .takes_value(true))
+        .arg(Arg::with_name("step")
+            .short("s")
+            .long("step")
+            .value_name("PAWNS")
+            .help("The step by which weights are moved, in pawns. Defaults to 0.01.")
+            .takes_value(true))
+        .arg(Arg::with_name("passes")
+            .short("p")
+            .long("passes")
+            .value_name("N")
+            .help("The maximum number of passes over the weights. Defaults to 100.")
+            .takes_value(true))
+        .get_matches();
+
+    let step = f32::from_str(args.value_of("step").unwrap_or(DEFAULT_STEP))?;
+    let passes = usize::from_str(args.value_of("passes").unwrap_or(DEFAULT_PASSES))?;
+    let threads = thread::available_parallelism().map_or(1, usize::from);
+
+    let samples = fs::read_to_string(args.value_of("positions").unwrap())?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_sample)
+        .collect::<Result<Vec<_>>>()?;
+    if samples.is_empty() {
+        return Err(Error::msg("No labeled positions to tune on."));
+    }
+    println!("Loaded {} positions.", samples.len());
+
+    let mut weights = args.value_of("weights").map_or(Ok(EvalParams::default()), EvalParams::load)?;
+    let k = find_k(&samples, &weights, threads);
+    println!("Optimal sigmoid scale: {:.4}.", k);
+
+    local_search(&samples, &mut weights, k, step, passes, threads);
+
+    match args.value_of("output") {
+        Some(path) if path.ends_with(".json") => weights.save(path)?,
+        Some(path) => fs::write(path, weights.to_rust())?,
+        None => println!("{}", weights.to_rust()),
+    }
+
+    Ok(())
+}
@@ -4,31 +4,26 @@ use chess::color::Color;
 use chess::piece::Piece;
 use chess::square::Square;
 
+use crate::params;
+
 /// Returns true if the board is in pseudo-draw because of either the
-/// 50 move rule or an incoming threefold repetition.
+/// 50 move rule or an incoming threefold repetition, which is only
+/// worth considering when a draw would raise alpha.
 #[inline]
-pub(crate) fn is_pseudo_draw(board: &Board, alpha: f32, root: bool) -> bool {
-    board.get_halfmove() >= 100 || (!root && alpha < 0.0 && board.test_upcoming_repetition())
+pub(crate) fn is_pseudo_draw(board: &Board, alpha: f32, draw: f32, root: bool) -> bool {
+    board.get_halfmove() >= 100 || (!root && alpha < draw && board.test_upcoming_repetition())
 }
 
-/// Returns true if the board can be considered in endgame.
+/// Returns the score of the position if the game was won by a rule of the variant,
+/// relative to the side to move. The sooner the win, the better the score.
 #[inline]
-pub(crate) fn is_endgame(board: &Board) -> bool {
-    Color::COLORS.iter().all(|&color| {
-        let queens = board.get_bitboard(color, Piece::Queen);
-        let rooks  = board.get_bitboard(color, Piece::Rook);
-        let occ    = board.get_occupancy().colored(color);
-
-        queens.empty() || (queens.count() == 1 && rooks.empty() && occ.count() < 3)
+pub(crate) fn variant_score(board: &Board, ply: u8) -> Option<f32> {
+    board.get_variant_winner().map(|winner| {
+        let score = params::value_of(Piece::King) - ply as f32;
+        if winner == board.get_side_to_move() {score} else {-score}
     })
 }
 
-/// Returns the color the king is of that color is standing on.
-#[inline]
-pub(crate) fn king_sq_color(board: &Board, color: Color) -> Square {
-    unsafe {board.get_bitboard(color, Piece::King).as_square_unchecked()}
-}
-
 /// Returns true if any of our pawn may promote this turn.
 #[inline]
 pub(crate) fn may_promote(board: &Board) -> bool {
@@ -36,9 +31,56 @@ pub(crate) fn may_promote(board: &Board) -> bool {
     (board.get_bitboard(us, Piece::Pawn) & BitBoard::promote_rank(us)).not_empty()
 }
 
-/// Returns a pseudo-random draw value, to avoid threefold repetitions.
+/// Returns the value of a draw relative to the side to move: the contempt is the value
+/// the engine playing `us` gives up by drawing, and a small jitter derived from the
+/// nodes searched keeps the search from settling on repetitions, reproducibly.
+#[inline]
+pub(crate) fn draw_value(board: &Board, us: Color, contempt: f32, nodes: u64) -> f32 {
+    let jitter = params::DRAW_JITTER * (2.0 * (nodes & 1) as f32 - 1.0);
+    let contempt = if board.get_side_to_move() == us {-contempt} else {contempt};
+    contempt + jitter
+}
+
+/// Returns the rank of the square from the point of view of that color.
+#[inline]
+pub(crate) fn relative_rank(color: Color, sq: Square) -> usize {
+    match color {
+        Color::White => sq.y() as usize,
+        Color::Black => 7 - sq.y() as usize,
+    }
+}
+
+/// Returns the file of the square.
+#[inline]
+pub(crate) fn file_mask(sq: Square) -> BitBoard {
+    BitBoard(BitBoard::FILE_A.0 << sq.x())
+}
+
+/// Returns the files next to the file of the square.
+#[inline]
+pub(crate) fn adjacent_files(sq: Square) -> BitBoard {
+    let file = file_mask(sq).0;
+    BitBoard(((file << 1) & !BitBoard::FILE_A.0) | ((file >> 1) & !BitBoard::FILE_H.0))
+}
+
+/// Returns the ranks in front of the square, from the point of view of that color.
+#[inline]
+pub(crate) fn front_ranks(color: Color, sq: Square) -> BitBoard {
+    let y = sq.y() as u32;
+    match color {
+        Color::White => BitBoard(u64::MAX.checked_shl(8 * (y + 1)).unwrap_or(0)),
+        Color::Black => BitBoard((1u64 << (8 * y)) - 1),
+    }
+}
+
+/// Returns true if no pawn of the opponent may stop the pawn of that color on the square.
+#[inline]
+pub(crate) fn is_passed(board: &Board, color: Color, sq: Square) -> bool {
+    (board.get_bitboard(color.invert(), Piece::Pawn) & (file_mask(sq) | adjacent_files(sq)) & front_ranks(color, sq)).empty()
+}
+
+/// Returns the distance in king moves between the two squares.
 #[inline]
-pub(crate) fn prng_draw_value(seed: &mut u32) -> f32 {
-    *seed += 1;
-    2.0 * (*seed & 1) as f32 - 1.0
-}
\ No newline at end of file
+pub(crate) fn distance(a: Square, b: Square) -> i8 {
+    (a.x() - b.x()).abs().max((a.y() - b.y()).abs())
+}
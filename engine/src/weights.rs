@@ -0,0 +1,352 @@
+use std::convert::TryInto;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const PAWNS_MG: [f32; 64] = [
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.05, 0.1, 0.1, -0.2, -0.2, 0.1, 0.1, 0.05,
+	0.05, -0.05, -0.1, 0.0, 0.0, -0.1, -0.05, 0.05,
+	0.0, 0.0, 0.0, 0.2, 0.2, 0.0, 0.0, 0.0,
+	0.05, 0.05, 0.1, 0.25, 0.25, 0.1, 0.05, 0.05,
+	0.1, 0.1, 0.2, 0.3, 0.3, 0.2, 0.1, 0.1,
+	0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+];
+
+const ROOKS_MG: [f32; 64] = [
+	0.0, 0.0, 0.0, 0.05, 0.05, 0.0, 0.0, 0.0,
+	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
+	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
+	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
+	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
+	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
+	0.05, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.05,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+];
+
+const KNIGHTS_MG: [f32; 64] = [
+	-0.5, -0.4, -0.3, -0.3, -0.3, -0.3, -0.4, -0.5,
+	-0.4, -0.2, 0.0, 0.05, 0.05, 0.0, -0.2, -0.4,
+	-0.3, 0.05, 0.1, 0.15, 0.15, 0.1, 0.05, -0.3,
+	-0.3, 0.0, 0.15, 0.2, 0.2, 0.15, 0.0, -0.3,
+	-0.3, 0.05, 0.15, 0.2, 0.2, 0.15, 0.05, -0.3,
+	-0.3, 0.0, 0.1, 0.15, 0.15, 0.1, 0.0, -0.3,
+	-0.4, -0.2, 0.0, 0.0, 0.0, 0.0, -0.2, -0.4,
+	-0.5, -0.4, -0.3, -0.3, -0.3, -0.3, -0.4, -0.5,
+];
+
+const BISHOPS_MG: [f32; 64] = [
+	-0.2, -0.1, -0.1, -0.1, -0.1, -0.1, -0.1, -0.2,
+	-0.1, 0.05, 0.0, 0.0, 0.0, 0.0, 0.05, -0.1,
+	-0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1, -0.1,
+	-0.1, 0.0, 0.1, 0.1, 0.1, 0.1, 0.0, -0.1,
+	-0.1, 0.05, 0.05, 0.1, 0.1, 0.05, 0.05, -0.1,
+	-0.1, 0.0, 0.05, 0.1, 0.1, 0.05, 0.0, -0.1,
+	-0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.1,
+	-0.2, -0.1, -0.1, -0.1, -0.1, -0.1, -0.1, -0.2,
+];
+
+const QUEENS_MG: [f32; 64] = [
+	-0.2, -0.1, -0.1, -0.05, -0.05, -0.1, -0.1, -0.2,
+	-0.1, 0.0, 0.05, 0.0, 0.0, 0.0, 0.0, -0.1,
+	-0.1, 0.05, 0.05, 0.05, 0.05, 0.05, 0.0, -0.1,
+	0.0, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.05,
+	-0.05, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.05,
+	-0.1, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.1,
+	-0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.1,
+	-0.2, -0.1, -0.1, -0.05, -0.05, -0.1, -0.1, -0.2,
+];
+
+const PAWNS_EG: [f32; 64] = [
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.05, 0.05, 0.05, 0.05, 0.05, 0.05, 0.05, 0.05,
+	0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1,
+	0.2, 0.2, 0.2, 0.2, 0.2, 0.2, 0.2, 0.2,
+	0.35, 0.35, 0.35, 0.35, 0.35, 0.35, 0.35, 0.35,
+	0.6, 0.6, 0.6, 0.6, 0.6, 0.6, 0.6, 0.6,
+	0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+];
+
+const ROOKS_EG: [f32; 64] = [
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+	0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1,
+	0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+];
+
+const KNIGHTS_EG: [f32; 64] = [
+	-0.4, -0.3, -0.2, -0.2, -0.2, -0.2, -0.3, -0.4,
+	-0.3, -0.1, 0.0, 0.0, 0.0, 0.0, -0.1, -0.3,
+	-0.2, 0.0, 0.1, 0.1, 0.1, 0.1, 0.0, -0.2,
+	-0.2, 0.0, 0.1, 0.15, 0.15, 0.1, 0.0, -0.2,
+	-0.2, 0.0, 0.1, 0.15, 0.15, 0.1, 0.0, -0.2,
+	-0.2, 0.0, 0.1, 0.1, 0.1, 0.1, 0.0, -0.2,
+	-0.3, -0.1, 0.0, 0.0, 0.0, 0.0, -0.1, -0.3,
+	-0.4, -0.3, -0.2, -0.2, -0.2, -0.2, -0.3, -0.4,
+];
+
+const BISHOPS_EG: [f32; 64] = [
+	-0.1, -0.05, -0.05, -0.05, -0.05, -0.05, -0.05, -0.1,
+	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
+	-0.05, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.05,
+	-0.05, 0.0, 0.05, 0.1, 0.1, 0.05, 0.0, -0.05,
+	-0.05, 0.0, 0.05, 0.1, 0.1, 0.05, 0.0, -0.05,
+	-0.05, 0.0, 0.05, 0.05, 0.05, 0.05, 0.0, -0.05,
+	-0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05,
+	-0.1, -0.05, -0.05, -0.05, -0.05, -0.05, -0.05, -0.1,
+];
+
+const QUEENS_EG: [f32; 64] = [
+	-0.2, -0.15, -0.1, -0.1, -0.1, -0.1, -0.15, -0.2,
+	-0.15, -0.05, 0.0, 0.0, 0.0, 0.0, -0.05, -0.15,
+	-0.1, 0.0, 0.05, 0.1, 0.1, 0.05, 0.0, -0.1,
+	-0.1, 0.0, 0.1, 0.15, 0.15, 0.1, 0.0, -0.1,
+	-0.1, 0.0, 0.1, 0.15, 0.15, 0.1, 0.0, -0.1,
+	-0.1, 0.0, 0.05, 0.1, 0.1, 0.05, 0.0, -0.1,
+	-0.15, -0.05, 0.0, 0.0, 0.0, 0.0, -0.05, -0.15,
+	-0.2, -0.15, -0.1, -0.1, -0.1, -0.1, -0.15, -0.2,
+];
+
+const KINGS_MG: [f32; 64] = [
+	0.2, 0.3, 0.1, 0.0, 0.0, 0.1, 0.3, 0.2,
+	0.2, 0.2, 0.0, 0.0, 0.0, 0.0, 0.2, 0.2,
+	-0.1, -0.2, -0.2, -0.2, -0.2, -0.2, -0.2, -0.1,
+	-0.2, -0.3, -0.3, -0.4, -0.4, -0.3, -0.3, -0.2,
+	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
+	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
+	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
+	-0.3, -0.4, -0.4, -0.5, -0.5, -0.4, -0.4, -0.3,
+];
+
+const KINGS_EG: [f32; 64] = [
+	-0.5, -0.3, -0.3, -0.3, -0.3, -0.3, -0.3, -0.5,
+	-0.3, -0.3, 0.0, 0.0, 0.0, 0.0, -0.3, -0.3,
+	-0.3, -0.1, 0.2, 0.3, 0.3, 0.2, -0.1, -0.3,
+	-0.3, -0.1, 0.3, 0.4, 0.4, 0.3, -0.1, -0.3,
+	-0.3, -0.1, 0.3, 0.4, 0.4, 0.3, -0.1, -0.3,
+	-0.3, -0.1, 0.2, 0.3, 0.3, 0.2, -0.1, -0.3,
+	-0.3, -0.2, -0.1, 0.0, 0.0, -0.1, -0.2, -0.3,
+	-0.5, -0.4, -0.3, -0.2, -0.2, -0.3, -0.4, -0.5,
+];
+
+//#################################################################################################
+//
+//                                       struct EvalParams
+//
+//#################################################################################################
+
+/// The weights of the handcrafted evaluation, in pawns. Most terms have a midgame and an
+/// endgame value, interpolated by the phase of the game, and are from white's point of view:
+/// the tables start at a1, and are mirrored for black. The struct is only made of f32, so
+/// that the tuner can see it as a slice. In json files, missing fields keep their built-in value.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvalParams {
+    /// The values of the pieces, by piece order.
+    pub material: [[f32; 2]; 5],
+    /// The piece-square tables, by piece order.
+    #[serde(serialize_with = "serialize_tables", deserialize_with = "deserialize_tables")]
+    pub tables: [[[f32; 64]; 2]; 6],
+    /// The bonus for passed pawns, by relative rank.
+    pub passed: [[f32; 2]; 8],
+    /// The bonus for candidate passed pawns, by relative rank.
+    pub candidate: [[f32; 2]; 8],
+    /// The bonus for pawns defended by or next to another pawn, by relative rank.
+    pub connected: [[f32; 2]; 8],
+    /// The penalties for isolated, doubled and backward pawns.
+    pub isolated: [f32; 2],
+    pub doubled: [f32; 2],
+    pub backward: [f32; 2],
+    /// The endgame bonus per square of distance from the enemy king and the own king
+    /// to the square in front of a passed pawn, multiplied by its relative rank.
+    pub king_distance: [f32; 2],
+    /// The bonus per safe square attacked, by piece order.
+    pub mobility: [[f32; 2]; 6],
+    /// The attack units of a piece hitting a square of the enemy king zone, by piece order.
+    pub attack_units: [f32; 6],
+    /// The bonus for the attacks on the enemy king, per square of the attack units, and its
+    /// maximum in the midgame.
+    pub king_danger: [f32; 2],
+    pub max_king_danger: f32,
+    /// The midgame bonus for the closest pawn of ours in front of the king on its file and the
+    /// adjacent ones, by distance: 0 when there are none, 3 when it is that far or further.
+    pub shelter: [f32; 4],
+    /// The midgame penalty for the closest enemy pawn storming towards the king on its file and
+    /// the adjacent ones, by distance: 0 when there are none or they are further than 4 ranks away.
+    pub storm: [f32; 5],
+    /// The bonus for having both bishops.
+    pub bishop_pair: [f32; 2],
+    /// The bonus for rooks on files without pawns, without pawns of ours, and on the seventh rank.
+    pub open_file: [f32; 2],
+    pub semi_open_file: [f32; 2],
+    pub seventh_rank: [f32; 2],
+    /// The bonus for a knight defended by a pawn, that no enemy pawn can chase away.
+    pub outpost: [f32; 2],
+    /// The bonus for the checks given in three-check, by number of checks.
+    pub checks: [f32; 3],
+    /// The bonus for the king in king of the hill, by distance to the hill.
+    pub hill: [f32; 4],
+}
+
+// ================================ pub impl
+
+impl EvalParams {
+    /// The built-in weights.
+    pub const DEFAULT: EvalParams = EvalParams {
+        material: [[0.9, 1.2], [4.8, 5.3], [3.2, 3.0], [3.3, 3.4], [9.0, 9.5]],
+        tables: [
+            [PAWNS_MG, PAWNS_EG], [ROOKS_MG, ROOKS_EG], [KNIGHTS_MG, KNIGHTS_EG],
+            [BISHOPS_MG, BISHOPS_EG], [QUEENS_MG, QUEENS_EG], [KINGS_MG, KINGS_EG],
+        ],
+        passed: [
+            [0.0, 0.0], [0.05, 0.1], [0.05, 0.15], [0.1, 0.25],
+            [0.2, 0.45], [0.35, 0.75], [0.6, 1.1], [0.0, 0.0],
+        ],
+        candidate: [
+            [0.0, 0.0], [0.02, 0.05], [0.02, 0.05], [0.05, 0.1],
+            [0.1, 0.2], [0.15, 0.3], [0.0, 0.0], [0.0, 0.0],
+        ],
+        connected: [
+            [0.0, 0.0], [0.05, 0.02], [0.07, 0.04], [0.1, 0.08],
+            [0.15, 0.12], [0.25, 0.2], [0.4, 0.3], [0.0, 0.0],
+        ],
+        isolated: [-0.1, -0.15],
+        doubled: [-0.1, -0.2],
+        backward: [-0.08, -0.1],
+        king_distance: [0.01, -0.005],
+        mobility: [[0.0, 0.0], [0.02, 0.04], [0.04, 0.04], [0.05, 0.05], [0.01, 0.02], [0.0, 0.0]],
+        attack_units: [0.0, 3.0, 2.0, 2.0, 5.0, 0.0],
+        king_danger: [0.01, 0.002],
+        max_king_danger: 5.0,
+        shelter: [-0.15, 0.1, 0.05, -0.05],
+        storm: [0.0, -0.05, -0.15, -0.1, -0.05],
+        bishop_pair: [0.3, 0.5],
+        open_file: [0.2, 0.1],
+        semi_open_file: [0.1, 0.05],
+        seventh_rank: [0.2, 0.3],
+        outpost: [0.25, 0.15],
+        checks: [0.0, 1.0, 3.0],
+        hill: [0.0, 1.0, 0.4, 0.1],
+    };
+
+    /// Loads weights from a json file. The missing fields keep their built-in value.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<EvalParams> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Saves the weights to a json file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Returns the weights as a slice, in the order of the fields.
+    pub fn as_slice(&self) -> &[f32] {
+        // SAFE: the struct is repr(C) and only made of f32, without any padding.
+        unsafe {std::slice::from_raw_parts(self as *const EvalParams as *const f32, EvalParams::LEN)}
+    }
+
+    /// Returns the weights as a mutable slice, in the order of the fields.
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        // SAFE: the struct is repr(C) and only made of f32, without any padding.
+        unsafe {std::slice::from_raw_parts_mut(self as *mut EvalParams as *mut f32, EvalParams::LEN)}
+    }
+
+    /// Formats the weights as the rust source of an EvalParams, to replace EvalParams::DEFAULT.
+    /// They are rounded to 4 decimals.
+    pub fn to_rust(&self) -> String {
+        let mut rounded = self.clone();
+        for weight in rounded.as_mut_slice() {
+            *weight = (*weight * 10000.0).round() / 10000.0;
+        }
+
+        let mut s = String::from("EvalParams {\n");
+
+        macro_rules! fields {
+            ($($field: ident),*) => {
+                $(writeln!(s, "    {}: {:?},", stringify!($field), rounded.$field).unwrap();)*
+            };
+        }
+        fields!(
+            material, tables, passed, candidate, connected, isolated, doubled, backward, king_distance,
+            mobility, attack_units, king_danger, max_king_danger, shelter, storm, bishop_pair,
+            open_file, semi_open_file, seventh_rank, outpost, checks, hill
+        );
+
+        s.push('}');
+        s
+    }
+}
+
+// ================================ impl
+
+impl EvalParams {
+    /// The number of weights.
+    const LEN: usize = std::mem::size_of::<EvalParams>() / std::mem::size_of::<f32>();
+
+    /// Counts the weights field by field, which only compiles if all of them are made of f32.
+    const fn count(&self) -> usize {
+        const fn len(weights: &[f32]) -> usize {
+            weights.len()
+        }
+
+        let EvalParams {
+            material, tables, passed, candidate, connected, isolated, doubled, backward, king_distance,
+            mobility, attack_units, king_danger, max_king_danger, shelter, storm, bishop_pair,
+            open_file, semi_open_file, seventh_rank, outpost, checks, hill,
+        } = self;
+
+        len(material.as_flattened()) + len(tables.as_flattened().as_flattened()) + len(passed.as_flattened())
+            + len(candidate.as_flattened()) + len(connected.as_flattened()) + len(isolated) + len(doubled)
+            + len(backward) + len(king_distance) + len(mobility.as_flattened()) + len(attack_units)
+            + len(king_danger) + len(std::slice::from_ref(max_king_danger)) + len(shelter) + len(storm)
+            + len(bishop_pair) + len(open_file) + len(semi_open_file) + len(seventh_rank) + len(outpost)
+            + len(checks) + len(hill)
+    }
+}
+
+// The slices of EvalParams::as_slice() cover the whole struct: it is only made of f32, without padding.
+const _: () = assert!(
+    EvalParams::DEFAULT.count() == EvalParams::LEN
+        && EvalParams::LEN * std::mem::size_of::<f32>() == std::mem::size_of::<EvalParams>()
+        && std::mem::align_of::<EvalParams>() == std::mem::align_of::<f32>()
+);
+
+// ================================ traits impl
+
+impl Default for EvalParams {
+    /// Returns the built-in weights.
+    fn default() -> EvalParams {
+        EvalParams::DEFAULT
+    }
+}
+
+/// Serializes the piece-square tables as nested sequences, as serde only implements arrays up to 32.
+fn serialize_tables<S: Serializer>(tables: &[[[f32; 64]; 2]; 6], serializer: S) -> Result<S::Ok, S::Error> {
+    tables.iter().map(|[mg, eg]| [&mg[..], &eg[..]]).collect::<Vec<_>>().serialize(serializer)
+}
+
+/// Deserializes the piece-square tables from nested sequences.
+fn deserialize_tables<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[[[f32; 64]; 2]; 6], D::Error> {
+    let tables = Vec::<[Vec<f32>; 2]>::deserialize(deserializer)?;
+    if tables.len() != 6 {
+        return Err(D::Error::custom("expected 6 piece-square tables"));
+    }
+
+    let mut res = [[[0.0; 64]; 2]; 6];
+    for (res, table) in res.iter_mut().flatten().zip(tables.iter().flatten()) {
+        *res = table.as_slice().try_into().map_err(|_| D::Error::custom("expected 64 squares in piece-square tables"))?;
+    }
+
+    Ok(res)
+}
@@ -0,0 +1,20 @@
+use chess::board::Board;
+use chess::variant::Variant;
+use engine::Engine;
+
+/// The lowest score of a won position.
+const MATE_BOUND: f32 = 100.0;
+
+#[test]
+fn forced_capture() {
+    // After Nf3, black must take the knight, which leaves white without pieces. The capture
+    // is only seen by the quiescent search if black is not allowed to stand pat instead.
+    let board = Board::new_variant(Variant::Antichess, "k7/8/8/8/4p3/8/8/6N1 w - - 0 1").unwrap();
+    let engine = Engine::new(board.clone());
+
+    engine.search_fixed_depth(1);
+
+    let line = &engine.get_lines()[0];
+    assert_eq!(line.get_move(), board.parse_move("g1f3").unwrap());
+    assert!(line.get_score() > MATE_BOUND, "Nf3 scored {}.", line.get_score());
+}
@@ -0,0 +1,47 @@
+use chess::board::Board;
+use chess::piece::Piece;
+use engine::Engine;
+
+/// The depth of the searches.
+const DEPTH: u8 = 6;
+
+/// The amplitude of the jitter of draw scores.
+const DRAW_JITTER: f32 = 0.01;
+
+#[test]
+fn contempt() {
+    // Drawn rook pawn endgames, where any king move draws by the 50 move rule
+    // and a pawn move keeps the game going.
+    let fens = [
+        "7k/8/8/8/8/8/7P/7K w - - 99 80",
+        "7k/7p/8/8/8/8/8/7K b - - 99 80",
+    ];
+
+    for fen in fens {
+        let board = Board::new(fen).unwrap();
+        let engine = Engine::new(board.clone());
+
+        for contempt in [0.5, -0.5] {
+            engine.set_contempt(contempt);
+            assert_eq!(engine.get_contempt(), contempt);
+            engine.search_fixed_depth(DEPTH);
+
+            // The draws are worth the opposite of the contempt to the engine.
+            let root_moves = engine.get_root_moves();
+            let draws = root_moves.iter().filter(|root| board.get_piece_unchecked(root.get_move().from()) == Piece::King);
+            for root in draws {
+                assert!(
+                    (root.get_score() + contempt).abs() <= DRAW_JITTER,
+                    "With a contempt of {}, {} scored {} from {}.", contempt, root.get_move(), root.get_score(), fen,
+                );
+            }
+
+            // A positive contempt avoids them, a negative one seeks them.
+            let mv = engine.get_best_move().unwrap();
+            assert_eq!(
+                board.get_piece_unchecked(mv.from()) == Piece::Pawn, contempt > 0.0,
+                "With a contempt of {}, {} was played from {}.", contempt, mv, fen,
+            );
+        }
+    }
+}
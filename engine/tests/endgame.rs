@@ -0,0 +1,93 @@
+use chess::board::{Board, Status};
+use chess::color::Color;
+use engine::Engine;
+
+/// The depth of the searches of the playouts.
+const PLAYOUT_DEPTH: u8 = 5;
+
+#[test]
+fn kpk() {
+    let positions = [
+        // The king in front of the pawn on the sixth rank always wins.
+        ("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1", true),
+        ("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1", true),
+        // Stalemate, or the king must step aside.
+        ("4k3/4P3/4K3/8/8/8/8/8 b - - 0 1", false),
+        ("4k3/4P3/4K3/8/8/8/8/8 w - - 0 1", true),
+        ("8/8/8/8/8/4k3/4P3/4K3 w - - 0 1", false),
+        // A rook pawn cannot be won with the king in the corner.
+        ("7k/8/8/8/7P/8/6K1/8 w - - 0 1", false),
+        // The same positions for black.
+        ("8/8/8/8/8/4k3/4p3/4K3 w - - 0 1", false),
+        ("8/8/8/8/8/4k3/4p3/4K3 b - - 0 1", true),
+    ];
+
+    for (fen, won) in positions {
+        let board = Board::new(fen).unwrap();
+        let score = engine::evaluate(&board);
+        let score = if board.get_side_to_move() == Color::White {score} else {-score};
+        let strong = if fen.contains('P') {1.0} else {-1.0};
+
+        if won {
+            assert!(strong * score > 5.0, "{} should be won, scored {}.", fen, score);
+        } else {
+            assert!(score.abs() < 0.5, "{} should be drawn, scored {}.", fen, score);
+        }
+    }
+}
+
+#[test]
+fn scale() {
+    let board = Board::new("8/5k2/4b3/1p3p2/1P3P2/4K3/3B4/8 w - - 0 1").unwrap();
+    assert_eq!(Engine::new(board.clone()).eval_trace(&board).get_scale(), 0.5);
+
+    let board = Board::new("8/5k2/4n3/8/8/4K3/3B4/8 w - - 0 1").unwrap();
+    assert_eq!(Engine::new(board.clone()).eval_trace(&board).get_scale(), 0.0);
+}
+
+#[test]
+fn kbnk() {
+    // The weak king in the corner of the bishop's color, in the other corner, and in the center.
+    let positions = [
+        ["7k/8/8/8/4K3/4N3/8/2B5 w - - 0 1", "k7/8/8/8/4K3/4N3/8/2B5 w - - 0 1", "8/8/8/3k4/8/8/8/2B1KN2 w - - 0 1"],
+        ["2b5/8/4n3/4k3/8/8/8/7K b - - 0 1", "2b5/8/4n3/4k3/8/8/8/K7 b - - 0 1", "2bk1n2/8/8/8/4K3/8/8/8 b - - 0 1"],
+    ];
+
+    for fens in positions {
+        let [mating, other, center] = fens.map(|fen| engine::evaluate(&Board::new(fen).unwrap()));
+
+        assert!(center > 10.0, "{} should be a known win, scored {}.", fens[2], center);
+        assert!(mating > other && other > center, "The king should be driven to the right corner, scored {:?}.", [mating, other, center]);
+    }
+}
+
+#[test]
+fn kbnk_playout() {
+    // The weak king in the center, and in the wrong corner, with bishops of both colors.
+    let positions = [
+        ("8/8/8/3k4/8/8/8/2B1KN2 w - - 0 1", Color::White),
+        ("k7/8/8/8/4K3/4N3/8/2B5 w - - 0 1", Color::White),
+        ("8/8/4k3/8/8/8/8/B3K1N1 w - - 0 1", Color::White),
+        ("7k/8/8/8/8/8/8/KBN5 w - - 0 1", Color::White),
+        ("8/8/8/3k4/8/8/1N6/K1B5 w - - 0 1", Color::White),
+        ("2bk1n2/8/8/8/4K3/8/8/8 b - - 0 1", Color::Black),
+        ("2b5/8/4n3/4k3/8/8/8/K7 b - - 0 1", Color::Black),
+        ("8/8/8/8/3K4/8/8/kbn5 b - - 0 1", Color::Black),
+    ];
+
+    // The engine plays both sides, and must mate before the 50 move rule.
+    for (fen, strong) in positions {
+        let mut board = Board::new(fen).unwrap();
+        let engine = Engine::new(board.clone());
+
+        while board.status().is_playing() {
+            assert!(board.get_halfmove() < 100, "No mate from {} after 50 moves, at:\n{}", fen, board);
+
+            engine.search_fixed_depth(PLAYOUT_DEPTH);
+            board.do_move(engine.get_best_move().unwrap());
+            *engine.write_board() = board.clone();
+        }
+
+        assert!(matches!(board.status(), Status::Win(winner) if winner == strong), "{} ended with:\n{}", fen, board);
+    }
+}
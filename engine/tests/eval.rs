@@ -0,0 +1,107 @@
+use chess::board::Board;
+use chess::color::Color;
+use chess::variant::Variant;
+use engine::{Engine, EvalTerms, Term};
+
+/// Returns the fen of the position with the colors flipped: the board is mirrored
+/// vertically, the pieces change color and the other side is to move.
+fn flip(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split(' ').collect();
+    let swap_case = |s: &str| s.chars().map(|c| {
+        if c.is_ascii_uppercase() {c.to_ascii_lowercase()} else {c.to_ascii_uppercase()}
+    }).collect::<String>();
+
+    let board = fields[0].split('/').rev().map(swap_case).collect::<Vec<_>>().join("/");
+    let side = if fields[1] == "w" {"b"} else {"w"};
+    let mut castles: Vec<char> = swap_case(fields[2]).chars().collect();
+    castles.sort_unstable();
+    let castles: String = castles.into_iter().collect();
+    let ep = match fields[3].as_bytes() {
+        [file, rank] => format!("{}{}", *file as char, (b'1' + b'8' - rank) as char),
+        _ => "-".to_string(),
+    };
+
+    format!("{} {} {} {} {} {}", board, side, castles, ep, fields[4], fields[5])
+}
+
+#[test]
+fn eval_symmetry() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "r1bqk2r/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R2QKB1R w KQkq - 2 8",
+        "8/5k2/1p4p1/pP1p3p/P2P3P/6P1/5K2/8 w - - 0 40",
+        "8/2P5/8/3k4/8/1p6/4K3/8 b - - 0 60",
+        "r3k2r/1pp2ppp/p1n5/3Pp3/2P1P1b1/2N5/PP3PPP/R3KB1R w KQkq e6 0 12",
+        "6k1/5ppp/8/2p5/2P5/1P6/P4PPP/6K1 w - - 0 30",
+    ];
+
+    for fen in fens {
+        let board = Board::new(fen).unwrap();
+        let flipped = Board::new(&flip(fen)).unwrap();
+
+        // The scores are relative to the side to move: the scores from white's point of
+        // view of the two positions are opposite, so these ones are equal.
+        let (score, flipped_score) = (engine::evaluate(&board), engine::evaluate(&flipped));
+        assert!((score - flipped_score).abs() < 1e-4, "Asymmetric evaluation of {}: {} and {}.", fen, score, flipped_score);
+    }
+}
+
+#[test]
+fn eval_trace() {
+    let fens = [
+        (Variant::Standard, "r1bqk2r/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R2QKB1R w KQkq - 2 8"),
+        (Variant::Standard, "8/5k2/1p4p1/pP1p3p/P2P3P/6P1/5K2/8 b - - 0 40"),
+        (Variant::KingOfTheHill, "r1bq1rk1/ppp2ppp/2n5/3pP3/3Kn3/2P5/PP3PPP/RNBQ1BNR w - - 0 9"),
+        (Variant::ThreeCheck, "rnbqkbnr/ppp2ppp/8/3pp3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2+1 0 3"),
+        (Variant::Crazyhouse, "r1bqkb1r/pppp1ppp/2n2n2/4p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R[Pp] w KQkq - 0 4"),
+        (Variant::Antichess, "rnbqkbnr/ppp1pppp/8/8/8/8/PPPPP1PP/RNBQKBNR w - - 0 3"),
+    ];
+
+    for (variant, fen) in fens {
+        let board = Board::new_variant(variant, fen).unwrap();
+        let engine = Engine::new(board.clone());
+        let trace = engine.eval_trace(&board);
+        let phase = trace.get_phase();
+
+        // The score of the trace is the evaluation from white's point of view, and the
+        // sum of the interpolated contributions of the terms.
+        let score = engine::evaluate(&board);
+        let score = if board.get_side_to_move() == Color::White {score} else {-score};
+        assert!((trace.get_score() - score).abs() < 1e-4, "Trace of {} scores {} instead of {}.", fen, trace.get_score(), score);
+
+        let taper = |[mg, eg]: [f32; 2]| mg * phase + eg * (1.0 - phase);
+        let sum: f32 = trace.get_terms().iter().map(|term| taper(term.white) - taper(term.black)).sum();
+        assert!((trace.get_score() - sum).abs() < 1e-3, "Terms of {} sum to {} instead of {}.", fen, sum, trace.get_score());
+        assert_eq!(trace.get_terms().iter().map(|term| term.term).collect::<Vec<_>>(), Term::TERMS);
+    }
+}
+
+#[test]
+fn eval_terms() {
+    let board = Board::new("r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP2BPPP/R2QKB1R w KQ - 0 8").unwrap();
+    let engine = Engine::new(board.clone());
+    let optional = [Term::Mobility, Term::KingSafety, Term::Pieces];
+    let contribution = |engine: &Engine, term: Term| {
+        let trace = engine.eval_trace(&board);
+        trace.get_terms().iter().find(|trace| trace.term == term).map(|trace| [trace.white, trace.black]).unwrap()
+    };
+
+    assert_eq!(engine.get_eval_terms(), EvalTerms::default());
+    for term in optional {
+        assert_ne!(contribution(&engine, term), [[0.0; 2]; 2], "{:?} is missing.", term);
+    }
+
+    // Switching the terms off removes their contributions, and only theirs.
+    let material = contribution(&engine, Term::Material);
+    engine.set_eval_terms(EvalTerms::NONE);
+    assert_eq!(engine.get_eval_terms(), EvalTerms::NONE);
+    for term in optional {
+        assert_eq!(contribution(&engine, term), [[0.0; 2]; 2], "{:?} is still evaluated.", term);
+    }
+    assert_eq!(contribution(&engine, Term::Material), material);
+
+    // Mobility alone only brings the mobility back.
+    engine.set_eval_terms(EvalTerms {mobility: true, ..EvalTerms::NONE});
+    assert_ne!(contribution(&engine, Term::Mobility), [[0.0; 2]; 2]);
+    assert_eq!(contribution(&engine, Term::KingSafety), [[0.0; 2]; 2]);
+}
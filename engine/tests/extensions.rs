@@ -0,0 +1,96 @@
+use std::thread;
+use std::time::Duration;
+
+use chess::board::Board;
+use engine::{Engine, Extensions};
+
+/// The time the engine thinks for each position.
+const THINK_TIME: Duration = Duration::from_millis(500);
+
+/// The deepest fixed-depth search checked against the budget of extensions.
+const MAX_DEPTH: u8 = 6;
+
+#[test]
+fn tactics() {
+    // Forcing lines that extensions must not lose in the growth of the tree.
+    let positions = [
+        // A smothered mate, by a double check and a sacrifice.
+        ("5rk1/5Npp/8/3Q4/8/8/8/6K1 w - - 0 1", "f7h6"),
+        // A queen sacrifice opening the file for a back rank mate.
+        ("r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 1 1", "d5d8"),
+        // A pawn breakthrough, queening a passed pawn.
+        ("7k/ppp5/8/PPP5/8/8/8/7K w - - 0 1", "b5b6"),
+    ];
+
+    for (fen, expected) in positions {
+        let board = Board::new(fen).unwrap();
+        let engine = Engine::new(board.clone());
+
+        engine.start();
+        thread::sleep(THINK_TIME);
+        engine.stop();
+
+        let mv = engine.get_best_move().unwrap();
+        assert_eq!(mv, board.parse_move(expected).unwrap(), "Expected {} in {}, got {}.", expected, fen, mv);
+    }
+}
+
+#[test]
+fn extended_lines() {
+    let only_recapture = Extensions {recapture: true, ..Extensions::NONE};
+
+    // Positions searched to a fixed depth, where the extensions find the best move and their
+    // absence does not.
+    let positions = [
+        // Nxd7 wins material, which is only seen with the recaptures on d7 extended.
+        ("2rqkb1r/p2b1p2/4p2p/4N1p1/4P3/1Q4B1/PP3PPP/R3K2R w KQk - 2 21", 4, only_recapture, "e5d7"),
+        // The smothered mate, whose checks run past the depth.
+        ("5rk1/5Npp/8/3Q4/8/8/8/6K1 w - - 0 1", 6, Extensions::default(), "f7h6"),
+    ];
+
+    for (fen, depth, extensions, expected) in positions {
+        let board = Board::new(fen).unwrap();
+        let engine = Engine::new(board.clone());
+        let expected = board.parse_move(expected).unwrap();
+
+        engine.set_extensions(extensions);
+        assert_eq!(engine.get_extensions(), extensions);
+        engine.search_fixed_depth(depth);
+        let mv = engine.get_best_move().unwrap();
+        assert_eq!(mv, expected, "With {:?}, expected {} in {}, got {}.", extensions, expected, fen, mv);
+
+        engine.set_extensions(Extensions::NONE);
+        engine.search_fixed_depth(depth);
+        let mv = engine.get_best_move().unwrap();
+        assert_ne!(mv, expected, "Without extensions, {} was found in {}.", mv, fen);
+    }
+}
+
+#[test]
+fn extension_budget() {
+    // Checks on every move, so that the paths are extended as much as they may be.
+    let fens = [
+        "5rk1/5Npp/8/3Q4/8/8/8/6K1 w - - 0 1",
+        "r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 1 1",
+    ];
+
+    for fen in fens {
+        let engine = Engine::new(Board::new(fen).unwrap());
+
+        for depth in 1..=MAX_DEPTH {
+            // A path is extended while its extensions are below half the depth.
+            engine.set_extensions(Extensions::default());
+            engine.search_fixed_depth(depth);
+            let seldepth = engine.get_lines()[0].get_seldepth();
+            assert!(
+                depth <= seldepth && seldepth <= depth + depth.div_ceil(2),
+                "At depth {}, the search of {} reached ply {}.", depth, fen, seldepth,
+            );
+
+            // Without extensions, every path stops at the depth.
+            engine.set_extensions(Extensions::NONE);
+            engine.search_fixed_depth(depth);
+            assert_eq!(engine.get_lines()[0].get_seldepth(), depth, "At depth {} in {}.", depth, fen);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use chess::board::Board;
+use engine::{Bound, Engine};
+
+/// The depth of the searches.
+const DEPTH: u8 = 5;
+
+/// Searches the position with that number of lines, and checks the lines found.
+fn search_lines(fen: &str, multi_pv: usize, expected: usize) {
+    let board = Board::new(fen).unwrap();
+    let engine = Engine::new(board.clone());
+    engine.set_multi_pv(multi_pv);
+
+    engine.search_fixed_depth(DEPTH);
+
+    let lines = engine.get_lines();
+    assert_eq!(lines.len(), expected, "Wrong number of lines for {}.", fen);
+    assert_eq!(Some(lines[0].get_move()), engine.get_best_move());
+
+    for (i, line) in lines.iter().enumerate() {
+        // Distinct root moves, the best first.
+        assert!(lines[..i].iter().all(|other| other.get_move() != line.get_move()));
+        assert!(i == 0 || lines[i - 1].get_score() >= line.get_score(), "Lines are not sorted for {}.", fen);
+        assert_eq!(line.get_depth(), DEPTH);
+
+        // Each line is searched again until its score is exact.
+        let root = engine.get_root_moves().into_iter().find(|root| root.get_move() == line.get_move()).unwrap();
+        assert_eq!((root.get_score(), root.get_bound()), (line.get_score(), Bound::Exact), "Inexact score for {}.", fen);
+
+        // The principal variation starts with the root move and is playable.
+        assert_eq!(line.get_pv()[0], line.get_move());
+        let mut board = board.clone();
+        for &mv in line.get_pv() {
+            assert!(board.is_pseudo_legal(mv) && board.is_legal(mv), "Illegal move {} in the pv of {}.", mv, fen);
+            board.do_move(mv);
+        }
+    }
+}
+
+#[test]
+fn multi_pv() {
+    search_lines("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 3, 3);
+    search_lines("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3", 1, 1);
+
+    // Fewer root moves than lines.
+    search_lines("k7/8/8/8/8/8/8/1R5K b - - 0 1", 5, 1);
+    search_lines("7k/8/8/8/8/8/8/K7 w - - 0 1", 5, 3);
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use chess::board::MoveHook;
+use chess::piece::Piece;
+use chess::prelude::*;
+use chess::square::Square;
+use engine::{Accumulator, Network};
+
+/// The small network shipped for the tests, with random weights.
+const TEST_NETWORK: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/test.nnue");
+
+/// Walks the tree of legal moves, checking at each node that the incrementally
+/// updated accumulator matches one computed from scratch.
+fn check_tree(board: &mut Board, acc: &mut Accumulator, depth: usize) {
+    let mut fresh = acc.clone();
+    fresh.refresh(board);
+    assert_eq!(acc.get_values(), fresh.get_values(), "Accumulator mismatch at {}.", board);
+    assert_eq!(acc.evaluate(board), fresh.evaluate(board));
+
+    if depth == 0 {
+        return;
+    }
+
+    let mut moves = Vec::new();
+    movegen::legals(board, &mut moves);
+    for mv in moves {
+        board.do_move_with(mv, acc);
+        check_tree(board, acc, depth - 1);
+        board.undo_move_with(mv, acc);
+    }
+}
+
+#[test]
+fn nnue_incremental() {
+    let network = Arc::new(Network::load(TEST_NETWORK).unwrap());
+    assert_eq!(network.get_hidden(), 24);
+    assert!(Network::from_bytes(b"RNN1\x01\x00\x00\x00").is_err());
+
+    // Castling, en passant, promotions and captures.
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbqkbnr/pp1ppppp/8/2pP4/8/8/PPP1PPPP/RNBQKBNR w KQkq c6 0 2",
+    ];
+
+    for fen in fens {
+        let mut board = Board::new(fen).unwrap();
+        let mut acc = Accumulator::new(network.clone(), &board);
+        check_tree(&mut board, &mut acc, 2);
+    }
+}
+
+#[test]
+fn nnue_simd() {
+    // Without avx2, both networks are scalar and the test is trivial.
+    let simd = Arc::new(Network::load(TEST_NETWORK).unwrap());
+    let mut scalar = (*simd).clone();
+    scalar.set_simd(false);
+    assert!(!scalar.has_simd());
+    let scalar = Arc::new(scalar);
+
+    // A xorshift seed, for the features.
+    let mut seed: u64 = 0x2545F4914F6CDD1D;
+    let mut next = |n: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % n as u64) as usize
+    };
+
+    let board = Board::default();
+    for _ in 0..100 {
+        let mut accs = [Accumulator::new(simd.clone(), &board), Accumulator::new(scalar.clone(), &board)];
+
+        // Random features added on top of the board, half of them removed afterwards.
+        let features = (0..32).map(|_| {
+            (Color::COLORS[next(2)], Piece::PIECES[next(6)], Square::SQUARES[next(64)])
+        }).collect::<Vec<_>>();
+
+        for acc in &mut accs {
+            for &(color, piece, sq) in &features {
+                acc.place(color, piece, sq);
+            }
+            for &(color, piece, sq) in features.iter().step_by(2) {
+                acc.remove(color, piece, sq);
+            }
+        }
+
+        assert_eq!(accs[0].get_values(), accs[1].get_values(), "Accumulator mismatch for {:?}.", features);
+        assert_eq!(accs[0].evaluate(&board).to_bits(), accs[1].evaluate(&board).to_bits(), "Output mismatch for {:?}.", features);
+    }
+}
@@ -0,0 +1,59 @@
+use chess::board::Board;
+use engine::{Engine, Pruning};
+
+/// The depth of the searches.
+const DEPTH: u8 = 6;
+
+/// The lowest score of a mate.
+const MATE_BOUND: f32 = 100.0;
+
+#[test]
+fn tactics() {
+    // Quiet moves and checks that pruning at shallow depths must not miss.
+    let positions = [
+        // A quiet mate.
+        ("k7/8/1K6/8/8/8/8/7R w - - 0 1", "h1h8"),
+        // A back rank mate.
+        ("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", "d1d8"),
+        // A mating attack starting with a check.
+        ("5k2/6pp/p1qN4/1p1p4/3P4/2PKP2Q/PP3r2/3R4 b - - 0 1", "c6c4"),
+        // A knight fork with check.
+        ("2k5/8/6q1/3N4/8/8/8/4K3 w - - 0 1", "d5e7"),
+        // A quiet rook move, winning the pawn and the game.
+        ("7k/p7/1R5K/6r1/6p1/6P1/8/8 w - - 0 1", "b6b7"),
+        // A sacrifice opening the king.
+        ("2br2k1/2q3rn/p2NppQ1/2p1P3/Pp5R/4P3/1P3PPP/3R2K1 w - - 0 1", "h4h7"),
+    ];
+
+    let mut total = [0, 0];
+
+    for (fen, expected) in positions {
+        let board = Board::new(fen).unwrap();
+        let engine = Engine::new(board.clone());
+        assert_eq!(engine.get_pruning(), Pruning::default());
+
+        // The same move is found with and without pruning.
+        let mut nodes = [0, 0];
+        let mut score = 0.0;
+        for (i, &pruning) in [Pruning::default(), Pruning::NONE].iter().enumerate() {
+            engine.set_pruning(pruning);
+            let before = engine.get_nodes();
+            engine.search_fixed_depth(DEPTH);
+
+            let mv = engine.get_best_move().unwrap();
+            assert_eq!(mv, board.parse_move(expected).unwrap(), "Expected {} in {} with {:?}, got {}.", expected, fen, pruning, mv);
+            nodes[i] = engine.get_nodes() - before;
+            score = engine.get_lines()[0].get_score();
+        }
+
+        // Pruning is off close to mates, elsewhere it saves nodes.
+        if score.abs() < MATE_BOUND {
+            assert!(nodes[0] < nodes[1], "Pruning searched {} nodes instead of {} in {}.", nodes[0], nodes[1], fen);
+        }
+
+        total[0] += nodes[0];
+        total[1] += nodes[1];
+    }
+
+    assert!(total[0] < total[1], "Pruning searched {} nodes instead of {}.", total[0], total[1]);
+}
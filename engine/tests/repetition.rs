@@ -0,0 +1,46 @@
+use std::thread;
+use std::time::Duration;
+
+use chess::board::Board;
+use engine::Engine;
+
+/// The time the engine thinks for each position.
+const THINK_TIME: Duration = Duration::from_millis(500);
+
+#[test]
+fn perpetual_check() {
+    // White is lost, but after a first round of checks, checking again repeats the
+    // position, which is better than winning the knight on e2.
+    let mut board = Board::new("6k1/6p1/pppp4/7Q/8/1r6/b3n1PP/nn5K w - - 0 40").unwrap();
+    for s in ["h5e8", "g8h7", "e8h5", "h7g8"] {
+        let mv = board.parse_move(s).unwrap();
+        board.do_move(mv);
+    }
+
+    let engine = Engine::new(board.clone());
+    engine.start();
+    thread::sleep(THINK_TIME);
+    engine.stop();
+
+    let mv = engine.get_best_move().unwrap();
+    assert_eq!(mv, board.parse_move("h5e8").unwrap(), "Expected the perpetual check, got {}.", mv);
+}
+
+#[test]
+fn search_depth_bound() {
+    // Every move draws by the 50-move rule, and the iterative deepening goes on at no cost.
+    let board = Board::new("k7/8/8/8/8/8/8/K7 w - - 99 80").unwrap();
+    let engine = Engine::new(board);
+    engine.start();
+    thread::sleep(THINK_TIME);
+
+    // The search threads of an overflowing depth are dead, the engine cannot be stopped.
+    let depth = engine.get_current_depth();
+    if depth > 32 {
+        std::mem::forget(engine);
+        panic!("The search depth went up to {}.", depth);
+    }
+
+    engine.stop();
+    assert!(engine.get_best_move().is_some());
+}
@@ -0,0 +1,45 @@
+use chess::board::Board;
+use engine::{Bound, Engine};
+
+/// The depth of the searches.
+const DEPTH: u8 = 5;
+
+#[test]
+fn root_moves() {
+    let board = Board::new("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3").unwrap();
+    let engine = Engine::new(board.clone());
+
+    // Every legal move is scored, the best first.
+    engine.search_fixed_depth(DEPTH);
+    let root_moves = engine.get_root_moves();
+    assert_eq!(root_moves.len(), 27);
+    assert_eq!(Some(root_moves[0].get_move()), engine.get_best_move());
+    assert!(root_moves.windows(2).all(|pair| pair[0].get_score() >= pair[1].get_score()));
+    assert!(root_moves.iter().all(|root| root.get_nodes() > 0));
+
+    // The best move is exact, the others failed low at most, and all were searched.
+    assert_eq!(root_moves[0].get_bound(), Bound::Exact);
+    assert!(root_moves[1..].iter().all(|root| root.get_bound() != Bound::Lower));
+    assert!(root_moves.iter().all(|root| !root.is_stale()));
+
+    // The same search finds the same results.
+    engine.search_fixed_depth(DEPTH);
+    assert_eq!(engine.get_root_moves(), root_moves);
+
+    // Restricted to a few moves, even if they are bad.
+    let moves = ["a2a3", "h2h4", "f3g1"].map(|mv| board.parse_move(mv).unwrap());
+    engine.set_search_moves(&moves);
+    engine.search_fixed_depth(DEPTH);
+    assert!(moves.contains(&engine.get_best_move().unwrap()));
+    let root_moves = engine.get_root_moves();
+    assert_eq!(root_moves.len(), 3);
+    assert!(moves.iter().all(|&mv| root_moves.iter().any(|root| root.get_move() == mv)));
+
+    // The restriction is lifted with a new board, or ignored if no move is legal.
+    *engine.write_board() = board.clone();
+    assert!(engine.get_search_moves().is_empty());
+    let other = Board::new("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+    engine.set_search_moves(&[other.parse_move("e2e4").unwrap()]);
+    engine.search_fixed_depth(DEPTH);
+    assert_eq!(engine.get_root_moves().len(), 27);
+}
@@ -0,0 +1,61 @@
+use std::process::Command;
+use std::{env, fs};
+
+use engine::EvalParams;
+
+/// Positions labeled with the results of their games, which the material does not always decide.
+const POSITIONS: &str = "\
+4k3/8/8/8/8/8/8/3QK3 w - - 0 1 [1.0]
+4k3/8/8/8/8/8/8/2Q1K3 w - - 0 1 [1.0]
+3qk3/8/8/8/8/8/8/4K3 w - - 0 1 [0.0]
+4k3/8/8/8/8/8/8/R3K3 b - - 0 1 1-0
+r3k3/8/8/8/8/8/8/4K3 b - - 0 1 1/2-1/2
+4k3/8/8/8/8/8/PP6/4K3 w - - 0 1 1/2-1/2
+4k3/8/8/8/8/8/5PP1/4K3 w - - 0 1 1-0
+4k3/pp6/8/8/8/8/8/4K3 b - - 0 1 1/2-1/2
+4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1 1/2-1/2
+";
+
+/// Returns the errors printed by the tuner, before and after each pass.
+fn errors(stdout: &str) -> Vec<f64> {
+    stdout.lines()
+        .filter_map(|line| line.strip_prefix("Initial error: ").or_else(|| line.split_once(": error ").map(|(_, rest)| rest)))
+        .map(|rest| rest.split([',', ' ']).next().unwrap().trim_end_matches('.').parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn toy_tuning() {
+    let dir = env::temp_dir();
+    let id = std::process::id();
+    let (positions, start, output) = (
+        dir.join(format!("rush-tune-{}.txt", id)),
+        dir.join(format!("rush-tune-start-{}.json", id)),
+        dir.join(format!("rush-tune-output-{}.json", id)),
+    );
+
+    // Starts from weights where all pieces are worth a pawn.
+    fs::write(&positions, POSITIONS).unwrap();
+    fs::write(&start, r#"{"material": [[1.0, 1.0], [1.0, 1.0], [1.0, 1.0], [1.0, 1.0], [1.0, 1.0]]}"#).unwrap();
+
+    let out = Command::new(env!("CARGO_BIN_EXE_tune"))
+        .arg(&positions)
+        .args(["--weights", start.to_str().unwrap(), "--output", output.to_str().unwrap(), "--passes", "2"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "The tuner failed: {}", String::from_utf8_lossy(&out.stderr));
+
+    // The error decreases with each pass.
+    let errors = errors(&stdout);
+    assert_eq!(errors.len(), 3, "Unexpected output: {}", stdout);
+    assert!(errors.windows(2).all(|pair| pair[1] < pair[0]), "The error did not decrease: {:?}", errors);
+
+    // The tuned weights are saved.
+    let tuned = EvalParams::load(&output).unwrap();
+    assert_ne!(tuned, EvalParams::load(&start).unwrap());
+
+    for path in [positions, start, output] {
+        fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,49 @@
+use std::{env, fs};
+
+use engine::EvalParams;
+
+#[test]
+fn weights_files() {
+    let path = env::temp_dir().join(format!("rush-weights-{}.json", std::process::id()));
+
+    // Saved weights are loaded back identical.
+    let mut weights = EvalParams::default();
+    weights.tables[2][1][27] = 0.42;
+    weights.bishop_pair = [0.5, 0.7];
+    weights.save(&path).unwrap();
+    assert_eq!(EvalParams::load(&path).unwrap(), weights);
+
+    // Missing fields keep their built-in value.
+    fs::write(&path, r#"{"bishop_pair": [0.5, 0.7]}"#).unwrap();
+    let loaded = EvalParams::load(&path).unwrap();
+    assert_eq!(loaded.bishop_pair, [0.5, 0.7]);
+    assert_eq!(loaded.tables, EvalParams::DEFAULT.tables);
+
+    // Tables must be complete.
+    fs::write(&path, r#"{"tables": [[[0.0], [0.0]]]}"#).unwrap();
+    assert!(EvalParams::load(&path).is_err());
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn weights_round_trip() {
+    let path = env::temp_dir().join(format!("rush-weights-round-trip-{}.json", std::process::id()));
+
+    // Every weight is set to a random value, which must be read back exactly.
+    let mut seed: u64 = 0x2545F4914F6CDD1D;
+    let mut weights = EvalParams::default();
+    for weight in weights.as_mut_slice() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        *weight = (seed >> 40) as f32 / 1e6 - 8.0;
+    }
+
+    weights.save(&path).unwrap();
+    let loaded = EvalParams::load(&path).unwrap();
+    let bits = |weights: &EvalParams| weights.as_slice().iter().map(|w| w.to_bits()).collect::<Vec<_>>();
+    assert_eq!(bits(&loaded), bits(&weights));
+
+    fs::remove_file(&path).ok();
+}
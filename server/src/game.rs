@@ -2,101 +2,17 @@ use std::time::Duration;
 
 use anyhow::{Error, Result};
 use engine::Engine;
-use serde_json::Value;
 use tokio::sync::mpsc::{self, UnboundedSender};
 use warp::ws::Message;
 
+use chess::game::Game as Record;
 use chess::prelude::*;
 
-use crate::messages::{Command, Response};
+use crate::messages::{Command, EvalState, GameState, Response};
 
 /// The fen used for the default position.
 const DEFAULT_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
-//#################################################################################################
-//
-//                                       struct History
-//
-//#################################################################################################
-
-/// A struct keeping a history of played and/or undoed moves, as well
-/// as their textual representations.
-#[derive(Debug)]
-struct History {
-    moves: Vec<Move>,
-    strings: Vec<String>,
-    cursor: usize,
-}
-
-// ================================ impl
-
-impl History {
-    /// Creates a new empty move history.
-    fn new() -> Self {
-        Self {
-            moves: Vec::new(),
-            strings: Vec::new(),
-            cursor: 0,
-        }
-    }
-
-    /// Pushes a new move to the history, losing all undoed moves.
-    fn push(&mut self, mv: Move) {
-        // If we are not at the end of the timeline.
-        if self.cursor != self.moves.len() {
-            // Turns out the move has already been done in the past future, redo it.
-            if mv == self.moves[self.cursor] {
-                self.redo().ok();
-                return;
-            }
-
-            // Throw out all future moves, we are changing timeline.
-            self.moves.truncate(self.cursor);
-            self.strings.truncate(self.cursor);
-        }
-
-        // Push a new move.
-        self.moves.push(mv);
-        self.strings.push(mv.to_string());
-        self.cursor += 1;
-    }
-
-    /// Undo a move.
-    fn undo(&mut self) -> Result<Move> {
-        // Check there is something to undo.
-        if self.cursor == 0 {
-            return Err(Error::msg("There is no move to undo"));
-        }
-
-        // Decrement the cursor and return that move.
-        self.cursor -= 1;
-        Ok(self.moves[self.cursor])
-    }
-
-    /// Redo a move.
-    fn redo(&mut self) -> Result<Move> {
-        // Check that we are not at the end of the timeline.
-        if self.cursor == self.moves.len() {
-            return Err(Error::msg("There is no move to redo"));
-        }
-
-        // Get the move to redo and increment the cursor.
-        let mv = self.moves[self.cursor];
-        self.cursor += 1;
-        Ok(mv)
-    }
-}
-
-// ================================ traits impl
-
-impl From<&History> for Value {
-    /// Converts the history into it's json representation: an array of the 
-    /// moves currently played.
-    fn from(history: &History) -> Self {
-        Self::from(&history.strings[..history.cursor])
-    }
-}
-
 //#################################################################################################
 //
 //                                         struct Game
@@ -107,18 +23,18 @@ impl From<&History> for Value {
 #[derive(Debug)]
 pub struct Game {
     engine: Engine,
-    history: History,
+    record: Record,
     tx: UnboundedSender<Command>,
 }
 
 // ================================ pub impl
 
 impl Game {
-    /// Creates a new game with the default position.
+    /// Spawns a new game with the default position.
     /// Returns a channel used to pass messages to the game state.
     /// Takes a channel in argument, used by the game state to respond
     /// to incoming messages.
-    pub fn new(tx: UnboundedSender<Result<Response>>) -> UnboundedSender<Command> {
+    pub fn spawn(tx: UnboundedSender<Result<Response>>) -> UnboundedSender<Command> {
         // Creates the communication channels used to send messages to the game state.
         let (game_tx, mut game_rx) = mpsc::unbounded_channel();
         let self_tx = game_tx.clone();
@@ -128,7 +44,7 @@ impl Game {
             // The game state itself.
             let mut game = Self {
                 engine: Engine::new(Board::new(DEFAULT_FEN).unwrap()),
-                history: History::new(),
+                record: Record::new(Board::new(DEFAULT_FEN).unwrap()),
                 tx: self_tx,
             };
 
@@ -156,14 +72,14 @@ impl Game {
                 });
             },
             // Request to play a move.
-            Command::Play(s) => {
+            Command::Play {mv} => {
                 // Parses and performs the move.
-                let mv = self.engine.read_board().parse_move(s.as_str()).map_err(|_| Error::msg("Unable to parse move."))?;
-                self.engine.write_board().do_move(mv);
-                self.history.push(mv);
+                let mv = self.record.get_board().parse_move(mv.as_str()).map_err(|_| Error::msg("Unable to parse move."))?;
+                self.record.play(mv)?;
+                self.sync_engine();
             },
             // Request to start the engine for a given amount of seconds.
-            Command::Think(seconds) => {
+            Command::Think {seconds} => {
                 // Starts the engine.
                 if self.engine.is_thinking() {
                     return Err(Error::msg("Engine is already thinking."));
@@ -177,7 +93,7 @@ impl Game {
                     tx.send(Command::Stop).ok();
                 });
             },
-            Command::ThinkDo(seconds) => {
+            Command::ThinkDo {seconds} => {
                 // Starts the engine.
                 if self.engine.is_thinking() {
                     return Err(Error::msg("Engine is already thinking."));
@@ -198,22 +114,34 @@ impl Game {
                 }
                 self.engine.stop();
             },
+            // Request to search that number of lines, the best one and its alternatives.
+            Command::MultiPv {lines} => {
+                self.engine.set_multi_pv(lines);
+            },
             // Request to perform the engine's preferred move.
             Command::Do => {
                 self.engine.stop();
                 let mv = self.engine.get_best_move().ok_or(Error::msg("Engine has no preferred move."))?;
-                self.engine.write_board().do_move(mv);
-                self.history.push(mv);
+                self.record.play(mv)?;
+                self.sync_engine();
             },
             // Request to undo move.
             Command::Undo => {
-                let mv = self.history.undo()?;
-                self.engine.write_board().undo_move(mv);
+                self.record.undo()?;
+                self.sync_engine();
             },
             // Request to redo the last undoed move.
             Command::Redo => {
-                let mv = self.history.redo()?;
-                self.engine.write_board().do_move(mv);
+                self.record.redo()?;
+                self.sync_engine();
+            },
+            // Request for the breakdown of the evaluation of the position, sent to the requester only.
+            Command::Eval {dest} => {
+                let trace = self.engine.eval_trace(self.record.get_board());
+                return Ok(Response::Send {
+                    dest,
+                    msg: EvalState {eval: &trace}.to_msg(),
+                });
             },
         }
 
@@ -222,16 +150,27 @@ impl Game {
     }
 }
 
+// ================================ impl
+
 impl Game {
+    /// Sets the engine's board to the current position of the game record.
+    fn sync_engine(&self) {
+        *self.engine.write_board() = self.record.get_board().clone();
+    }
+
     /// Gets the warp message to send to a client to completely describe the current state of the game.
     fn get_msg(&self) -> Message {
-        Message::text(serde_json::json!({
-            "fen": self.engine.read_board().to_string(),
-            "history": Value::from(&self.history),
-            "end": !matches!(self.engine.read_board().status(), Status::Playing),
-            "thinking": self.engine.is_thinking(),
-            "engineMove": self.engine.get_best_move().map_or(Value::Null, |mv| Value::from(mv.to_string())),
-            "engineDepth": self.engine.get_current_depth(),
-        }).to_string())
+        let board = self.record.get_board();
+        let lines = self.engine.get_lines();
+
+        GameState {
+            fen: board,
+            history: self.record.played(),
+            end: !board.status().is_playing(),
+            thinking: self.engine.is_thinking(),
+            engine_move: self.engine.get_best_move(),
+            engine_depth: self.engine.get_current_depth(),
+            engine_lines: &lines,
+        }.to_msg()
     }
 }
\ No newline at end of file
@@ -39,9 +39,6 @@ async fn main() {
         },
     };
 
-    // Initializes the chess library.
-    chess::init();
-
     // Creates our state object and converts it into a warp filter.
     let sockets = {
         let sockets = Sockets::new();
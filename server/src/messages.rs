@@ -1,7 +1,10 @@
 use anyhow::{Error, Result};
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
 use warp::ws::Message;
 
+use chess::prelude::*;
+use engine::{EvalTrace, Line};
+
 //#################################################################################################
 //
 //                                         enum Command
@@ -9,53 +12,48 @@ use warp::ws::Message;
 //#################################################################################################
 
 /// A struct representing a parsed message from a client.
-#[derive(Debug)]
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
 pub enum Command {
+    #[serde(skip)]
     Welcome(usize),
-    Play(String),
-    Think(f64),
-    ThinkDo(f64),
+    Play {
+        #[serde(rename = "move")]
+        mv: String,
+    },
+    Think {
+        seconds: f64,
+    },
+    ThinkDo {
+        seconds: f64,
+    },
     Stop,
+    MultiPv {
+        lines: usize,
+    },
     Do,
     Undo,
     Redo,
+    Eval {
+        #[serde(skip)]
+        dest: usize,
+    },
 }
 
 // ================================ pub impl
 
 impl Command {
-    /// Tries to parse a command from a warp message.
-    pub fn from_msg(msg: Message) -> Result<Self> {
+    /// Tries to parse a command from a warp message, sent by the client of that id.
+    pub fn from_msg(uid: usize, msg: Message) -> Result<Self> {
         let data = msg.to_str().map_err(|_| Error::msg("Incoming message is not text."))?;
-        let json: Value = serde_json::from_str(data)?;
-
-        let obj = json.as_object().ok_or(Error::msg("Json value is not an object."))?;
-
-        let kind = obj.get("kind").ok_or(Error::msg("No attribute kind in json value."))?
-            .as_str().ok_or(Error::msg("kind attribute is not a string."))?;
-
-        Ok(match kind {
-            "play" => {
-                let mv = obj.get("move").ok_or(Error::msg("No attribute move in json value."))?
-                    .as_str().ok_or(Error::msg("move attribute is not a string."))?.to_string();
-                Self::Play(mv)
-            },
-            "think" => {
-                let seconds = obj.get("seconds").ok_or(Error::msg("No attribute move in json value."))?
-                    .as_f64().ok_or(Error::msg("seconds attribute is not a string."))?;
-                Self::Think(seconds)
-            },
-            "thinkdo" => {
-                let seconds = obj.get("seconds").ok_or(Error::msg("No attribute move in json value."))?
-                    .as_f64().ok_or(Error::msg("seconds attribute is not a string."))?;
-                Self::ThinkDo(seconds)
-            },
-            "stop" => Self::Stop,
-            "do" => Self::Do,
-            "undo" => Self::Undo,
-            "redo" => Self::Redo,
-            _ => return Err(Error::msg("Invalid message kind")),
-        })
+        let mut command = serde_json::from_str(data)?;
+
+        // Requests answered to their sender only.
+        if let Command::Eval {dest} = &mut command {
+            *dest = uid;
+        }
+
+        Ok(command)
     }
 }
 
@@ -73,4 +71,53 @@ pub enum Response {
         dest: usize,
         msg: Message,
     }
-}
\ No newline at end of file
+}
+
+//#################################################################################################
+//
+//                                       struct GameState
+//
+//#################################################################################################
+
+/// The message sent to clients to completely describe the current state of the game.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameState<'a> {
+    pub fen: &'a Board,
+    pub history: &'a [Move],
+    pub end: bool,
+    pub thinking: bool,
+    pub engine_move: Option<Move>,
+    pub engine_depth: u8,
+    pub engine_lines: &'a [Line],
+}
+
+// ================================ pub impl
+
+impl GameState<'_> {
+    /// Converts the state into a warp message, in json.
+    pub fn to_msg(&self) -> Message {
+        Message::text(serde_json::to_string(self).unwrap())
+    }
+}
+
+//#################################################################################################
+//
+//                                       struct EvalState
+//
+//#################################################################################################
+
+/// The message sent to a client requesting the breakdown of the evaluation of the position.
+#[derive(Debug, Serialize)]
+pub struct EvalState<'a> {
+    pub eval: &'a EvalTrace,
+}
+
+// ================================ pub impl
+
+impl EvalState<'_> {
+    /// Converts the breakdown into a warp message, in json.
+    pub fn to_msg(&self) -> Message {
+        Message::text(serde_json::to_string(self).unwrap())
+    }
+}
@@ -36,7 +36,7 @@ impl Sockets {
     pub fn new() -> Arc<Self> {
         // Create channels to communicate with the game state.
         let (tx, mut game_rx) = mpsc::unbounded_channel();
-        let game_tx = Game::new(tx);
+        let game_tx = Game::spawn(tx);
 
         // Construct the state object.
         let state = Arc::new(Self {
@@ -104,7 +104,7 @@ impl Sockets {
 
                     // If the message was incorrect, send all the state
                     // to the sender, so they can sync back with us.
-                    if let Err(e) = self.on_message(msg) {
+                    if let Err(e) = self.on_message(uid, msg) {
                         eprintln!("Erroneous order: {}", e);
                     }
                 },
@@ -139,8 +139,8 @@ impl Sockets {
     }
 
     /// Upon receiving a message from a client, parses it and forwards to the game state.
-    fn on_message(&self, msg: Message) -> Result<()> {
-        let command = Command::from_msg(msg)?;
+    fn on_message(&self, uid: usize, msg: Message) -> Result<()> {
+        let command = Command::from_msg(uid, msg)?;
         self.game_tx.send(command)?;
         Ok(())
     }
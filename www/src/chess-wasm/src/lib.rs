@@ -42,9 +42,6 @@ impl WasmChess {
     /// Constructs a new WasmChess object, from it's fen representation.
     #[wasm_bindgen(constructor)]
     pub fn new() -> WasmChess {
-        // Initialize the chess lib, if not done already.
-        chess::init();
-
         let board = Board::new(DEFAULT_FEN).unwrap();
         let mut legals = Vec::new();
         movegen::legals(&board, &mut legals);
@@ -53,7 +50,7 @@ impl WasmChess {
     }
 
     /// A setter for the current position, given by a fen string.
-    #[wasm_bindgen(method, js_name = setPosition)]
+    #[wasm_bindgen(js_name = setPosition)]
     pub fn set_position(&mut self, fen: &str, end: bool) -> Result<(), JsValue> {
         self.board = Board::new(fen).map_err(|_| js_error("Invalid fen literal."))?;
 
@@ -66,7 +63,7 @@ impl WasmChess {
     }
 
     /// Returns true if the given move is legal.
-    #[wasm_bindgen(method, js_name = isLegal)]
+    #[wasm_bindgen(js_name = isLegal)]
     pub fn is_legal(&self, from: String, to: String) -> Result<bool, JsValue> {
         let from = parse_square(&from)?;
         let to = parse_square(&to)?;
@@ -75,7 +72,7 @@ impl WasmChess {
     }
 
     /// Returns true if the given move is a promotion. 
-    #[wasm_bindgen(method, js_name = isPromotion)]
+    #[wasm_bindgen(js_name = isPromotion)]
     pub fn is_promotion(&self, from: String, to: String) -> Result<bool, JsValue> {
         let from = parse_square(&from)?;
         let to = parse_square(&to)?;
@@ -88,13 +85,13 @@ impl WasmChess {
     }
 
     /// Returns true if the king is in check in this position.
-    #[wasm_bindgen(method, js_name = isInCheck)]
+    #[wasm_bindgen(js_name = isInCheck)]
     pub fn is_in_check(&self) -> bool {
         self.board.get_checkers().not_empty()
     }
 
     /// Returns true if the side to move is white.
-    #[wasm_bindgen(method, js_name = isWhiteToMove)]
+    #[wasm_bindgen(js_name = isWhiteToMove)]
     pub fn is_white_to_move(&self) -> bool {
         self.board.get_side_to_move() == Color::White
     }
@@ -102,8 +99,15 @@ impl WasmChess {
     // Compile only when in debug mode to save up some bytes.
     /// Prints self, using rust debug's format.
     #[cfg(debug_assertions)]
-    #[wasm_bindgen(method, js_name = toString)]
-    pub fn to_string(&self) -> String {
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_debug_string(&self) -> String {
         format!("{:?}", self)
     }
-}
\ No newline at end of file
+}
+
+impl Default for WasmChess {
+    /// The default position.
+    fn default() -> WasmChess {
+        WasmChess::new()
+    }
+}